@@ -4,7 +4,9 @@ use cosmwasm_std::{
 use cosmwasm_std::{wasm_execute, Reply, StdError};
 use cw2::set_contract_version;
 
-use amm::pool_manager::{ExecuteMsg, FeatureToggle, InstantiateMsg, MigrateMsg, QueryMsg};
+use amm::pool_manager::{
+    ExecuteMsg, FeatureToggle, FeeTierThresholds, InstantiateMsg, MigrateMsg, QueryMsg,
+};
 use mantra_utils::validate_contract;
 
 use crate::error::ContractError;
@@ -37,6 +39,8 @@ pub fn instantiate(
             deposits_enabled: true,
             swaps_enabled: true,
         },
+        fee_tier_thresholds: FeeTierThresholds::default(),
+        quote_denom_priority: vec![],
     };
     CONFIG.save(deps.storage, &config)?;
     // initialize pool counter
@@ -137,6 +141,7 @@ pub fn execute(
             pool_identifier,
         } => swap::commands::swap(
             deps,
+            env,
             info.clone(),
             info.sender,
             ask_asset_denom,
@@ -158,6 +163,7 @@ pub fn execute(
             max_spread,
         } => router::commands::execute_swap_operations(
             deps,
+            env,
             info,
             operations,
             minimum_receive,
@@ -169,6 +175,8 @@ pub fn execute(
             farm_manager_addr,
             pool_creation_fee,
             feature_toggle,
+            fee_tier_thresholds,
+            quote_denom_priority,
         } => manager::update_config(
             deps,
             info,
@@ -176,12 +184,19 @@ pub fn execute(
             farm_manager_addr,
             pool_creation_fee,
             feature_toggle,
+            fee_tier_thresholds,
+            quote_denom_priority,
         ),
+        ExecuteMsg::RampAmp {
+            pool_identifier,
+            target_amp,
+            ramp_end_time,
+        } => manager::ramp_amp(deps, env, info, pool_identifier, target_amp, ramp_end_time),
     }
 }
 
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
     match msg {
         QueryMsg::Config {} => Ok(to_json_binary(&queries::query_config(deps)?)?),
         QueryMsg::AssetDecimals {
@@ -198,6 +213,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             pool_identifier,
         } => Ok(to_json_binary(&queries::query_simulation(
             deps,
+            env,
             offer_asset,
             ask_asset_denom,
             pool_identifier,
@@ -208,6 +224,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             pool_identifier,
         } => Ok(to_json_binary(&queries::query_reverse_simulation(
             deps,
+            env,
             ask_asset,
             offer_asset_denom,
             pool_identifier,
@@ -217,6 +234,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             operations,
         } => Ok(to_json_binary(&queries::simulate_swap_operations(
             deps,
+            env,
             offer_amount,
             operations,
         )?)?),
@@ -224,7 +242,7 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             ask_amount,
             operations,
         } => Ok(to_json_binary(&queries::reverse_simulate_swap_operations(
-            deps, ask_amount, operations,
+            deps, env, ask_amount, operations,
         )?)?),
         QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
         QueryMsg::Pools {
@@ -237,6 +255,937 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractErr
             start_after,
             limit,
         )?)?),
+        QueryMsg::RebalanceSwap {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            target_price,
+        } => Ok(to_json_binary(&queries::query_rebalance_swap(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            target_price,
+        )?)?),
+        QueryMsg::CanonicalSimulation {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_canonical_simulation(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::LpValue {
+            pool_identifier,
+            lp_amount,
+        } => Ok(to_json_binary(&queries::query_lp_value(
+            deps,
+            pool_identifier,
+            lp_amount,
+        )?)?),
+        QueryMsg::SimulationSanity {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            max_reasonable_price,
+        } => Ok(to_json_binary(&queries::query_simulation_sanity(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            max_reasonable_price,
+        )?)?),
+        QueryMsg::PoolTypeStats {} => Ok(to_json_binary(&queries::query_pool_type_stats(deps)?)?),
+        QueryMsg::SimulateZapIn {
+            pool_identifier,
+            offer_asset,
+        } => Ok(to_json_binary(&queries::query_simulate_zap_in(
+            deps,
+            env,
+            pool_identifier,
+            offer_asset,
+        )?)?),
+        QueryMsg::SimulateZapOut {
+            pool_identifier,
+            lp_amount,
+            target_denom,
+        } => Ok(to_json_binary(&queries::query_simulate_zap_out(
+            deps,
+            env,
+            pool_identifier,
+            lp_amount,
+            target_denom,
+        )?)?),
+        QueryMsg::AmpRampStatus { pool_identifier } => Ok(to_json_binary(
+            &queries::query_amp_ramp_status(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::PoolIntegrity { pool_identifier } => Ok(to_json_binary(
+            &queries::query_pool_integrity(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::SimulationConservative {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_simulation_conservative(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::CheaperDirection { pool_identifier } => Ok(to_json_binary(
+            &queries::query_cheaper_direction(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::FeeRecipients {} => Ok(to_json_binary(&queries::query_fee_recipients(deps)?)?),
+        QueryMsg::AmpChangeLpImpact {
+            pool_identifier,
+            new_amp,
+        } => Ok(to_json_binary(&queries::query_amp_change_lp_impact(
+            deps,
+            env,
+            pool_identifier,
+            new_amp,
+        )?)?),
+        QueryMsg::EncodeRoute { operations } => Ok(to_json_binary(&queries::query_encode_route(
+            deps, operations,
+        )?)?),
+        QueryMsg::DecodeRoute { encoded } => Ok(to_json_binary(&queries::query_decode_route(
+            deps, encoded,
+        )?)?),
+        QueryMsg::FeeSplit {
+            pool_identifier,
+            offer_amount,
+        } => Ok(to_json_binary(&queries::query_fee_split(
+            deps,
+            pool_identifier,
+            offer_amount,
+        )?)?),
+        QueryMsg::CapacityHeadroom { pool_identifier } => Ok(to_json_binary(
+            &queries::query_capacity_headroom(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::SimulationWithReservesOut {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(
+            &queries::query_simulation_with_reserves_out(
+                deps,
+                env,
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+            )?,
+        )?),
+        QueryMsg::MarginalOutputCurve {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            steps,
+            max_offer,
+        } => Ok(to_json_binary(&queries::query_marginal_output_curve(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            steps,
+            max_offer,
+        )?)?),
+        QueryMsg::ValidatePoolSpec {
+            asset_denoms,
+            asset_decimals,
+            pool_type,
+            pool_fees,
+        } => Ok(to_json_binary(&queries::query_validate_pool_spec(
+            asset_denoms,
+            asset_decimals,
+            pool_type,
+            pool_fees,
+        ))?),
+        QueryMsg::CanonicalAssetOrder { pool_identifier } => Ok(to_json_binary(
+            &queries::query_canonical_asset_order(deps, pool_identifier)?,
+        )?),
+        QueryMsg::SimulationMinAmp {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_simulation_min_amp(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::OfferToReachPrice {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            target_price,
+        } => Ok(to_json_binary(&queries::query_offer_to_reach_price(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            target_price,
+        )?)?),
+        QueryMsg::PricingPath {
+            denom,
+            base_denom,
+            max_hops,
+        } => Ok(to_json_binary(&queries::query_pricing_path(
+            deps, denom, base_denom, max_hops,
+        )?)?),
+        QueryMsg::BreakevenSpread {
+            pool_identifier,
+            offer_asset,
+            ask_denom,
+        } => Ok(to_json_binary(&queries::query_breakeven_spread(
+            deps,
+            env,
+            pool_identifier,
+            offer_asset,
+            ask_denom,
+        )?)?),
+        QueryMsg::SwapWouldSucceed {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            min_receive,
+            max_spread,
+        } => Ok(to_json_binary(&queries::query_swap_would_succeed(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            min_receive,
+            max_spread,
+        ))?),
+        QueryMsg::PegDeviation {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_peg_deviation(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::SimulationManyToOne {
+            offer_assets,
+            ask_denom,
+            pool_routes,
+        } => Ok(to_json_binary(&queries::query_simulation_many_to_one(
+            deps,
+            env,
+            offer_assets,
+            ask_denom,
+            pool_routes,
+        )?)?),
+        QueryMsg::SwapFeeInOffer {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_swap_fee_in_offer(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::PostFeeWithdrawalReserves { pool_identifier } => Ok(to_json_binary(
+            &queries::query_post_fee_withdrawal_reserves(deps, pool_identifier)?,
+        )?),
+        QueryMsg::SafeTradeSize {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            max_impact_bps,
+        } => Ok(to_json_binary(&queries::query_safe_trade_size(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            max_impact_bps,
+        )?)?),
+        QueryMsg::AdjacentPools {
+            pool_identifier,
+            limit,
+        } => Ok(to_json_binary(&queries::query_adjacent_pools(
+            deps,
+            pool_identifier,
+            limit,
+        )?)?),
+        QueryMsg::PriceMatrix {
+            pool_identifier,
+            probe_amount,
+        } => Ok(to_json_binary(&queries::query_price_matrix(
+            deps,
+            env,
+            pool_identifier,
+            probe_amount,
+        )?)?),
+        QueryMsg::ConstantProductEquivalentAmp { pool_identifier } => Ok(to_json_binary(
+            &queries::query_constant_product_equivalent_amp(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::SandwichRisk {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            attacker_capital,
+        } => Ok(to_json_binary(&queries::query_sandwich_risk(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            attacker_capital,
+        )?)?),
+        QueryMsg::RampingPools { limit } => Ok(to_json_binary(&queries::query_ramping_pools(
+            deps, env, limit,
+        )?)?),
+        QueryMsg::LiquidityConcentration { pool_identifier } => Ok(to_json_binary(
+            &queries::query_liquidity_concentration(deps, pool_identifier)?,
+        )?),
+        QueryMsg::SimulationWithPending {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            pending_deposits,
+            pending_withdrawals,
+        } => Ok(to_json_binary(&queries::query_simulation_with_pending(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            pending_deposits,
+            pending_withdrawals,
+        )?)?),
+        QueryMsg::MaxPossibleOutput {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+        } => Ok(to_json_binary(&queries::query_max_possible_output(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+        )?)?),
+        QueryMsg::DirectVsRoute {
+            offer_asset,
+            ask_asset_denom,
+            direct_pool,
+            route,
+        } => Ok(to_json_binary(&queries::query_direct_vs_route(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            direct_pool,
+            route,
+        )?)?),
+        QueryMsg::SimulationVsReference {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            reference_price,
+        } => Ok(to_json_binary(&queries::query_simulation_vs_reference(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            reference_price,
+        )?)?),
+        QueryMsg::SimulateUntilPrice {
+            pool_identifier,
+            offer_asset_denom,
+            ask_asset_denom,
+            total_offer,
+            stop_price,
+        } => Ok(to_json_binary(&queries::query_simulate_until_price(
+            deps,
+            env,
+            pool_identifier,
+            offer_asset_denom,
+            ask_asset_denom,
+            total_offer,
+            stop_price,
+        )?)?),
+        QueryMsg::ImbalanceFeeSchedule {
+            pool_identifier,
+            sample_points,
+        } => Ok(to_json_binary(&queries::query_imbalance_fee_schedule(
+            deps,
+            env,
+            pool_identifier,
+            sample_points,
+        )?)?),
+        QueryMsg::LpToWithdraw {
+            pool_identifier,
+            desired,
+        } => Ok(to_json_binary(&queries::query_lp_to_withdraw(
+            deps,
+            env,
+            pool_identifier,
+            desired,
+        )?)?),
+        QueryMsg::RebalanceToEqual {
+            pool_identifier,
+            balance_a,
+            balance_b,
+        } => Ok(to_json_binary(&queries::query_rebalance_to_equal(
+            deps,
+            env,
+            pool_identifier,
+            balance_a,
+            balance_b,
+        )?)?),
+        QueryMsg::RevenueOverDistribution {
+            pool_identifier,
+            trade_sizes,
+        } => Ok(to_json_binary(&queries::query_revenue_over_distribution(
+            deps,
+            env,
+            pool_identifier,
+            trade_sizes,
+        )?)?),
+        QueryMsg::BandWidthForFees {
+            pool_identifier,
+            new_total_fee,
+        } => Ok(to_json_binary(&queries::query_band_width_for_fees(
+            deps,
+            env,
+            pool_identifier,
+            new_total_fee,
+        )?)?),
+        QueryMsg::MidPriceRational {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_mid_price_rational(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::SwapAprContribution {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_swap_apr_contribution(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::ReservesForInvariant {
+            pool_identifier,
+            target_d,
+        } => Ok(to_json_binary(&queries::query_reserves_for_invariant(
+            deps,
+            pool_identifier,
+            target_d,
+        )?)?),
+        QueryMsg::Reachable {
+            offer_denom,
+            ask_denom,
+            max_hops,
+        } => Ok(to_json_binary(&queries::query_reachable(
+            deps,
+            offer_denom,
+            ask_denom,
+            max_hops,
+        )?)?),
+        QueryMsg::PriceSensitivity {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_price_sensitivity(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::FeeTier { pool_identifier } => Ok(to_json_binary(&queries::query_fee_tier(
+            deps,
+            pool_identifier,
+        )?)?),
+        QueryMsg::BalancedSlippage {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            amount,
+        } => Ok(to_json_binary(&queries::query_balanced_slippage(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            amount,
+        )?)?),
+        QueryMsg::MinAmpForPositiveSpread { pool_identifier } => Ok(to_json_binary(
+            &queries::query_min_amp_for_positive_spread(deps, pool_identifier)?,
+        )?),
+        QueryMsg::SimulationWithRebate {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            rebate_bps,
+        } => Ok(to_json_binary(&queries::query_simulation_with_rebate(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            rebate_bps,
+        )?)?),
+        QueryMsg::PriceCurveCoefficients {
+            pool_identifier,
+            offer_asset_denom,
+            ask_asset_denom,
+            around,
+        } => Ok(to_json_binary(&queries::query_price_curve_coefficients(
+            deps,
+            env,
+            pool_identifier,
+            offer_asset_denom,
+            ask_asset_denom,
+            around,
+        )?)?),
+        QueryMsg::PoolsAboveFee {
+            min_total_fee,
+            limit,
+        } => Ok(to_json_binary(&queries::query_pools_above_fee(
+            deps,
+            min_total_fee,
+            limit,
+        )?)?),
+        QueryMsg::SimulationAmpRange {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_simulation_amp_range(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::ReserveSharesOfD { pool_identifier } => Ok(to_json_binary(
+            &queries::query_reserve_shares_of_d(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::LpPrice {
+            pool_identifier,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_lp_price(
+            deps,
+            env,
+            pool_identifier,
+            quote_denom,
+        )?)?),
+        QueryMsg::InvariantHolds {
+            pool_identifier,
+            tolerance_bps,
+        } => Ok(to_json_binary(&queries::query_invariant_holds(
+            deps,
+            env,
+            pool_identifier,
+            tolerance_bps,
+        )?)?),
+        QueryMsg::SimulationDynamicFee {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            base_fee,
+            volatility_multiplier,
+        } => Ok(to_json_binary(&queries::query_simulation_dynamic_fee(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            base_fee,
+            volatility_multiplier,
+        )?)?),
+        QueryMsg::StandardSlippage {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            standard_notional,
+        } => Ok(to_json_binary(&queries::query_standard_slippage(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            standard_notional,
+        )?)?),
+        QueryMsg::CheapestForOutput {
+            ask_asset,
+            offer_denom,
+            candidate_pools,
+        } => Ok(to_json_binary(&queries::query_cheapest_for_output(
+            deps,
+            env,
+            ask_asset,
+            offer_denom,
+            candidate_pools,
+        )?)?),
+        QueryMsg::EffectiveBidAsk {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            size,
+        } => Ok(to_json_binary(&queries::query_effective_bid_ask(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            size,
+        )?)?),
+        QueryMsg::OptimalTradeForUtility {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            slippage_penalty,
+            max_offer,
+        } => Ok(to_json_binary(&queries::query_optimal_trade_for_utility(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            slippage_penalty,
+            max_offer,
+        )?)?),
+        QueryMsg::ReservesAfterRoute {
+            offer_amount,
+            operations,
+        } => Ok(to_json_binary(&queries::query_reserves_after_route(
+            deps,
+            env,
+            offer_amount,
+            operations,
+        )?)?),
+        QueryMsg::BreakevenFee {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            expected_volatility,
+        } => Ok(to_json_binary(&queries::query_breakeven_fee(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            expected_volatility,
+        )?)?),
+        QueryMsg::RoutingGraph { limit } => {
+            Ok(to_json_binary(&queries::query_routing_graph(deps, limit)?)?)
+        }
+        QueryMsg::SimulationExternalFee {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            fee_denom,
+            fee_price,
+        } => Ok(to_json_binary(&queries::query_simulation_external_fee(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+            fee_denom,
+            fee_price,
+        )?)?),
+        QueryMsg::OracleArbToPrice {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            oracle_price,
+        } => Ok(to_json_binary(&queries::query_oracle_arb_to_price(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            oracle_price,
+        )?)?),
+        QueryMsg::FeePercentages {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_fee_percentages(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::CanonicalPools { quote_denom, limit } => Ok(to_json_binary(
+            &queries::query_canonical_pools(deps, quote_denom, limit)?,
+        )?),
+        QueryMsg::WhaleExitImpact {
+            pool_identifier,
+            lp_amount,
+            single_denom,
+        } => Ok(to_json_binary(&queries::query_whale_exit_impact(
+            deps,
+            env,
+            pool_identifier,
+            lp_amount,
+            single_denom,
+        )?)?),
+        QueryMsg::RebalanceToBalanced { pool_identifier } => Ok(to_json_binary(
+            &queries::query_rebalance_to_balanced(deps, env, pool_identifier)?,
+        )?),
+        QueryMsg::FeeCaptureRatio { pool_identifier } => Ok(to_json_binary(
+            &queries::query_fee_capture_ratio(deps, pool_identifier)?,
+        )?),
+        QueryMsg::SharePriceHwm {
+            pool_identifier,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_share_price_hwm(
+            deps,
+            env,
+            pool_identifier,
+            quote_denom,
+        )?)?),
+        QueryMsg::GlobalStats {} => Ok(to_json_binary(&queries::query_global_stats(deps, env)?)?),
+        QueryMsg::ReverseSimulationPartial {
+            ask_asset,
+            offer_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_reverse_simulation_partial(
+            deps,
+            env,
+            ask_asset,
+            offer_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::PriceConsistency {
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_price_consistency(
+            deps,
+            env,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::DepositRebalance {
+            pool_identifier,
+            assets,
+        } => Ok(to_json_binary(&queries::query_deposit_rebalance(
+            deps,
+            env,
+            pool_identifier,
+            assets,
+        )?)?),
+        QueryMsg::SimulationSafe {
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_simulation_safe(
+            deps,
+            env,
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::MinHops {
+            offer_denom,
+            ask_denom,
+            max_search,
+        } => Ok(to_json_binary(&queries::query_min_hops(
+            deps,
+            offer_denom,
+            ask_denom,
+            max_search,
+        )?)?),
+        QueryMsg::LpForTargetValue {
+            pool_identifier,
+            target_value,
+            value_denom,
+        } => Ok(to_json_binary(&queries::query_lp_for_target_value(
+            deps,
+            env,
+            pool_identifier,
+            target_value,
+            value_denom,
+        )?)?),
+        QueryMsg::SimulationBench {
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+            max_iterations,
+        } => Ok(to_json_binary(&queries::query_simulation_bench(
+            deps,
+            env,
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+            max_iterations,
+        )?)?),
+        QueryMsg::HistoricalReserves {
+            pool_identifier,
+            at_height,
+        } => Ok(to_json_binary(&queries::query_historical_reserves(
+            deps,
+            pool_identifier,
+            at_height,
+        )?)?),
+        QueryMsg::BlendedEntryPrice {
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            existing_amount,
+            existing_price,
+            new_offer,
+        } => Ok(to_json_binary(&queries::query_blended_entry_price(
+            deps,
+            env,
+            pool_identifier,
+            offer_denom,
+            ask_denom,
+            existing_amount,
+            existing_price,
+            new_offer,
+        )?)?),
+        QueryMsg::PriceSummary {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            size,
+        } => Ok(to_json_binary(&queries::query_price_summary(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            size,
+        )?)?),
+        QueryMsg::AssetRoles { pool_identifier } => Ok(to_json_binary(
+            &queries::query_asset_roles(deps, pool_identifier)?,
+        )?),
+        QueryMsg::FractionalSimulation {
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+            fractions,
+        } => Ok(to_json_binary(&queries::query_fractional_simulation(
+            deps,
+            env,
+            offer_asset,
+            ask_denom,
+            pool_identifier,
+            fractions,
+        )?)?),
+        QueryMsg::EqualFeeSplit {
+            pool_a,
+            pool_b,
+            offer_denom,
+            ask_denom,
+            total_offer,
+        } => Ok(to_json_binary(&queries::query_equal_fee_split(
+            deps,
+            env,
+            pool_a,
+            pool_b,
+            offer_denom,
+            ask_denom,
+            total_offer,
+        )?)?),
+        QueryMsg::ReserveRatio {
+            pool_identifier,
+            numerator_denom,
+            denominator_denom,
+        } => Ok(to_json_binary(&queries::query_reserve_ratio(
+            deps,
+            pool_identifier,
+            numerator_denom,
+            denominator_denom,
+        )?)?),
+        QueryMsg::SimulationRange {
+            offer_denom,
+            ask_denom,
+            pool_identifier,
+            min_amount,
+            max_amount,
+            steps,
+        } => Ok(to_json_binary(&queries::query_simulation_range(
+            deps,
+            env,
+            offer_denom,
+            ask_denom,
+            pool_identifier,
+            min_amount,
+            max_amount,
+            steps,
+        )?)?),
+        QueryMsg::BestPoolForPair {
+            offer_denom,
+            ask_denom,
+            standard_offer,
+        } => Ok(to_json_binary(&queries::query_best_pool_for_pair(
+            deps,
+            env,
+            offer_denom,
+            ask_denom,
+            standard_offer,
+        )?)?),
+        QueryMsg::SlippageCoefficient {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        } => Ok(to_json_binary(&queries::query_slippage_coefficient(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+        )?)?),
+        QueryMsg::ConfigMigrationCheck { proposed } => Ok(to_json_binary(
+            &queries::query_config_migration_check(deps, proposed)?,
+        )?),
+        QueryMsg::SimulationWithReinvest {
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        } => Ok(to_json_binary(&queries::query_simulation_with_reinvest(
+            deps,
+            env,
+            offer_asset,
+            ask_asset_denom,
+            pool_identifier,
+        )?)?),
+        QueryMsg::RealizedVolatility {
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            window_blocks,
+        } => Ok(to_json_binary(&queries::query_realized_volatility(
+            deps,
+            env,
+            pool_identifier,
+            base_denom,
+            quote_denom,
+            window_blocks,
+        )?)?),
     }
 }
 
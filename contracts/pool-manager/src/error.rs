@@ -1,8 +1,8 @@
 use crate::manager::commands::MAX_ASSETS_PER_POOL;
 use cosmwasm_std::{
     CheckedFromRatioError, CheckedMultiplyFractionError, CheckedMultiplyRatioError,
-    ConversionOverflowError, DivideByZeroError, Instantiate2AddressError, OverflowError, StdError,
-    Uint128,
+    ConversionOverflowError, Decimal, Decimal256, DivideByZeroError, Instantiate2AddressError,
+    OverflowError, StdError, Uint128, Uint256,
 };
 use cw_migrate_error_derive::cw_migrate_invalid_version_error;
 use cw_ownable::OwnershipError;
@@ -162,6 +162,80 @@ pub enum ContractError {
 
     #[error("The token factory lp denom creation fee was not paid.")]
     TokenFactoryFeeNotPaid,
+
+    #[error("The target price must be greater than zero")]
+    InvalidTargetPrice,
+
+    #[error(
+        "The effective price of the swap {price} exceeds the maximum reasonable price {max_price}"
+    )]
+    PriceSanityViolation {
+        price: Decimal256,
+        max_price: Decimal256,
+    },
+
+    #[error("The pool is not a stableswap pool")]
+    NotStableSwapPool,
+
+    #[error("The amp ramp must target a positive amp at most 10 times away from the current one, and last at least a day")]
+    InvalidAmpRamp,
+
+    #[error("Invalid amp {0}")]
+    InvalidAmp(Uint256),
+
+    #[error("The encoded route is malformed")]
+    InvalidEncodedRoute,
+
+    #[error("Expected one route per offer asset, got {routes} routes for {offer_assets} assets")]
+    RouteCountMismatch { offer_assets: usize, routes: usize },
+
+    #[error("The max price impact must be below 10000 bps, got {0}")]
+    InvalidMaxPriceImpact(u16),
+
+    #[error("The pending withdrawals of {denom} exceed the pool reserves")]
+    PendingWithdrawalExceedsReserves { denom: String },
+
+    #[error("The pool can't return {amount} of {denom} through a withdrawal")]
+    WithdrawalExceedsReserves { denom: String, amount: Uint128 },
+
+    #[error("The stable fee tier threshold can't be above the standard one")]
+    InvalidFeeTierThresholds,
+
+    #[error("The rebate of {0} bps exceeds the swap fee of {1} bps")]
+    InvalidRebate(u16, Uint128),
+
+    #[error("The dynamic swap fee {0} brings the pool's total fee to 100% or more")]
+    InvalidDynamicFee(Decimal),
+
+    #[error("None of the candidate pools can return the requested output")]
+    NoPoolForOutput,
+
+    #[error("The price of the fee denom {fee_denom} must be positive")]
+    InvalidFeePrice { fee_denom: String },
+    #[error("At least two pools with a price for {base_denom} in {quote_denom} are needed, found {pools}")]
+    NotEnoughPoolsForPair {
+        base_denom: String,
+        quote_denom: String,
+        pools: u32,
+    },
+    #[error("No snapshot of the reserves of pool {pool_identifier} has been taken yet")]
+    NoReserveSnapshots { pool_identifier: String },
+    #[error("The fractions to execute the swap in must sum to at most one, got {0}")]
+    InvalidSwapFractions(Decimal256),
+    #[error(
+        "The minimum amount {min_amount} of the range is above its maximum amount {max_amount}"
+    )]
+    InvalidSimulationRange {
+        min_amount: Uint128,
+        max_amount: Uint128,
+    },
+    #[error("No pool can swap {offer_denom} into {ask_denom}")]
+    NoPoolForPair {
+        offer_denom: String,
+        ask_denom: String,
+    },
+    #[error("No quote denom has been configured to value the pools in")]
+    NoQuoteDenom,
 }
 
 impl From<semver::Error> for ContractError {
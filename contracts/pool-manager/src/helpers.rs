@@ -1,17 +1,24 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::ops::Mul;
 
-use amm::coin::{aggregate_coins, FACTORY_MAX_SUBDENOM_SIZE};
+use amm::coin::{add_coins, aggregate_coins, FACTORY_MAX_SUBDENOM_SIZE};
 use amm::constants::LP_SYMBOL;
 use amm::fee::PoolFee;
-use amm::pool_manager::{PoolInfo, PoolType, SimulationResponse};
+use amm::pool_manager::{PoolInfo, PoolType, SimulationResponse, SwapOperation};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    coin, ensure, Addr, Coin, Decimal, Decimal256, Deps, DepsMut, Env, MessageInfo, StdError,
-    StdResult, Uint128, Uint256, Uint512,
+    coin, ensure, Addr, Binary, Coin, Decimal, Decimal256, Deps, DepsMut, Env, Isqrt, MessageInfo,
+    Order, StdError, StdResult, Storage, Uint128, Uint256, Uint512,
 };
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
+use crate::manager::commands::MIN_AMP;
 use crate::math::Decimal256Helper;
+use crate::state::{
+    get_pool_by_identifier, POOLS, RESERVE_SNAPSHOTS, SHARE_PRICE_HIGH_WATER_MARKS,
+};
 
 /// The amount of iterations to perform when calculating the Newton-Raphson approximation.
 const NEWTON_ITERATIONS: u64 = 32;
@@ -27,6 +34,17 @@ pub struct SwapResult {
     pub amount_swapped: Uint128,
 }
 
+/// The result of a Newton-Raphson approximation performed with a cap on its iterations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewtonApproximation<T> {
+    /// The approximated value, i.e. the last one computed if it didn't converge.
+    pub value: T,
+    /// The amount of iterations performed.
+    pub iterations: u64,
+    /// Whether the approximation converged within the iteration cap.
+    pub converged: bool,
+}
+
 fn calculate_stableswap_d(
     n_coins: Uint256,
     offer_pool: Decimal256,
@@ -34,12 +52,42 @@ fn calculate_stableswap_d(
     amp: &u64,
     precision: u8,
 ) -> Result<Decimal256, ContractError> {
+    let approximation = approximate_stableswap_d(
+        n_coins,
+        offer_pool,
+        ask_pool,
+        amp,
+        precision,
+        NEWTON_ITERATIONS,
+    )?;
+
+    // completed iterations
+    // but we never approximated correctly
+    ensure!(approximation.converged, ContractError::ConvergeError);
+
+    Ok(approximation.value)
+}
+
+/// Approximates the stableswap invariant of the pools with the Newton-Raphson method, performing
+/// at most `max_iterations` iterations.
+fn approximate_stableswap_d(
+    n_coins: Uint256,
+    offer_pool: Decimal256,
+    ask_pool: Decimal256,
+    amp: &u64,
+    precision: u8,
+    max_iterations: u64,
+) -> Result<NewtonApproximation<Decimal256>, ContractError> {
     let n_coins_decimal = Decimal256::from_ratio(n_coins, Uint256::one());
 
     let sum_pools = offer_pool.checked_add(ask_pool)?;
     if sum_pools.is_zero() {
         // there was nothing to swap, return `0`.
-        return Ok(Decimal256::zero());
+        return Ok(NewtonApproximation {
+            value: Decimal256::zero(),
+            iterations: 0,
+            converged: true,
+        });
     }
 
     // ann = amp * n_coins
@@ -47,7 +95,7 @@ fn calculate_stableswap_d(
 
     // perform Newton-Raphson method
     let mut current_d = sum_pools;
-    for _ in 0..NEWTON_ITERATIONS {
+    for iteration in 1..=max_iterations {
         // multiply each pool by the number of coins
         // and multiply together
         let new_d = [offer_pool, ask_pool]
@@ -73,23 +121,27 @@ fn calculate_stableswap_d(
                 ))?,
         )?;
 
-        if current_d >= old_d {
-            if current_d.checked_sub(old_d)? <= Decimal256::decimal_with_precision(1u8, precision)?
-            {
-                // success
-                return Ok(current_d);
-            }
-        } else if old_d.checked_sub(current_d)?
-            <= Decimal256::decimal_with_precision(1u8, precision)?
-        {
+        let converged = if current_d >= old_d {
+            current_d.checked_sub(old_d)? <= Decimal256::decimal_with_precision(1u8, precision)?
+        } else {
+            old_d.checked_sub(current_d)? <= Decimal256::decimal_with_precision(1u8, precision)?
+        };
+
+        if converged {
             // success
-            return Ok(current_d);
+            return Ok(NewtonApproximation {
+                value: current_d,
+                iterations: iteration,
+                converged: true,
+            });
         }
     }
 
-    // completed iterations
-    // but we never approximated correctly
-    Err(ContractError::ConvergeError)
+    Ok(NewtonApproximation {
+        value: current_d,
+        iterations: max_iterations,
+        converged: false,
+    })
 }
 
 /// Determines the direction of `offer_pool` -> `ask_pool`.
@@ -112,9 +164,51 @@ pub fn calculate_stableswap_y(
     ask_precision: u8,
     direction: StableSwapDirection,
 ) -> Result<Uint128, ContractError> {
+    let approximation = approximate_stableswap_y(
+        n_coins,
+        offer_pool,
+        ask_pool,
+        offer_amount,
+        amp,
+        ask_precision,
+        direction,
+        NEWTON_ITERATIONS,
+    )?;
+    ensure!(approximation.converged, ContractError::ConvergeError);
+
+    approximation
+        .value
+        .try_into()
+        .map_err(|_| ContractError::SwapOverflowError)
+}
+
+/// Approximates the new pool amount given the current pools and swap size like
+/// [calculate_stableswap_y], performing at most `max_iterations` iterations for each of the
+/// invariant and the pool amount. The iterations of both are reported together, and the
+/// approximation converges only if both do.
+#[allow(clippy::too_many_arguments)]
+pub fn approximate_stableswap_y(
+    n_coins: Uint256,
+    offer_pool: Decimal256,
+    ask_pool: Decimal256,
+    offer_amount: Decimal256,
+    amp: &u64,
+    ask_precision: u8,
+    direction: StableSwapDirection,
+    max_iterations: u64,
+) -> Result<NewtonApproximation<Uint256>, ContractError> {
     let ann = Uint256::from_u128((*amp).into()).checked_mul(n_coins)?;
 
-    let d = calculate_stableswap_d(n_coins, offer_pool, ask_pool, amp, ask_precision)?
+    let d_approximation = approximate_stableswap_d(
+        n_coins,
+        offer_pool,
+        ask_pool,
+        amp,
+        ask_precision,
+        max_iterations,
+    )?;
+    let d = d_approximation
+        .value
         .to_uint256_with_precision(u32::from(ask_precision))?;
 
     let pool_sum = match direction {
@@ -131,7 +225,7 @@ pub fn calculate_stableswap_y(
 
     // attempt to converge solution using Newton-Raphson method
     let mut y = d;
-    for _ in 0..NEWTON_ITERATIONS {
+    for iteration in 1..=max_iterations {
         let previous_y = y;
         // y = (y^2 + c) / (2y + b - d)
         y = y
@@ -139,16 +233,20 @@ pub fn calculate_stableswap_y(
             .checked_add(c)?
             .checked_div(y.checked_add(y)?.checked_add(b)?.checked_sub(d)?)?;
 
-        if y >= previous_y {
-            if y.checked_sub(previous_y)? <= Uint256::one() {
-                return y.try_into().map_err(|_| ContractError::SwapOverflowError);
-            }
-        } else if y < previous_y && previous_y.checked_sub(y)? <= Uint256::one() {
-            return y.try_into().map_err(|_| ContractError::SwapOverflowError);
+        if y.abs_diff(previous_y) <= Uint256::one() {
+            return Ok(NewtonApproximation {
+                value: y,
+                iterations: d_approximation.iterations + iteration,
+                converged: d_approximation.converged,
+            });
         }
     }
 
-    Err(ContractError::ConvergeError)
+    Ok(NewtonApproximation {
+        value: y,
+        iterations: d_approximation.iterations + max_iterations,
+        converged: false,
+    })
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -228,6 +326,85 @@ pub fn compute_swap(
     }
 }
 
+/// Computes a stableswap swap like [compute_swap], with the Newton-Raphson approximations capped
+/// at `max_iterations` iterations each. A swap whose approximation doesn't converge is computed
+/// from the last approximated pool amount anyway, for the caller to inspect.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_stableswap_swap_with_iteration_cap(
+    n_coins: Uint256,
+    offer_pool: Uint128,
+    ask_pool: Uint128,
+    offer_amount: Uint128,
+    pool_fees: PoolFee,
+    amp: &u64,
+    offer_precision: u8,
+    ask_precision: u8,
+    max_iterations: u64,
+) -> Result<NewtonApproximation<SwapComputation>, ContractError> {
+    let offer_pool = Decimal256::decimal_with_precision(offer_pool, offer_precision)?;
+    let ask_pool = Decimal256::decimal_with_precision(ask_pool, ask_precision)?;
+    let offer_amount = Decimal256::decimal_with_precision(offer_amount, offer_precision)?;
+
+    let new_pool = approximate_stableswap_y(
+        n_coins,
+        offer_pool,
+        ask_pool,
+        offer_amount,
+        amp,
+        ask_precision,
+        StableSwapDirection::Simulate,
+        max_iterations,
+    )?;
+
+    // an approximation that didn't converge may land past the ask pool
+    let return_amount = ask_pool
+        .to_uint256_with_precision(u32::from(ask_precision))?
+        .saturating_sub(new_pool.value);
+    let spread_amount = offer_amount
+        .to_uint256_with_precision(u32::from(ask_precision))?
+        .saturating_sub(return_amount);
+
+    let fees_computation = compute_fees(pool_fees, return_amount)?;
+
+    Ok(NewtonApproximation {
+        value: get_swap_computation(return_amount, spread_amount, fees_computation)?,
+        iterations: new_pool.iterations,
+        converged: new_pool.converged,
+    })
+}
+
+/// Computes the amount to offer to a stableswap pool for it to return `ask_amount` before fees,
+/// expressed with the offer asset's precision. All the amounts are normalized by their decimals.
+#[allow(clippy::too_many_arguments)]
+pub fn compute_stableswap_reverse_offer_amount(
+    n_coins: Uint256,
+    offer_pool: Decimal256,
+    ask_pool: Decimal256,
+    ask_amount: Decimal256,
+    amp: &u64,
+    offer_decimal: u8,
+    ask_decimal: u8,
+) -> Result<Uint128, ContractError> {
+    let max_precision = offer_decimal.max(ask_decimal);
+
+    let new_offer_pool_amount = calculate_stableswap_y(
+        n_coins,
+        offer_pool,
+        ask_pool,
+        ask_amount,
+        amp,
+        max_precision,
+        StableSwapDirection::ReverseSimulate,
+    )?;
+
+    let offer_amount = new_offer_pool_amount.checked_sub(Uint128::try_from(
+        offer_pool.to_uint256_with_precision(u32::from(max_precision))?,
+    )?)?;
+
+    // convert into the original offer precision
+    adjust_precision(offer_amount, max_precision, offer_decimal)
+}
+
 /// Computes the pool fees for a given (return) amount
 fn compute_fees(pool_fees: PoolFee, amount: Uint256) -> Result<FeesComputation, ContractError> {
     let swap_fee_amount: Uint256 = pool_fees.swap_fee.compute(amount)?;
@@ -939,6 +1116,906 @@ pub fn swap_to(
     })
 }
 
+/// Computes a swap of `offer_amount` from the asset at `offer_index` into the asset at `ask_index`
+/// against the current reserves of the given pool.
+pub fn compute_swap_for_pool(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+) -> Result<SwapComputation, ContractError> {
+    compute_swap(
+        Uint256::from(pool_info.assets.len() as u128),
+        pool_info.assets[offer_index].amount,
+        pool_info.assets[ask_index].amount,
+        offer_amount,
+        pool_info.pool_fees.clone(),
+        &pool_info.pool_type,
+        pool_info.asset_decimals[offer_index],
+        pool_info.asset_decimals[ask_index],
+    )
+}
+
+/// Applies a swap to the pool's reserves the same way it's done when the swap is executed, i.e.
+/// the offer amount is added to the offer asset while the return amount and the outgoing fees
+/// (protocol and burn fees) are deducted from the ask asset. The swap fee stays in the pool.
+pub fn apply_swap_to_pool(
+    pool_info: &mut PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    swap_computation: &SwapComputation,
+) -> Result<(), ContractError> {
+    // add the offer amount to the pool
+    pool_info.assets[offer_index].amount = pool_info.assets[offer_index]
+        .amount
+        .checked_add(offer_amount)?;
+
+    // Deduct the return amount and fees from the pool
+    let outgoing_fees = aggregate_outgoing_fees(&swap_computation.to_simulation_response())?;
+
+    pool_info.assets[ask_index].amount = pool_info.assets[ask_index]
+        .amount
+        .checked_sub(swap_computation.return_amount)?
+        .checked_sub(outgoing_fees)?;
+
+    Ok(())
+}
+
+/// Converts an amount expressed with `from` decimals into `to` decimals. Scaling down truncates
+/// the digits that don't fit the lower precision, while equal precisions leave the amount as is.
+pub fn adjust_precision(amount: Uint128, from: u8, to: u8) -> Result<Uint128, ContractError> {
+    Ok(match from.cmp(&to) {
+        Ordering::Equal => amount,
+        Ordering::Less => amount.checked_mul(Uint128::new(10u128.pow(u32::from(to - from))))?,
+        Ordering::Greater => amount.checked_div(Uint128::new(10u128.pow(u32::from(from - to))))?,
+    })
+}
+
+/// Computes the marginal, fee-free price of the asset at `offer_index` in terms of the asset at
+/// `ask_index`, i.e. how many units of the ask asset a single unit of the offer asset is worth at
+/// the current reserves. Both assets are normalized by their decimals.
+pub fn compute_spot_price(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+) -> Result<Decimal256, ContractError> {
+    let (numerator, denominator) = compute_spot_price_fraction(pool_info, offer_index, ask_index)?;
+
+    Ok(Decimal256::checked_from_ratio(numerator, denominator)?)
+}
+
+/// Computes the spot price returned by [compute_spot_price] as a fraction, reduced by the greatest
+/// common divisor of its numerator and denominator.
+pub fn compute_spot_price_fraction(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+) -> Result<(Uint256, Uint256), ContractError> {
+    let offer_decimal = pool_info.asset_decimals[offer_index];
+    let ask_decimal = pool_info.asset_decimals[ask_index];
+    let offer_pool =
+        Decimal256::decimal_with_precision(pool_info.assets[offer_index].amount, offer_decimal)?;
+    let ask_pool =
+        Decimal256::decimal_with_precision(pool_info.assets[ask_index].amount, ask_decimal)?;
+
+    ensure!(
+        !offer_pool.is_zero() && !ask_pool.is_zero(),
+        ContractError::PoolHasNoAssets
+    );
+
+    let (numerator, denominator) = match &pool_info.pool_type {
+        PoolType::ConstantProduct => (ask_pool, offer_pool),
+        PoolType::StableSwap { amp } => {
+            let n_coins = Uint256::from(pool_info.assets.len() as u128);
+            let n_coins_decimal = Decimal256::from_ratio(n_coins, Uint256::one());
+
+            let d = calculate_stableswap_d(
+                n_coins,
+                offer_pool,
+                ask_pool,
+                amp,
+                offer_decimal.max(ask_decimal),
+            )?;
+
+            // d_p = D^3 / (n_coins^2 * offer_pool * ask_pool), the same term used when computing D
+            let d_p = [offer_pool, ask_pool]
+                .into_iter()
+                .try_fold::<_, _, Result<_, ContractError>>(d, |acc, pool| {
+                    acc.checked_multiply_ratio(d, pool.checked_mul(n_coins_decimal)?)
+                })?;
+
+            let ann = Decimal256::from_ratio(
+                Uint256::from_u128((*amp).into()).checked_mul(n_coins)?,
+                1u8,
+            );
+
+            // the ratio of the partial derivatives of the invariant with respect to each pool:
+            // price = ask_pool * (ann * offer_pool + d_p) / (offer_pool * (ann * ask_pool + d_p))
+            (
+                ask_pool.checked_mul(ann.checked_mul(offer_pool)?.checked_add(d_p)?)?,
+                offer_pool.checked_mul(ann.checked_mul(ask_pool)?.checked_add(d_p)?)?,
+            )
+        }
+    };
+
+    // both terms share the same decimal scale, so the ratio of their atomics is the price
+    let (numerator, denominator) = (numerator.atomics(), denominator.atomics());
+    let divisor = gcd(numerator, denominator);
+
+    Ok((
+        numerator.checked_div(divisor)?,
+        denominator.checked_div(divisor)?,
+    ))
+}
+
+/// Computes the relative change of the spot price of the asset at `base_index`, in the asset at
+/// `quote_index`, per whole unit of the base asset sold into the pool, i.e. `-p'(x) / p(x)` where
+/// `p = -dy/dx` along the invariant curve. The deeper the pool, the lower the coefficient.
+///
+/// For constant product pools this is `2 / x`. For stableswap pools, it's derived from the first
+/// and second partial derivatives of the invariant. With `g = D^3 / (n_coins^2 * x * y)`,
+/// `p_x = ann * x + g` and `p_y = ann * y + g`, it's `2 * g * (p_x^2 - p_x * p_y + p_y^2) /
+/// (x * p_x * p_y^2)`, which tends to the constant product one as the amplification vanishes.
+pub fn compute_slippage_coefficient(
+    pool_info: &PoolInfo,
+    base_index: usize,
+    quote_index: usize,
+) -> Result<Decimal256, ContractError> {
+    let base_decimal = pool_info.asset_decimals[base_index];
+    let quote_decimal = pool_info.asset_decimals[quote_index];
+    let base_pool =
+        Decimal256::decimal_with_precision(pool_info.assets[base_index].amount, base_decimal)?;
+    let quote_pool =
+        Decimal256::decimal_with_precision(pool_info.assets[quote_index].amount, quote_decimal)?;
+
+    ensure!(
+        !base_pool.is_zero() && !quote_pool.is_zero(),
+        ContractError::PoolHasNoAssets
+    );
+
+    let two = Decimal256::from_ratio(2u8, 1u8);
+
+    match &pool_info.pool_type {
+        PoolType::ConstantProduct => Ok(two.checked_div(base_pool)?),
+        PoolType::StableSwap { amp } => {
+            let n_coins = Uint256::from(pool_info.assets.len() as u128);
+            let n_coins_decimal = Decimal256::from_ratio(n_coins, Uint256::one());
+
+            let d = calculate_stableswap_d(
+                n_coins,
+                base_pool,
+                quote_pool,
+                amp,
+                base_decimal.max(quote_decimal),
+            )?;
+
+            // g = D^3 / (n_coins^2 * base_pool * quote_pool), the same term used when computing D
+            let g = [base_pool, quote_pool]
+                .into_iter()
+                .try_fold::<_, _, Result<_, ContractError>>(d, |acc, pool| {
+                    acc.checked_multiply_ratio(d, pool.checked_mul(n_coins_decimal)?)
+                })?;
+
+            let ann = Decimal256::from_ratio(
+                Uint256::from_u128((*amp).into()).checked_mul(n_coins)?,
+                1u8,
+            );
+
+            // the partial derivatives of the invariant, each scaled by its own pool
+            let p_base = ann.checked_mul(base_pool)?.checked_add(g)?;
+            let p_quote = ann.checked_mul(quote_pool)?.checked_add(g)?;
+
+            // (p_base^2 - p_base * p_quote + p_quote^2) / p_quote^2 = r^2 - r + 1, r = p_base / p_quote
+            let ratio = p_base.checked_div(p_quote)?;
+            let curvature = ratio
+                .checked_mul(ratio)?
+                .checked_add(Decimal256::one())?
+                .checked_sub(ratio)?;
+
+            Ok(two
+                .checked_mul(g.checked_div(base_pool)?)?
+                .checked_mul(curvature)?
+                .checked_div(p_base)?)
+        }
+    }
+}
+
+/// Computes the greatest common divisor of two numbers using the Euclidean algorithm.
+fn gcd(a: Uint256, b: Uint256) -> Uint256 {
+    let (mut a, mut b) = (a, b);
+
+    while !b.is_zero() {
+        (a, b) = (b, a % b);
+    }
+
+    a
+}
+
+/// Computes the total value locked in the pool, expressed in the asset at `quote_index` normalized
+/// by its decimals. Every reserve is valued at its spot price in the quote asset.
+pub fn compute_pool_value(
+    pool_info: &PoolInfo,
+    quote_index: usize,
+) -> Result<Decimal256, ContractError> {
+    (0..pool_info.assets.len()).try_fold(Decimal256::zero(), |acc, index| {
+        let reserve = Decimal256::decimal_with_precision(
+            pool_info.assets[index].amount,
+            pool_info.asset_decimals[index],
+        )?;
+
+        let value = if index == quote_index {
+            reserve
+        } else {
+            reserve.checked_mul(compute_spot_price(pool_info, index, quote_index)?)?
+        };
+
+        Ok(acc.checked_add(value)?)
+    })
+}
+
+/// Computes the value of a unit of the pool's LP token in the asset at `quote_index`, i.e. the
+/// pool's total value locked in that asset divided by `total_share`. Returns zero if no LP tokens
+/// have been minted.
+pub fn compute_lp_price(
+    pool_info: &PoolInfo,
+    quote_index: usize,
+    total_share: Uint128,
+) -> Result<Decimal256, ContractError> {
+    if total_share.is_zero() {
+        return Ok(Decimal256::zero());
+    }
+
+    // the pool value is in whole units of the quote asset, scale it back to its smallest unit
+    let quote_unit =
+        Uint256::from(10u128).checked_pow(pool_info.asset_decimals[quote_index].into())?;
+
+    Ok(compute_pool_value(pool_info, quote_index)?
+        .checked_mul(Decimal256::from_ratio(quote_unit, total_share))?)
+}
+
+/// Raises the recorded high-water marks of the pool's LP token value, in each of its assets, to
+/// the current values given the `total_share`. The marks are left untouched while the pool is
+/// missing the liquidity to price its LP token, e.g. after it's been fully withdrawn from.
+pub fn update_share_price_high_water_marks(
+    storage: &mut dyn Storage,
+    pool_info: &PoolInfo,
+    total_share: Uint128,
+) -> Result<(), ContractError> {
+    for (quote_index, denom) in pool_info.asset_denoms.iter().enumerate() {
+        let price = match compute_lp_price(pool_info, quote_index, total_share) {
+            Ok(price) => price,
+            Err(ContractError::PoolHasNoAssets) => continue,
+            Err(error) => return Err(error),
+        };
+
+        let key = (pool_info.pool_identifier.as_str(), denom.as_str());
+        if SHARE_PRICE_HIGH_WATER_MARKS
+            .may_load(storage, key)?
+            .is_none_or(|high_water_mark| price > high_water_mark)
+        {
+            SHARE_PRICE_HIGH_WATER_MARKS.save(storage, key, &price)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The minimum amount of blocks between two snapshots of a pool's reserves.
+pub const RESERVE_SNAPSHOT_INTERVAL: u64 = 100;
+/// The maximum amount of snapshots of a pool's reserves kept at a time.
+pub const MAX_RESERVE_SNAPSHOTS: u64 = 100;
+
+/// The maximum amount of expired snapshots pruned each time a new one is taken. Pruning more than
+/// one per snapshot makes the expired ones left after an idle period go away over time, while
+/// keeping the cost of each operation bounded.
+const MAX_PRUNED_RESERVE_SNAPSHOTS: usize = 2;
+
+/// Snapshots the reserves of the pool at `height`, unless the latest snapshot was taken less than
+/// [RESERVE_SNAPSHOT_INTERVAL] blocks before, in which case only the latest snapshot height is
+/// read. Each snapshot is its own entry, and up to [MAX_PRUNED_RESERVE_SNAPSHOTS] of the oldest
+/// ones falling out of the window the last [MAX_RESERVE_SNAPSHOTS] intervals cover are pruned when
+/// a new one is taken.
+pub fn snapshot_reserves(
+    storage: &mut dyn Storage,
+    height: u64,
+    pool_info: &PoolInfo,
+) -> Result<(), ContractError> {
+    let snapshots = RESERVE_SNAPSHOTS.prefix(&pool_info.pool_identifier);
+
+    let latest_height = snapshots
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?;
+    if latest_height.is_some_and(|latest_height| {
+        height < latest_height.saturating_add(RESERVE_SNAPSHOT_INTERVAL)
+    }) {
+        return Ok(());
+    }
+
+    RESERVE_SNAPSHOTS.save(
+        storage,
+        (&pool_info.pool_identifier, height),
+        &pool_info.assets,
+    )?;
+
+    let cutoff = height.saturating_sub(RESERVE_SNAPSHOT_INTERVAL * MAX_RESERVE_SNAPSHOTS);
+    let expired_heights = snapshots
+        .keys(
+            storage,
+            None,
+            Some(Bound::inclusive(cutoff)),
+            Order::Ascending,
+        )
+        .take(MAX_PRUNED_RESERVE_SNAPSHOTS)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for expired_height in expired_heights {
+        RESERVE_SNAPSHOTS.remove(storage, (&pool_info.pool_identifier, expired_height));
+    }
+
+    Ok(())
+}
+
+/// The maximum amount of iterations to perform when searching for a swap amount.
+const SEARCH_ITERATIONS: u32 = 128;
+
+/// Finds the smallest amount in `[low, high]` for which the given monotonic `predicate` holds.
+/// Returns `high` if the predicate doesn't hold for any smaller amount.
+pub fn binary_search_amount(
+    low: Uint128,
+    high: Uint128,
+    predicate: impl Fn(Uint128) -> bool,
+) -> Uint128 {
+    let (mut low, mut high) = (low, high);
+
+    for _ in 0..SEARCH_ITERATIONS {
+        if low >= high {
+            break;
+        }
+
+        let mid = low + (high - low) / Uint128::new(2);
+        if predicate(mid) {
+            high = mid;
+        } else {
+            low = mid + Uint128::one();
+        }
+    }
+
+    high
+}
+
+/// Doubles `start` until the given monotonic `predicate` holds, returning the first amount for
+/// which it does. Used to find the upper bound of a [binary_search_amount].
+pub fn find_upper_bound(start: Uint128, predicate: impl Fn(Uint128) -> bool) -> Uint128 {
+    let mut high = start.max(Uint128::one());
+
+    while !predicate(high) {
+        match high.checked_mul(Uint128::new(2)) {
+            Ok(doubled) => high = doubled,
+            Err(_) => return Uint128::MAX,
+        }
+    }
+
+    high
+}
+
+/// Computes the amount of the asset at `offer_index` that needs to be swapped into the asset at
+/// `ask_index` so that the spot price of the offer asset drops to `target_price`, expressed in ask
+/// asset per offer asset. Returns zero if the spot price is already at or below the target.
+///
+/// For constant product pools the amount is derived in closed form from the invariant, ignoring
+/// fees. For stableswap pools a bounded binary search is performed over [compute_swap].
+pub fn compute_offer_to_reach_price(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    target_price: Decimal256,
+) -> Result<Uint128, ContractError> {
+    ensure!(!target_price.is_zero(), ContractError::InvalidTargetPrice);
+
+    if compute_spot_price(pool_info, offer_index, ask_index)? <= target_price {
+        return Ok(Uint128::zero());
+    }
+
+    match &pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let offer_pool = Uint512::from(pool_info.assets[offer_index].amount);
+            let ask_pool = Uint512::from(pool_info.assets[ask_index].amount);
+
+            // new_offer_pool = sqrt(offer_pool * ask_pool / target_price), with the target price
+            // converted from its normalized form into raw pool units
+            let numerator = offer_pool
+                .checked_mul(ask_pool)?
+                .checked_mul(Uint512::from(
+                    10u128.pow(u32::from(pool_info.asset_decimals[offer_index])),
+                ))?
+                .checked_mul(Uint512::from(10u128.pow(Decimal256::DECIMAL_PLACES)))?;
+            let denominator = Uint512::from(target_price.atomics()).checked_mul(Uint512::from(
+                10u128.pow(u32::from(pool_info.asset_decimals[ask_index])),
+            ))?;
+
+            let new_offer_pool = numerator.checked_div(denominator)?.isqrt();
+
+            Ok(Uint128::try_from(
+                new_offer_pool.saturating_sub(offer_pool),
+            )?)
+        }
+        PoolType::StableSwap { .. } => {
+            // a swap that fails to compute, i.e. drains the pool, is considered past the target
+            let reaches_target = |offer_amount: Uint128| {
+                let mut pool = pool_info.clone();
+                compute_swap_for_pool(&pool, offer_index, ask_index, offer_amount)
+                    .and_then(|swap| {
+                        apply_swap_to_pool(&mut pool, offer_index, ask_index, offer_amount, &swap)
+                    })
+                    .and_then(|_| compute_spot_price(&pool, offer_index, ask_index))
+                    .map_or(true, |price| price <= target_price)
+            };
+
+            let high = find_upper_bound(pool_info.assets[offer_index].amount, reaches_target);
+
+            Ok(binary_search_amount(Uint128::zero(), high, reaches_target))
+        }
+    }
+}
+
+/// Computes the amount of each pool asset `lp_amount` LP tokens are redeemable for, i.e. their
+/// proportional share of the pool reserves. Errors if `lp_amount` exceeds the `total_share`.
+pub fn compute_lp_share_value(
+    pool_info: &PoolInfo,
+    lp_amount: Uint128,
+    total_share: Uint128,
+) -> Result<Vec<Coin>, ContractError> {
+    let share_ratio = Decimal256::checked_from_ratio(lp_amount, total_share)
+        .map_err(|_| ContractError::InvalidLpShareToWithdraw)?;
+
+    // sanity check, the share_ratio cannot possibly be greater than 1
+    ensure!(
+        share_ratio <= Decimal256::one(),
+        ContractError::InvalidLpShareToWithdraw
+    );
+
+    pool_info
+        .assets
+        .iter()
+        .map(|pool_asset| {
+            Ok(Coin {
+                denom: pool_asset.denom.clone(),
+                amount: Uint128::try_from(
+                    Decimal256::from_ratio(pool_asset.amount, Uint256::one())
+                        .checked_mul(share_ratio)?
+                        .to_uint_floor(),
+                )?,
+            })
+        })
+        .collect()
+}
+
+/// Computes the amount of LP tokens a deposit into a pool that already holds liquidity would
+/// mint, the same way it's done when the liquidity is provided.
+pub fn compute_deposit_share(
+    pool_info: &PoolInfo,
+    deposits: &[Coin],
+    total_share: Uint128,
+) -> Result<Uint128, ContractError> {
+    ensure!(
+        !total_share.is_zero() && !pool_info.assets.iter().any(|a| a.amount.is_zero()),
+        ContractError::PoolHasNoAssets
+    );
+
+    match &pool_info.pool_type {
+        PoolType::ConstantProduct => pool_info
+            .assets
+            .iter()
+            .map(|pool_asset| {
+                let deposit = deposits
+                    .iter()
+                    .find(|deposit| deposit.denom == pool_asset.denom)
+                    .map_or(Uint128::zero(), |deposit| deposit.amount);
+
+                Ok(deposit.multiply_ratio(total_share, pool_asset.amount))
+            })
+            .try_fold(Uint128::MAX, |acc, share: Result<_, ContractError>| {
+                Ok(acc.min(share?))
+            }),
+        PoolType::StableSwap { amp: amp_factor } => {
+            Ok(compute_lp_mint_amount_for_stableswap_deposit(
+                amp_factor,
+                &pool_info.assets,
+                &add_coins(pool_info.assets.clone(), deposits.to_vec())?,
+                total_share,
+            )?
+            .unwrap_or_default())
+        }
+    }
+}
+
+/// Computes the amount of the asset at `offer_index` to swap into the asset at `ask_index` when
+/// providing liquidity with `deposit_amount` of the offer asset only, so that the remainder and the
+/// swap return are deposited in the same ratio as the pool reserves after the swap.
+pub fn compute_zap_in_swap_amount(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    deposit_amount: Uint128,
+) -> Uint128 {
+    // a swap that fails to compute is considered past the balanced split
+    let is_balanced_or_past = |swap_amount: Uint128| {
+        let mut pool = pool_info.clone();
+        compute_swap_for_pool(&pool, offer_index, ask_index, swap_amount)
+            .and_then(|swap| {
+                apply_swap_to_pool(&mut pool, offer_index, ask_index, swap_amount, &swap)?;
+
+                // return / ask_pool >= remainder / offer_pool
+                Ok(Uint256::from(swap.return_amount)
+                    .checked_mul(pool.assets[offer_index].amount.into())?
+                    >= Uint256::from(deposit_amount.checked_sub(swap_amount)?)
+                        .checked_mul(pool.assets[ask_index].amount.into())?)
+            })
+            .unwrap_or(true)
+    };
+
+    binary_search_amount(Uint128::zero(), deposit_amount, is_balanced_or_past)
+}
+
+/// Computes the amount of the asset at `offer_index` a holder of `offer_balance` and `ask_balance`
+/// needs to swap into the asset at `ask_index` to end up holding equal value of both, valued at
+/// the pool's spot price after the swap. Both balances are normalized by the asset decimals.
+pub fn compute_rebalance_to_equal_amount(
+    pool_info: &PoolInfo,
+    offer_index: usize,
+    ask_index: usize,
+    offer_balance: Uint128,
+    ask_balance: Uint128,
+) -> Uint128 {
+    let offer_decimal = pool_info.asset_decimals[offer_index];
+    let ask_decimal = pool_info.asset_decimals[ask_index];
+
+    // a swap that fails to compute is considered past the balanced split
+    let is_balanced_or_past = |swap_amount: Uint128| {
+        let mut pool = pool_info.clone();
+        compute_swap_for_pool(&pool, offer_index, ask_index, swap_amount)
+            .and_then(|swap| {
+                apply_swap_to_pool(&mut pool, offer_index, ask_index, swap_amount, &swap)?;
+                let spot_price = compute_spot_price(&pool, offer_index, ask_index)?;
+
+                let offer_value = Decimal256::decimal_with_precision(
+                    offer_balance.checked_sub(swap_amount)?,
+                    offer_decimal,
+                )?
+                .checked_mul(spot_price)?;
+                let ask_value = Decimal256::decimal_with_precision(
+                    ask_balance.checked_add(swap.return_amount)?,
+                    ask_decimal,
+                )?;
+
+                Ok(offer_value <= ask_value)
+            })
+            .unwrap_or(true)
+    };
+
+    binary_search_amount(Uint128::zero(), offer_balance, is_balanced_or_past)
+}
+
+/// Computes the amount of the asset at `target_index` received when withdrawing `lp_amount` LP
+/// tokens and swapping every other withdrawn asset into it. The swaps are performed one after the
+/// other against the reserves left after the withdrawal.
+pub fn compute_zap_out_amount(
+    pool_info: &PoolInfo,
+    lp_amount: Uint128,
+    total_share: Uint128,
+    target_index: usize,
+) -> Result<Uint128, ContractError> {
+    apply_zap_out_to_pool(&mut pool_info.clone(), lp_amount, total_share, target_index)
+}
+
+/// Applies the withdrawal and swaps of [compute_zap_out_amount] to the pool's reserves, and
+/// returns the amount of the asset at `target_index` received.
+pub fn apply_zap_out_to_pool(
+    pool_info: &mut PoolInfo,
+    lp_amount: Uint128,
+    total_share: Uint128,
+    target_index: usize,
+) -> Result<Uint128, ContractError> {
+    let withdrawn_assets = compute_lp_share_value(pool_info, lp_amount, total_share)?;
+
+    for (pool_asset, withdrawn_asset) in pool_info.assets.iter_mut().zip(withdrawn_assets.iter()) {
+        pool_asset.amount = pool_asset.amount.checked_sub(withdrawn_asset.amount)?;
+    }
+
+    let mut return_amount = withdrawn_assets[target_index].amount;
+
+    for (offer_index, withdrawn_asset) in withdrawn_assets.iter().enumerate() {
+        if offer_index == target_index || withdrawn_asset.amount.is_zero() {
+            continue;
+        }
+
+        let swap_computation =
+            compute_swap_for_pool(pool_info, offer_index, target_index, withdrawn_asset.amount)?;
+        apply_swap_to_pool(
+            pool_info,
+            offer_index,
+            target_index,
+            withdrawn_asset.amount,
+            &swap_computation,
+        )?;
+
+        return_amount = return_amount.checked_add(swap_computation.return_amount)?;
+    }
+
+    Ok(return_amount)
+}
+
+/// Computes how much more of the asset at `index` the pool can hold before the invariant
+/// computation overflows. The amount is also capped by the reserves being stored as [Uint128].
+///
+/// For constant product pools the invariant is the product of the reserves, which must fit in a
+/// [Uint256]. Stableswap pools compute D and the swap amounts through several intermediate
+/// products, so the headroom is found by searching for the smallest addition that makes a swap
+/// against the pool fail to compute.
+pub fn compute_capacity_headroom(
+    pool_info: &PoolInfo,
+    index: usize,
+) -> Result<Uint128, ContractError> {
+    let storage_headroom = Uint128::MAX.checked_sub(pool_info.assets[index].amount)?;
+
+    match &pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let other_reserves_product = pool_info
+                .assets
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .try_fold(Uint256::one(), |acc, (_, asset)| {
+                    acc.checked_mul(asset.amount.into())
+                })?;
+
+            if other_reserves_product.is_zero() {
+                return Ok(storage_headroom);
+            }
+
+            let max_reserve = Uint256::MAX.checked_div(other_reserves_product)?;
+            let headroom = max_reserve.saturating_sub(pool_info.assets[index].amount.into());
+
+            Ok(Uint128::try_from(headroom)
+                .unwrap_or(Uint128::MAX)
+                .min(storage_headroom))
+        }
+        PoolType::StableSwap { .. } => {
+            let overflows = |additional_amount: Uint128| {
+                let mut pool = pool_info.clone();
+                pool.assets[index].amount += additional_amount;
+
+                (0..pool.assets.len()).filter(|i| *i != index).any(|other| {
+                    compute_swap_for_pool(&pool, index, other, Uint128::one()).is_err()
+                        || compute_swap_for_pool(&pool, other, index, Uint128::one()).is_err()
+                })
+            };
+
+            if !overflows(storage_headroom) {
+                return Ok(storage_headroom);
+            }
+
+            Ok(
+                binary_search_amount(Uint128::zero(), storage_headroom, overflows)
+                    .saturating_sub(Uint128::one()),
+            )
+        }
+    }
+}
+
+/// The maximum amount of pools [find_shortest_pool_path] searches through.
+pub const MAX_REACHABLE_HOPS: u32 = 5;
+
+/// Finds the shortest chain of pools connecting `from_denom` to `to_denom`, with a breadth-first
+/// search over the graph of denoms linked by the pools containing them. Returns the identifiers of
+/// the pools along the path, or an empty vec if no path exists within `max_hops` pools, capped at
+/// [MAX_REACHABLE_HOPS].
+///
+/// The pools are read once to map each denom to the pools containing it, so the cost of the
+/// search grows with the number of pools, not with the number of hops.
+pub fn find_shortest_pool_path(
+    deps: &Deps,
+    from_denom: &str,
+    to_denom: &str,
+    max_hops: u32,
+) -> Result<Vec<String>, ContractError> {
+    if from_denom == to_denom {
+        return Ok(vec![]);
+    }
+
+    // each denom maps to the identifiers and denoms of the pools containing it
+    let mut adjacency: HashMap<String, Vec<(String, Vec<String>)>> = HashMap::new();
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, pool) = item?;
+        for denom in &pool.asset_denoms {
+            adjacency
+                .entry(denom.clone())
+                .or_default()
+                .push((pool.pool_identifier.clone(), pool.asset_denoms.clone()));
+        }
+    }
+
+    // each reached denom maps to the denom and pool it was reached from
+    let mut reached: HashMap<String, Option<(String, String)>> = HashMap::new();
+    reached.insert(from_denom.to_string(), None);
+
+    let mut frontier = vec![from_denom.to_string()];
+    for _ in 0..max_hops.min(MAX_REACHABLE_HOPS) {
+        let mut next_frontier = vec![];
+
+        for denom in &frontier {
+            for (pool_identifier, pool_denoms) in adjacency.get(denom).into_iter().flatten() {
+                for next_denom in pool_denoms {
+                    if reached.contains_key(next_denom) {
+                        continue;
+                    }
+
+                    reached.insert(
+                        next_denom.clone(),
+                        Some((denom.clone(), pool_identifier.clone())),
+                    );
+                    next_frontier.push(next_denom.clone());
+                }
+            }
+        }
+
+        if reached.contains_key(to_denom) {
+            let mut path = vec![];
+            let mut denom = to_denom.to_string();
+            while let Some(Some((previous_denom, pool_identifier))) = reached.get(&denom) {
+                path.push(pool_identifier.clone());
+                denom = previous_denom.clone();
+            }
+            path.reverse();
+
+            return Ok(path);
+        }
+
+        if next_frontier.is_empty() {
+            break;
+        }
+        frontier = next_frontier;
+    }
+
+    Ok(vec![])
+}
+
+/// Scales the reserves of a pool to the largest precision among its assets, so they can be
+/// compared to one another in the stableswap invariant.
+pub fn normalize_reserves(pool_info: &PoolInfo) -> Result<Vec<Coin>, ContractError> {
+    let max_decimals = pool_info.asset_decimals.iter().max().copied().unwrap_or(0);
+
+    pool_info
+        .assets
+        .iter()
+        .zip(pool_info.asset_decimals.iter())
+        .map(|(asset, decimals)| {
+            Ok(coin(
+                adjust_precision(asset.amount, *decimals, max_decimals)?.u128(),
+                asset.denom.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Computes the amp at which the stableswap curve of the pool approximates the constant product
+/// one at its current reserves.
+///
+/// Written in terms of the leverage `χ = amp * n * Π(x_i) / D^n`, the stableswap invariant is
+/// `χ * D^(n-1) * Σ(x_i) + Π(x_i) = χ * D^n + (D / n)^n`, which reduces to the constant product
+/// invariant as `χ` goes to zero. The returned amp is the one giving a leverage of 1%, at which
+/// the constant sum term only has a marginal weight on the curve, so this is an approximation.
+/// Reserves are normalized to the largest asset precision of the pool, and the invariant is
+/// computed with the pool's current amp.
+pub fn compute_constant_product_equivalent_amp(
+    pool_info: &PoolInfo,
+) -> Result<Uint256, ContractError> {
+    let PoolType::StableSwap { amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+    ensure!(
+        !pool_info.assets.iter().any(|asset| asset.amount.is_zero()),
+        ContractError::PoolHasNoAssets
+    );
+
+    let normalized_reserves = normalize_reserves(pool_info)?;
+
+    let d = Uint256::try_from(
+        compute_d(&amp, &normalized_reserves).ok_or(ContractError::StableInvariantError)?,
+    )?;
+
+    // D^n / Π(x_i)
+    let invariant_ratio = normalized_reserves
+        .iter()
+        .try_fold(Decimal256::one(), |acc, reserve| {
+            acc.checked_mul(Decimal256::from_ratio(d, reserve.amount))
+        })?;
+
+    // amp = χ * D^n / (n * Π(x_i)), with χ = 1%
+    let equivalent_amp = invariant_ratio
+        .checked_div(Decimal256::from_ratio(
+            100u128 * normalized_reserves.len() as u128,
+            1u128,
+        ))?
+        .to_uint_floor();
+
+    Ok(equivalent_amp.max(Uint256::from(MIN_AMP)))
+}
+
+/// Encodes the given swap operations into a compact binary format. Each operation is encoded as
+/// the length of the pool identifier (1 byte), the pool identifier, and the indexes of the input
+/// and output denoms within the pool (1 byte each).
+pub fn encode_route(deps: &Deps, operations: &[SwapOperation]) -> Result<Binary, ContractError> {
+    let mut encoded = vec![];
+
+    for operation in operations {
+        let SwapOperation::MantraSwap {
+            token_in_denom,
+            token_out_denom,
+            pool_identifier,
+        } = operation;
+
+        let pool = get_pool_by_identifier(deps, pool_identifier)?;
+        let denom_index = |denom: &String| {
+            pool.asset_denoms
+                .iter()
+                .position(|d| d == denom)
+                .and_then(|index| u8::try_from(index).ok())
+                .ok_or(ContractError::AssetMismatch)
+        };
+
+        let identifier_len = u8::try_from(pool_identifier.len()).map_err(|_| {
+            ContractError::InvalidPoolIdentifier {
+                identifier: pool_identifier.clone(),
+            }
+        })?;
+
+        encoded.push(identifier_len);
+        encoded.extend_from_slice(pool_identifier.as_bytes());
+        encoded.push(denom_index(token_in_denom)?);
+        encoded.push(denom_index(token_out_denom)?);
+    }
+
+    Ok(Binary::from(encoded))
+}
+
+/// Decodes swap operations encoded with [encode_route].
+pub fn decode_route(deps: &Deps, encoded: &Binary) -> Result<Vec<SwapOperation>, ContractError> {
+    let mut operations = vec![];
+    let mut bytes = encoded.as_slice();
+
+    while let Some((&identifier_len, rest)) = bytes.split_first() {
+        let identifier_len = usize::from(identifier_len);
+        ensure!(
+            rest.len() >= identifier_len + 2,
+            ContractError::InvalidEncodedRoute
+        );
+
+        let (identifier, rest) = rest.split_at(identifier_len);
+        let pool_identifier = String::from_utf8(identifier.to_vec())
+            .map_err(|_| ContractError::InvalidEncodedRoute)?;
+
+        let pool = get_pool_by_identifier(deps, &pool_identifier)?;
+        let denom_at = |index: u8| {
+            pool.asset_denoms
+                .get(usize::from(index))
+                .cloned()
+                .ok_or(ContractError::InvalidEncodedRoute)
+        };
+
+        operations.push(SwapOperation::MantraSwap {
+            token_in_denom: denom_at(rest[0])?,
+            token_out_denom: denom_at(rest[1])?,
+            pool_identifier,
+        });
+
+        bytes = &rest[2..];
+    }
+
+    Ok(operations)
+}
+
 #[cfg(test)]
 #[allow(
     clippy::unwrap_used,
@@ -1321,4 +2398,66 @@ mod tests {
             assert!(d0 <= d1);  // Pool token supply not changed on swaps
         }
     }
+
+    proptest! {
+        #[test]
+        fn decode_route_is_inverse_of_encode_route(
+            hops in prop::collection::vec((0..3usize, 0..3usize, 0..3usize), 0..8),
+        ) {
+            let mut deps = cosmwasm_std::testing::mock_dependencies();
+            let pool_identifiers = ["o.whale.uluna.uusd", "p.1", "3"];
+
+            for pool_identifier in pool_identifiers {
+                let pool = PoolInfo {
+                    pool_identifier: pool_identifier.to_string(),
+                    asset_denoms: vec![
+                        "uwhale".to_string(),
+                        "uluna".to_string(),
+                        "factory/mantra1/uusd".to_string(),
+                    ],
+                    lp_denom: format!("factory/mantra1/{pool_identifier}.{LP_SYMBOL}"),
+                    asset_decimals: vec![6u8, 6u8, 18u8],
+                    assets: vec![],
+                    pool_type: PoolType::ConstantProduct,
+                    pool_fees: PoolFee {
+                        protocol_fee: amm::fee::Fee { share: Decimal::zero() },
+                        swap_fee: amm::fee::Fee { share: Decimal::zero() },
+                        burn_fee: amm::fee::Fee { share: Decimal::zero() },
+                        extra_fees: vec![],
+                    },
+                };
+                crate::state::POOLS.save(&mut deps.storage, pool_identifier, &pool).unwrap();
+            }
+
+            let denoms = ["uwhale", "uluna", "factory/mantra1/uusd"];
+            let route: Vec<SwapOperation> = hops
+                .into_iter()
+                .map(|(pool, token_in, token_out)| SwapOperation::MantraSwap {
+                    token_in_denom: denoms[token_in].to_string(),
+                    token_out_denom: denoms[token_out].to_string(),
+                    pool_identifier: pool_identifiers[pool].to_string(),
+                })
+                .collect();
+
+            let encoded = encode_route(&deps.as_ref(), &route).unwrap();
+            prop_assert_eq!(decode_route(&deps.as_ref(), &encoded).unwrap(), route);
+        }
+    }
+
+    #[test]
+    fn test_adjust_precision() {
+        let amount = Uint128::new(1_234_567u128);
+
+        assert_eq!(adjust_precision(amount, 18, 18).unwrap(), amount);
+        assert_eq!(
+            adjust_precision(amount, 6, 8).unwrap(),
+            Uint128::new(123_456_700u128)
+        );
+        // scaling down truncates
+        assert_eq!(
+            adjust_precision(amount, 8, 6).unwrap(),
+            Uint128::new(12_345u128)
+        );
+        assert!(adjust_precision(Uint128::MAX, 6, 18).is_err());
+    }
 }
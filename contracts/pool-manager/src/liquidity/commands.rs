@@ -1,6 +1,6 @@
 use cosmwasm_std::{
-    coin, coins, ensure, to_json_binary, wasm_execute, BankMsg, Coin, CosmosMsg, Decimal256,
-    DepsMut, Env, MessageInfo, Response, StdResult, SubMsg, Uint256,
+    coin, coins, ensure, to_json_binary, wasm_execute, BankMsg, Coin, CosmosMsg, DepsMut, Env,
+    MessageInfo, Response, StdResult, SubMsg,
 };
 use cosmwasm_std::{Decimal, Uint128};
 
@@ -13,7 +13,7 @@ use amm::U256;
 
 use crate::{
     helpers::{self},
-    state::get_pool_by_identifier,
+    state::get_pool_at,
 };
 use crate::{
     state::{CONFIG, POOLS},
@@ -51,7 +51,7 @@ pub fn provide_liquidity(
     );
 
     // Get the pool by the pool_identifier
-    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut pool = get_pool_at(&deps.as_ref(), &pool_identifier, env.block.time.seconds())?;
 
     let mut pool_assets = pool.assets.clone();
     let deposits = aggregate_coins(info.funds.clone())?;
@@ -102,6 +102,7 @@ pub fn provide_liquidity(
 
         let swap_simulation_response = query_simulation(
             deps.as_ref(),
+            env.clone(),
             swap_half.clone(),
             ask_asset_denom.clone(),
             pool_identifier.clone(),
@@ -390,6 +391,16 @@ pub fn provide_liquidity(
 
         POOLS.save(deps.storage, &pool_identifier, &pool)?;
 
+        // the LP tokens are minted after this call, including the locked minimum on the first
+        // deposit
+        let new_total_share = if total_share.is_zero() {
+            share.checked_add(MINIMUM_LIQUIDITY_AMOUNT)?
+        } else {
+            total_share.checked_add(share)?
+        };
+        helpers::update_share_price_high_water_marks(deps.storage, &pool, new_total_share)?;
+        helpers::snapshot_reserves(deps.storage, env.block.height, &pool)?;
+
         Ok(Response::new().add_messages(messages).add_attributes(vec![
             ("action", "provide_liquidity"),
             ("sender", info.sender.as_str()),
@@ -424,7 +435,7 @@ pub fn withdraw_liquidity(
     }
 
     // Get the pool by the pool_identifier
-    let mut pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut pool = get_pool_at(&deps.as_ref(), &pool_identifier, env.block.time.seconds())?;
     let liquidity_token = pool.lp_denom.clone();
     // Verify that the LP token was sent
     let amount = cw_utils::must_pay(&info, &liquidity_token)?;
@@ -432,30 +443,8 @@ pub fn withdraw_liquidity(
     // Get the total share of the pool
     let total_share = get_total_share(&deps.as_ref(), liquidity_token.clone())?;
 
-    // Get the ratio of the amount to withdraw to the total share
-    let share_ratio: Decimal256 = Decimal256::from_ratio(amount, total_share);
-
-    // sanity check, the share_ratio cannot possibly be greater than 1
-    ensure!(
-        share_ratio <= Decimal256::one(),
-        ContractError::InvalidLpShareToWithdraw
-    );
-
-    // Use the ratio to calculate the amount of each pool asset to refund
-    let refund_assets: Vec<Coin> = pool
-        .assets
-        .iter()
-        .map(|pool_asset| {
-            Ok(Coin {
-                denom: pool_asset.denom.clone(),
-                amount: Uint128::try_from(
-                    Decimal256::from_ratio(pool_asset.amount, Uint256::one())
-                        .checked_mul(share_ratio)?
-                        .to_uint_floor(),
-                )?,
-            })
-        })
-        .collect::<Result<Vec<Coin>, ContractError>>()?
+    // Use the share of the pool to calculate the amount of each pool asset to refund
+    let refund_assets: Vec<Coin> = helpers::compute_lp_share_value(&pool, amount, total_share)?
         .into_iter()
         // filter out assets with zero amount
         .filter(|coin| coin.amount > Uint128::zero())
@@ -484,6 +473,7 @@ pub fn withdraw_liquidity(
     }
 
     POOLS.save(deps.storage, &pool_identifier, &pool)?;
+    helpers::snapshot_reserves(deps.storage, env.block.height, &pool)?;
 
     // Burn the LP tokens
     messages.push(amm::lp_common::burn_lp_asset_msg(
@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    attr, ensure, Attribute, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128,
+    attr, ensure, Attribute, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
+    Uint128, Uint256,
 };
 
 use amm::coin::is_factory_token;
@@ -20,6 +21,8 @@ use crate::{
 
 pub const MAX_ASSETS_PER_POOL: usize = 4usize;
 pub const MIN_ASSETS_PER_POOL: usize = 2usize;
+/// The minimum amplification coefficient of stableswap pools.
+pub const MIN_AMP: u64 = 1u64;
 
 /// The prefix used when creation a pool with an explicitly provided ID
 pub const EXPLICIT_POOL_ID_PREFIX: &str = "o.";
@@ -85,21 +88,13 @@ pub fn create_pool(
     // Load config for pool creation fee
     let config: Config = CONFIG.load(deps.storage)?;
 
-    // Ensure that the number of assets and decimals match, and that they are not empty
-    ensure!(
-        !asset_denoms.is_empty()
-            && asset_denoms.len() >= MIN_ASSETS_PER_POOL
-            && asset_denoms.len() == asset_decimals.len(),
-        ContractError::AssetMismatch
-    );
-
-    // Ensure that the number of assets is within the allowed range
-    ensure!(
-        asset_denoms.len() <= MAX_ASSETS_PER_POOL,
-        ContractError::TooManyAssets {
-            assets_provided: asset_denoms.len(),
-        }
-    );
+    // Verify the amount of assets and decimals
+    if let Some(error) = validate_pool_asset_counts(&asset_denoms, &asset_decimals)
+        .into_iter()
+        .next()
+    {
+        return Err(error);
+    }
 
     // check if the pool and token factory fees were paid
     let total_fees = validate_fees_are_paid(
@@ -111,6 +106,14 @@ pub fn create_pool(
     // make sure the user doesn't accidentally send more tokens than needed
     validate_no_additional_funds_sent_with_pool_creation(&info, total_fees)?;
 
+    // Verify the assets, the pool type and the pool fees
+    if let Some(error) = validate_pool_parameters(&asset_denoms, &pool_type, &pool_fees)
+        .into_iter()
+        .next()
+    {
+        return Err(error);
+    }
+
     // Prepare the sending of pool creation fee
     let mut messages: Vec<CosmosMsg> = vec![];
     if !config.pool_creation_fee.amount.is_zero() {
@@ -124,17 +127,6 @@ pub fn create_pool(
         );
     }
 
-    // Check if the asset infos are the same
-    if asset_denoms
-        .iter()
-        .any(|asset| asset_denoms.iter().filter(|&a| a == asset).count() > 1)
-    {
-        return Err(ContractError::SameAsset); //what if two assets are same but one is in lowercase n one is in upper
-    }
-
-    // Verify pool fees
-    pool_fees.is_valid()?;
-
     let identifier = if let Some(id) = pool_identifier {
         format!("{EXPLICIT_POOL_ID_PREFIX}{id}")
     } else {
@@ -214,3 +206,72 @@ pub fn create_pool(
         .add_attributes(attributes)
         .add_messages(messages))
 }
+
+/// Validates a pool specification, i.e. its assets, their decimals, the pool type and the fees,
+/// collecting every failure instead of stopping at the first one. [create_pool] fails with the
+/// first of the [validate_pool_asset_counts] failures before checking the creation fee payment,
+/// and with the first of the [validate_pool_parameters] ones after.
+pub fn validate_pool_spec(
+    asset_denoms: &[String],
+    asset_decimals: &[u8],
+    pool_type: &PoolType,
+    pool_fees: &PoolFee,
+) -> Vec<ContractError> {
+    let mut errors = validate_pool_asset_counts(asset_denoms, asset_decimals);
+    errors.extend(validate_pool_parameters(asset_denoms, pool_type, pool_fees));
+
+    errors
+}
+
+/// Validates the amount of assets of a pool specification and of their decimals.
+pub fn validate_pool_asset_counts(
+    asset_denoms: &[String],
+    asset_decimals: &[u8],
+) -> Vec<ContractError> {
+    let mut errors = vec![];
+
+    // Ensure that the number of assets and decimals match, and that they are not empty
+    if asset_denoms.len() < MIN_ASSETS_PER_POOL || asset_denoms.len() != asset_decimals.len() {
+        errors.push(ContractError::AssetMismatch);
+    }
+
+    // Ensure that the number of assets is within the allowed range
+    if asset_denoms.len() > MAX_ASSETS_PER_POOL {
+        errors.push(ContractError::TooManyAssets {
+            assets_provided: asset_denoms.len(),
+        });
+    }
+
+    errors
+}
+
+/// Validates the assets, the pool type and the fees of a pool specification.
+pub fn validate_pool_parameters(
+    asset_denoms: &[String],
+    pool_type: &PoolType,
+    pool_fees: &PoolFee,
+) -> Vec<ContractError> {
+    let mut errors = vec![];
+
+    // Check if the asset infos are the same
+    if asset_denoms
+        .iter()
+        .any(|asset| asset_denoms.iter().filter(|&a| a == asset).count() > 1)
+    {
+        errors.push(ContractError::SameAsset); //what if two assets are same but one is in lowercase n one is in upper
+    }
+
+    // Verify pool fees
+    if let Err(err) = pool_fees.is_valid() {
+        errors.push(err.into());
+    }
+
+    // Verify the amplification coefficient, the stableswap invariant is undefined below it
+    if let PoolType::StableSwap { amp } = pool_type {
+        if *amp < MIN_AMP {
+            errors.push(ContractError::InvalidAmp(Uint256::from(*amp)));
+        }
+    }
+
+    errors
+}
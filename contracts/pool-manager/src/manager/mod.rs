@@ -1,4 +1,6 @@
 pub mod commands;
 
+mod ramp_amp;
 mod update_config;
+pub use ramp_amp::ramp_amp;
 pub use update_config::update_config;
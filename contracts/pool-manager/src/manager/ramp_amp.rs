@@ -0,0 +1,63 @@
+use amm::pool_manager::PoolType;
+use cosmwasm_std::{ensure, DepsMut, Env, MessageInfo, Response};
+
+use crate::manager::commands::MIN_AMP;
+use crate::state::{get_pool_by_identifier, AmpRamp, AMP_RAMPS};
+use crate::ContractError;
+
+/// The minimum duration of an amp ramp, in seconds.
+pub const MIN_RAMP_DURATION: u64 = 86_400;
+/// The maximum factor an amp ramp can multiply or divide the current amp by.
+pub const MAX_AMP_CHANGE: u64 = 10;
+
+pub fn ramp_amp(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pool_identifier: String,
+    target_amp: u64,
+    ramp_end_time: u64,
+) -> Result<Response, ContractError> {
+    // permission check
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let pool = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let PoolType::StableSwap { amp } = pool.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let now = env.block.time.seconds();
+
+    // a new ramp starts from wherever the previous one is at
+    let initial_amp = AMP_RAMPS
+        .may_load(deps.storage, &pool_identifier)?
+        .map_or(amp, |ramp| ramp.amp_at(now));
+
+    // bound how fast and how far the amp can move, so it can't be changed abruptly
+    ensure!(
+        target_amp >= MIN_AMP
+            && ramp_end_time >= now.saturating_add(MIN_RAMP_DURATION)
+            && target_amp <= initial_amp.saturating_mul(MAX_AMP_CHANGE)
+            && target_amp.saturating_mul(MAX_AMP_CHANGE) >= initial_amp,
+        ContractError::InvalidAmpRamp
+    );
+
+    AMP_RAMPS.save(
+        deps.storage,
+        &pool_identifier,
+        &AmpRamp {
+            initial_amp,
+            target_amp,
+            start_time: now,
+            end_time: ramp_end_time,
+        },
+    )?;
+
+    Ok(Response::default().add_attributes(vec![
+        ("action", "ramp_amp".to_string()),
+        ("pool_identifier", pool_identifier),
+        ("initial_amp", initial_amp.to_string()),
+        ("target_amp", target_amp.to_string()),
+        ("ramp_end_time", ramp_end_time.to_string()),
+    ]))
+}
@@ -1,8 +1,9 @@
-use amm::pool_manager::{Config, FeatureToggle};
-use cosmwasm_std::{Coin, DepsMut, MessageInfo, Response};
+use amm::pool_manager::{Config, FeatureToggle, FeeTierThresholds};
+use cosmwasm_std::{ensure, Coin, DepsMut, MessageInfo, Response};
 
 use crate::{state::CONFIG, ContractError};
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
@@ -10,6 +11,8 @@ pub fn update_config(
     farm_manager_addr: Option<String>,
     pool_creation_fee: Option<Coin>,
     feature_toggle: Option<FeatureToggle>,
+    fee_tier_thresholds: Option<FeeTierThresholds>,
+    quote_denom_priority: Option<Vec<String>>,
 ) -> Result<Response, ContractError> {
     // permission check
     cw_ownable::assert_owner(deps.storage, &info.sender)?;
@@ -32,6 +35,18 @@ pub fn update_config(
         if let Some(feature_toggle) = feature_toggle {
             config.feature_toggle = feature_toggle;
         }
+
+        if let Some(fee_tier_thresholds) = fee_tier_thresholds {
+            ensure!(
+                fee_tier_thresholds.stable_max <= fee_tier_thresholds.standard_max,
+                ContractError::InvalidFeeTierThresholds
+            );
+            config.fee_tier_thresholds = fee_tier_thresholds;
+        }
+
+        if let Some(quote_denom_priority) = quote_denom_priority {
+            config.quote_denom_priority = quote_denom_priority;
+        }
         Ok::<Config, ContractError>(config)
     })?;
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::{Decimal256, Uint256};
+use cosmwasm_std::{Decimal256, Fraction, SignedDecimal256, Uint256};
 
 use crate::error::ContractError;
 
@@ -15,8 +15,16 @@ pub trait Decimal256Helper {
     ) -> Result<Decimal256, ContractError>;
 
     fn to_uint256_with_precision(&self, precision: u32) -> Result<Uint256, ContractError>;
+
+    fn checked_ln(&self) -> Result<SignedDecimal256, ContractError>;
 }
 
+/// The natural logarithm of 2, truncated to the 18 decimal places of a [Decimal256].
+const LN_2: Decimal256 = Decimal256::raw(693_147_180_559_945_309u128);
+
+/// The maximum amount of terms of the series used to approximate natural logarithms.
+const LN_SERIES_TERMS: u32 = 40;
+
 impl Decimal256Helper for Decimal256 {
     fn decimal_with_precision(
         value: impl Into<Uint256>,
@@ -42,4 +50,48 @@ impl Decimal256Helper for Decimal256 {
 
         Ok(value.checked_div(10u128.pow(self.decimal_places() - precision).into())?)
     }
+
+    /// Approximates the natural logarithm. The value is first reduced to `[1, 2)` by powers of two,
+    /// then `ln(x) = 2 * atanh((x - 1) / (x + 1))` is computed with its series, which converges
+    /// quickly on that range. Values below one are handled as `ln(x) = -ln(1 / x)`. Errors on zero,
+    /// as its logarithm is unbounded.
+    fn checked_ln(&self) -> Result<SignedDecimal256, ContractError> {
+        let (mut value, negative) = if *self >= Decimal256::one() {
+            (*self, false)
+        } else {
+            (self.inv().ok_or(ContractError::DecimalOverflow)?, true)
+        };
+
+        let two = Decimal256::from_ratio(2u8, 1u8);
+        let mut powers_of_two = 0u128;
+        while value >= two {
+            value = value.checked_div(two)?;
+            powers_of_two += 1;
+        }
+
+        // atanh(z) = z + z^3 / 3 + z^5 / 5 + ...
+        let z = value
+            .checked_sub(Decimal256::one())?
+            .checked_div(value.checked_add(Decimal256::one())?)?;
+        let z_squared = z.checked_mul(z)?;
+
+        let mut series = Decimal256::zero();
+        let mut power = z;
+        for term in 0..LN_SERIES_TERMS {
+            if power.is_zero() {
+                break;
+            }
+
+            series = series
+                .checked_add(power.checked_div(Decimal256::from_ratio(2 * term + 1, 1u8))?)?;
+            power = power.checked_mul(z_squared)?;
+        }
+
+        let ln = LN_2
+            .checked_mul(Decimal256::from_ratio(powers_of_two, 1u8))?
+            .checked_add(series.checked_mul(two)?)?;
+        let ln = SignedDecimal256::try_from(ln).map_err(|_| ContractError::DecimalOverflow)?;
+
+        Ok(if negative { -ln } else { ln })
+    }
 }
@@ -1,20 +1,48 @@
 use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
 
+use amm::coin::{add_coins, aggregate_coins};
+use amm::fee::{Fee, PoolFee};
 use amm::pool_manager::{
-    AssetDecimalsResponse, Config, PoolInfoResponse, PoolType, PoolsResponse,
-    ReverseSimulationResponse, SimulateSwapOperationsResponse, SimulationResponse, SwapOperation,
+    AmpImpactResponse, AmpRampResponse, AmpRangeSimulationResponse, AprContributionResponse,
+    AssetDecimalsResponse, AssetRolesResponse, BalancedSlippageResponse, BandWidthResponse,
+    BenchSimulationResponse, BestPoolResponse, BidAskResponse, CanonicalPoolsResponse,
+    CanonicalSimulationResponse, CapacityHeadroomResponse, CascadeResponse,
+    CheaperDirectionResponse, CheapestResponse, ConcentrationResponse, Config, ConsistencyResponse,
+    CurveCoefficientsResponse, DepositRebalanceResponse, DirectOrRoute, DirectVsRouteResponse,
+    EncodedRouteResponse, EqualFeeSplitResponse, ExternalFeeSimulationResponse, FeeCaptureResponse,
+    FeePercentagesResponse, FeeRecipientsResponse, FeeSplitResponse, FeeTier, FeeTierResponse,
+    FeeTierThresholds, FractionalSimulationResponse, GlobalStatsResponse,
+    HistoricalReservesResponse, ImbalanceFeeResponse, IntegrityResponse, LpForValueResponse,
+    LpPriceResponse, LpValueResponse, ManyToOneResponse, MarginalCurveResponse,
+    MigrationCheckResponse, PartialReverseResponse, PegDeviationResponse, PoolInfo,
+    PoolInfoResponse, PoolType, PoolTypeStatsResponse, PoolsResponse, PriceMatrixResponse,
+    PriceSummaryResponse, RangeSimulationResponse, RationalPriceResponse, RebalanceResponse,
+    ReferenceSimulationResponse, ReinvestSimulationResponse, ReserveRatioResponse,
+    RevenueDistributionResponse, ReverseSimulationResponse, RoutingGraphResponse,
+    SafeSimulationResponse, SandwichRiskResponse, SensitivityResponse, SharePriceResponse,
+    SimulateSwapOperationsResponse, SimulationResponse, SimulationWithReservesResponse,
+    SlippageCoefficientResponse, SwapCheckResponse, SwapFeeInOfferResponse, SwapOperation,
+    ValidatePoolResponse, VolatilityResponse, WhaleExitResponse, ZapInResponse, ZapOutResponse,
 };
 use cosmwasm_std::{
-    coin, ensure, Coin, Decimal256, Deps, Fraction, Order, StdResult, Uint128, Uint256,
+    coin, ensure, Binary, Coin, Decimal, Decimal256, Deps, Env, Fraction, Order, SignedDecimal256,
+    StdResult, Uint128, Uint256,
 };
 use cw_storage_plus::Bound;
 
 use crate::helpers::get_asset_indexes_in_pool;
+use crate::manager::commands::{validate_pool_spec, MIN_AMP};
 use crate::math::Decimal256Helper;
-use crate::state::{CONFIG, POOLS};
+use crate::router::commands::assert_operations;
+use crate::state::{
+    AMP_RAMPS, CONFIG, FEE_ACCUMULATORS, POOLS, RESERVE_SNAPSHOTS, SHARE_PRICE_HIGH_WATER_MARKS,
+};
+use crate::swap::perform_swap::assert_max_spread;
 use crate::{
-    helpers::{self, calculate_stableswap_y, StableSwapDirection},
-    state::get_pool_by_identifier,
+    helpers::{self},
+    state::{apply_amp_ramp, get_pool_at, get_pool_by_identifier},
     ContractError,
 };
 
@@ -23,6 +51,17 @@ pub fn query_config(deps: Deps) -> Result<Config, ContractError> {
     Ok(CONFIG.load(deps.storage)?)
 }
 
+/// Query the recipients of the fees collected by the contract. Burn fees are burned rather than
+/// sent anywhere, so there's no burn address.
+pub fn query_fee_recipients(deps: Deps) -> Result<FeeRecipientsResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(FeeRecipientsResponse {
+        protocol_fee_collector: config.fee_collector_addr,
+        burn_address: None,
+    })
+}
+
 /// Query the native asset decimals
 pub fn query_asset_decimals(
     deps: Deps,
@@ -46,11 +85,12 @@ pub fn query_asset_decimals(
 // Simulate a swap with the provided asset to determine the amount of the other asset that would be received
 pub fn query_simulation(
     deps: Deps,
+    env: Env,
     offer_asset: Coin,
     ask_asset_denom: String,
     pool_identifier: String,
 ) -> Result<SimulationResponse, ContractError> {
-    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
 
     let (offer_asset_in_pool, ask_asset_in_pool, _, _, offer_decimal, ask_decimal) =
         get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
@@ -80,11 +120,12 @@ pub fn query_simulation(
 /// the number of target tokens.
 pub fn query_reverse_simulation(
     deps: Deps,
+    env: Env,
     ask_asset: Coin,
     offer_asset_denom: String,
     pool_identifier: String,
 ) -> Result<ReverseSimulationResponse, ContractError> {
-    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
 
     let (offer_asset_in_pool, ask_asset_in_pool, _, _, offer_decimal, ask_decimal) =
         get_asset_indexes_in_pool(&pool_info, offer_asset_denom, ask_asset.denom)?;
@@ -128,34 +169,17 @@ pub fn query_reverse_simulation(
             let before_fees_offer = before_fees.to_uint256_with_precision(offer_decimal.into())?;
             let before_fees_ask = before_fees.to_uint256_with_precision(ask_decimal.into())?;
 
-            let max_precision = offer_decimal.max(ask_decimal);
-
-            let new_offer_pool_amount = calculate_stableswap_y(
+            let offer_amount = helpers::compute_stableswap_reverse_offer_amount(
                 Uint256::from(pool_info.assets.len() as u128),
                 offer_pool,
                 ask_pool,
                 before_fees,
                 &amp,
-                max_precision,
-                StableSwapDirection::ReverseSimulate,
+                offer_decimal,
+                ask_decimal,
             )?;
 
-            let offer_amount = new_offer_pool_amount.checked_sub(Uint128::try_from(
-                offer_pool.to_uint256_with_precision(u32::from(max_precision))?,
-            )?)?;
-
-            // convert into the original offer precision
-            let offer_amount = match max_precision.cmp(&offer_decimal) {
-                Ordering::Equal => offer_amount,
-                // note that Less should never happen (as max_precision = max(offer_decimal, ask_decimal))
-                Ordering::Less => offer_amount.checked_mul(Uint128::new(
-                    10u128.pow((offer_decimal - max_precision).into()),
-                ))?,
-                Ordering::Greater => offer_amount.checked_div(Uint128::new(
-                    10u128.pow((max_precision - offer_decimal).into()),
-                ))?,
-            };
-
+            // the offer amount can round below the 1:1 conversion, in which case there's no spread
             let spread_amount = offer_amount.saturating_sub(Uint128::try_from(before_fees_offer)?);
             let swap_fee_amount = pool_fees.swap_fee.compute(before_fees_ask)?;
             let protocol_fee_amount = pool_fees.protocol_fee.compute(before_fees_ask)?;
@@ -218,16 +242,53 @@ fn get_pool(deps: Deps, pool_identifier: String) -> Result<PoolInfoResponse, Con
     })
 }
 
+/// Gets the pool info like [get_pool], with the amp the pool is priced with at `time`, as given by
+/// [get_pool_at].
+fn get_pool_response_at(
+    deps: Deps,
+    pool_identifier: String,
+    time: u64,
+) -> Result<PoolInfoResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, time)?;
+    let total_share = deps.querier.query_supply(&pool_info.lp_denom)?;
+
+    Ok(PoolInfoResponse {
+        pool_info,
+        total_share,
+    })
+}
+
 /// This function iterates over the swap operations, simulates each swap
-/// to get the final amount after all the swaps.
+/// to get the final amount after all the swaps. The reserve changes of each swap are applied to an
+/// in-memory copy of the pool, so routes going through the same pool more than once see the
+/// reserves left by the previous hops.
 pub fn simulate_swap_operations(
     deps: Deps,
+    env: Env,
     offer_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
-    let operations_len = operations.len();
-    ensure!(operations_len > 0, ContractError::NoSwapOperationsProvided);
+    ensure!(
+        !operations.is_empty(),
+        ContractError::NoSwapOperationsProvided
+    );
 
+    let amount =
+        simulate_operations_in_memory(deps, &env, &mut HashMap::new(), offer_amount, operations)?;
+
+    Ok(SimulateSwapOperationsResponse { amount })
+}
+
+/// Simulates the given swap operations, applying the reserve changes of each swap to the in-memory
+/// copies of the pools in `pools`. Pools not in `pools` yet are loaded from storage, with the amp
+/// they're priced with at the current block time.
+fn simulate_operations_in_memory(
+    deps: Deps,
+    env: &Env,
+    pools: &mut HashMap<String, PoolInfo>,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> Result<Uint128, ContractError> {
     let mut amount = offer_amount;
 
     for operation in operations.into_iter() {
@@ -237,24 +298,40 @@ pub fn simulate_swap_operations(
                 token_out_denom,
                 pool_identifier,
             } => {
-                let res = query_simulation(
-                    deps,
-                    coin(amount.u128(), token_in_denom),
-                    token_out_denom,
-                    pool_identifier,
+                let pool_info = match pools.entry(pool_identifier) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => {
+                        let pool_info = get_pool_at(&deps, entry.key(), env.block.time.seconds())?;
+                        entry.insert(pool_info)
+                    }
+                };
+
+                let (_, _, offer_index, ask_index, _, _) =
+                    get_asset_indexes_in_pool(pool_info, token_in_denom, token_out_denom)?;
+
+                let swap_computation =
+                    helpers::compute_swap_for_pool(pool_info, offer_index, ask_index, amount)?;
+                helpers::apply_swap_to_pool(
+                    pool_info,
+                    offer_index,
+                    ask_index,
+                    amount,
+                    &swap_computation,
                 )?;
-                amount = res.return_amount;
+
+                amount = swap_computation.return_amount;
             }
         }
     }
 
-    Ok(SimulateSwapOperationsResponse { amount })
+    Ok(amount)
 }
 
 /// This function iterates over the swap operations in the reverse order,
 /// simulates each swap to get the final amount after all the swaps.
 pub fn reverse_simulate_swap_operations(
     deps: Deps,
+    env: Env,
     ask_amount: Uint128,
     operations: Vec<SwapOperation>,
 ) -> Result<SimulateSwapOperationsResponse, ContractError> {
@@ -274,6 +351,7 @@ pub fn reverse_simulate_swap_operations(
             } => {
                 let res = query_simulation(
                     deps,
+                    env.clone(),
                     coin(amount.u128(), token_out_denom),
                     token_in_denom,
                     pool_identifier,
@@ -285,3 +363,3945 @@ pub fn reverse_simulate_swap_operations(
 
     Ok(SimulateSwapOperationsResponse { amount })
 }
+
+/// Computes the swap that moves the pool's spot price of `base_denom`, expressed in
+/// `quote_denom`, to `target_price`. If the base asset is overpriced the base asset is offered,
+/// otherwise the quote asset is. Returns a zero-amount swap if the pool is already at the target.
+pub fn query_rebalance_swap(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    target_price: Decimal256,
+) -> Result<RebalanceResponse, ContractError> {
+    ensure!(!target_price.is_zero(), ContractError::InvalidTargetPrice);
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom.clone(), quote_denom.clone())?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+
+    let (offer_denom, offer_amount) = match spot_price.cmp(&target_price) {
+        Ordering::Equal => (base_denom, Uint128::zero()),
+        // the base asset is overpriced, sell it for the quote asset
+        Ordering::Greater => (
+            base_denom,
+            helpers::compute_offer_to_reach_price(
+                &pool_info,
+                base_index,
+                quote_index,
+                target_price,
+            )?,
+        ),
+        // the base asset is underpriced, buy it with the quote asset
+        Ordering::Less => (
+            quote_denom,
+            helpers::compute_offer_to_reach_price(
+                &pool_info,
+                quote_index,
+                base_index,
+                target_price
+                    .inv()
+                    .ok_or(ContractError::InvalidTargetPrice)?,
+            )?,
+        ),
+    };
+
+    Ok(RebalanceResponse {
+        offer_denom,
+        offer_amount,
+    })
+}
+
+/// The amount of basis points in a whole.
+const BASIS_POINTS: u128 = 10_000;
+
+/// Simulates a swap and measures its total cost against the fee-free spot price of the pool.
+/// Constant product and stableswap pools report spread against different references, so the
+/// cost here is derived from the return amount alone, making it comparable across pool types.
+pub fn query_canonical_simulation(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<CanonicalSimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, offer_index, ask_index)?;
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    let ideal_return = Decimal256::decimal_with_precision(offer_asset.amount, offer_decimal)?
+        .checked_mul(spot_price)?;
+    let actual_return =
+        Decimal256::decimal_with_precision(swap_computation.return_amount, ask_decimal)?;
+
+    let total_cost_bps = if ideal_return.is_zero() {
+        Decimal256::zero()
+    } else {
+        Decimal256::one()
+            .saturating_sub(actual_return.checked_div(ideal_return)?)
+            .checked_mul(Decimal256::from_ratio(BASIS_POINTS, 1u128))?
+    };
+
+    Ok(CanonicalSimulationResponse {
+        return_amount: swap_computation.return_amount,
+        total_cost_bps,
+    })
+}
+
+/// Computes the proportional share of the pool reserves `lp_amount` LP tokens are redeemable for,
+/// i.e. the assets a withdrawal of that amount would return.
+pub fn query_lp_value(
+    deps: Deps,
+    pool_identifier: String,
+    lp_amount: Uint128,
+) -> Result<LpValueResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool(deps, pool_identifier)?;
+
+    let assets = helpers::compute_lp_share_value(&pool_info, lp_amount, total_share.amount)?;
+
+    Ok(LpValueResponse { assets })
+}
+
+/// Simulates a swap with [query_simulation], failing with [ContractError::PriceSanityViolation]
+/// if the effective price of the swap, i.e. the normalized return amount per unit of offer asset,
+/// exceeds `max_reasonable_price`. Meant to catch mispriced or manipulated pools.
+pub fn query_simulation_sanity(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    max_reasonable_price: Decimal256,
+) -> Result<SimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let simulation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response();
+
+    if !offer_asset.amount.is_zero() {
+        let price = Decimal256::decimal_with_precision(simulation.return_amount, ask_decimal)?
+            .checked_div(Decimal256::decimal_with_precision(
+                offer_asset.amount,
+                offer_decimal,
+            )?)?;
+
+        ensure!(
+            price <= max_reasonable_price,
+            ContractError::PriceSanityViolation {
+                price,
+                max_price: max_reasonable_price,
+            }
+        );
+    }
+
+    Ok(simulation)
+}
+
+/// Counts the pools in the contract by pool type.
+///
+/// This iterates over every pool in storage, so its gas cost grows linearly with the amount of
+/// pools. It is intended for occasional administrative use, not to be called on every block.
+pub fn query_pool_type_stats(deps: Deps) -> Result<PoolTypeStatsResponse, ContractError> {
+    let mut stats = PoolTypeStatsResponse {
+        constant_product: 0,
+        stableswap: 0,
+    };
+
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, pool) = item?;
+
+        match pool.pool_type {
+            PoolType::ConstantProduct => stats.constant_product += 1,
+            PoolType::StableSwap { .. } => stats.stableswap += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Simulates providing liquidity to a two-asset pool with `offer_asset` only. Part of the offer is
+/// swapped into the other asset, then the remainder and the swap return are deposited. Rather than
+/// swapping half of the offer, the split is chosen so that the deposit matches the ratio of the
+/// reserves after the swap, which maximizes the LP tokens minted.
+pub fn query_simulate_zap_in(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_asset: Coin,
+) -> Result<ZapInResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    ensure!(
+        pool_info.assets.len() == 2,
+        ContractError::InvalidPoolAssetsForSingleSideLiquidityProvision
+    );
+    ensure!(
+        !pool_info.assets.iter().any(|asset| asset.amount.is_zero()),
+        ContractError::EmptyPoolForSingleSideLiquidityProvision
+    );
+
+    let offer_index = pool_info
+        .asset_denoms
+        .iter()
+        .position(|denom| denom == &offer_asset.denom)
+        .ok_or(ContractError::AssetMismatch)?;
+    let ask_index = 1 - offer_index;
+
+    let swap_amount =
+        helpers::compute_zap_in_swap_amount(&pool_info, offer_index, ask_index, offer_asset.amount);
+
+    let mut pool = pool_info.clone();
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool, offer_index, ask_index, swap_amount)?;
+    helpers::apply_swap_to_pool(
+        &mut pool,
+        offer_index,
+        ask_index,
+        swap_amount,
+        &swap_computation,
+    )?;
+
+    let deposits = vec![
+        coin(
+            offer_asset.amount.checked_sub(swap_amount)?.u128(),
+            offer_asset.denom,
+        ),
+        coin(
+            swap_computation.return_amount.u128(),
+            pool.asset_denoms[ask_index].clone(),
+        ),
+    ];
+
+    let lp_amount = helpers::compute_deposit_share(&pool, &deposits, total_share.amount)?;
+
+    Ok(ZapInResponse {
+        lp_amount,
+        swap_return: swap_computation.return_amount,
+    })
+}
+
+/// Simulates withdrawing `lp_amount` LP tokens from the pool and swapping every withdrawn asset
+/// other than `target_denom` into it. The swaps are performed one after the other against the
+/// reserves left after the withdrawal.
+pub fn query_simulate_zap_out(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    lp_amount: Uint128,
+    target_denom: String,
+) -> Result<ZapOutResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    let target_index = pool_info
+        .asset_denoms
+        .iter()
+        .position(|denom| denom == &target_denom)
+        .ok_or(ContractError::AssetMismatch)?;
+
+    let return_amount =
+        helpers::compute_zap_out_amount(&pool_info, lp_amount, total_share.amount, target_index)?;
+
+    Ok(ZapOutResponse { return_amount })
+}
+
+/// Gets the status of the amp ramp of a stableswap pool at the current block time. The current amp
+/// is the one swaps and deposits are priced with. Pools that never ramped, or whose ramp is over,
+/// report the current amp as the target with full progress.
+pub fn query_amp_ramp_status(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<AmpRampResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let PoolType::StableSwap { amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let now = env.block.time.seconds();
+
+    let (current_amp, target_amp, progress) =
+        match AMP_RAMPS.may_load(deps.storage, &pool_identifier)? {
+            Some(ramp) => (ramp.amp_at(now), ramp.target_amp, ramp.progress_at(now)),
+            None => (amp, amp, Decimal256::one()),
+        };
+
+    Ok(AmpRampResponse {
+        current_amp: Uint256::from(current_amp),
+        target_amp: Uint256::from(target_amp),
+        progress,
+    })
+}
+
+/// Checks the pool reserves against the LP token supply.
+///
+/// LP tokens are minted as the square root of the reserves product on constant product pools, and
+/// as D on stableswap pools. Deposits mint proportionally and withdrawals round in favor of the
+/// pool, while swap fees only grow the reserves, so the invariant computed from the reserves
+/// should never drop below the one implied by the total share. If it does, value has leaked out of
+/// the pool, e.g. through rounding accumulation.
+pub fn query_pool_integrity(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<IntegrityResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    let (expected_k, actual_k) = match &pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let mut expected_k = Uint256::one();
+            let mut actual_k = Uint256::one();
+
+            for asset in pool_info.assets.iter() {
+                expected_k = expected_k.checked_mul(total_share.amount.into())?;
+                actual_k = actual_k.checked_mul(asset.amount.into())?;
+            }
+
+            (expected_k, actual_k)
+        }
+        PoolType::StableSwap { amp } => {
+            let d = helpers::compute_d(amp, &pool_info.assets)
+                .ok_or(ContractError::StableInvariantError)?;
+
+            (
+                Uint256::from(total_share.amount),
+                Uint256::try_from(d).map_err(|_| ContractError::StableInvariantError)?,
+            )
+        }
+    };
+
+    Ok(IntegrityResponse {
+        consistent: actual_k >= expected_k,
+        expected_k,
+        actual_k,
+    })
+}
+
+/// Simulates a swap with [query_simulation], reducing the return amount by the worst-case
+/// rounding of the swap computation, in units of the ask asset.
+///
+/// The computation works at the greater precision of the offer and ask assets, so a truncation
+/// at that precision shifts the return amount by up to `10^(max_precision - ask_decimals)` units of
+/// the ask asset. The truncating steps of the curve are:
+/// - on constant product pools, the return amount `ask_pool * offer / (offer_pool + offer)` is
+///   floored once.
+/// - on stableswap pools, D converges within one unit and is then truncated, the new offer pool
+///   amount is truncated and the new ask pool amount converges within one unit and is floored,
+///   i.e. five steps.
+///
+/// On top of that each fee, i.e. the swap, protocol and burn fees plus every extra fee, is floored
+/// on its own from the return amount in the ask asset, costing at most one more unit each. The
+/// margin is thus `curve_steps * 10^(max_precision - ask_decimals) + fee_steps`.
+pub fn query_simulation_conservative(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<SimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let mut simulation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response();
+
+    let curve_steps = match pool_info.pool_type {
+        PoolType::ConstantProduct => 1u128,
+        PoolType::StableSwap { .. } => 5u128,
+    };
+    let fee_steps = 3u128 + pool_info.pool_fees.extra_fees.len() as u128;
+    let precision_step = Uint128::from(10u128)
+        .checked_pow(u32::from(offer_decimal.max(ask_decimal) - ask_decimal))?;
+
+    let margin = Uint128::new(curve_steps)
+        .checked_mul(precision_step)?
+        .checked_add(Uint128::new(fee_steps))?;
+    simulation.return_amount = simulation.return_amount.saturating_sub(margin);
+
+    Ok(simulation)
+}
+
+/// Gets the direction of trade in a two-asset pool whose fee-adjusted marginal rate is the better
+/// one relative to the balanced 1:1 price. If both directions are priced the same, the
+/// lexicographically smaller denom is offered.
+pub fn query_cheaper_direction(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<CheaperDirectionResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    ensure!(
+        pool_info.assets.len() == 2,
+        ContractError::InvalidPoolAssetsLength {
+            expected: 2,
+            actual: pool_info.assets.len(),
+        }
+    );
+
+    let fee_factor = Decimal256::one().checked_sub(pool_info.pool_fees.total_fee_share())?;
+    let forward_rate = helpers::compute_spot_price(&pool_info, 0, 1)?.checked_mul(fee_factor)?;
+    let backward_rate = helpers::compute_spot_price(&pool_info, 1, 0)?.checked_mul(fee_factor)?;
+
+    let (first_denom, second_denom) = (
+        pool_info.asset_denoms[0].clone(),
+        pool_info.asset_denoms[1].clone(),
+    );
+
+    let first_is_offer = match forward_rate.cmp(&backward_rate) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => first_denom <= second_denom,
+    };
+
+    let (offer_denom, ask_denom) = if first_is_offer {
+        (first_denom, second_denom)
+    } else {
+        (second_denom, first_denom)
+    };
+
+    Ok(CheaperDirectionResponse {
+        offer_denom,
+        ask_denom,
+    })
+}
+
+/// The amount of units in one LP token, as LP tokens have 6 decimals.
+const LP_TOKEN_UNIT: Uint128 = Uint128::new(1_000_000u128);
+
+/// Simulates how changing the amp of a stableswap pool to `new_amp` affects the value of one LP
+/// token. A proportional withdrawal doesn't depend on the amp, so the value is measured, for each
+/// asset, as the amount received when withdrawing one LP token and swapping everything into that
+/// asset, which is priced by the curve and thus by the amp.
+pub fn query_amp_change_lp_impact(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    new_amp: Uint256,
+) -> Result<AmpImpactResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    ensure!(
+        matches!(pool_info.pool_type, PoolType::StableSwap { .. }),
+        ContractError::NotStableSwapPool
+    );
+
+    let new_amp = Uint128::try_from(new_amp)
+        .ok()
+        .and_then(|amp| u64::try_from(amp.u128()).ok())
+        .filter(|amp| *amp > 0)
+        .ok_or(ContractError::InvalidAmp(new_amp))?;
+
+    let mut new_pool_info = pool_info.clone();
+    new_pool_info.pool_type = PoolType::StableSwap { amp: new_amp };
+
+    let lp_amount = LP_TOKEN_UNIT.min(total_share.amount);
+    let lp_value = |pool: &PoolInfo| {
+        (0..pool.assets.len())
+            .map(|index| {
+                Ok(coin(
+                    helpers::compute_zap_out_amount(pool, lp_amount, total_share.amount, index)?
+                        .u128(),
+                    pool.asset_denoms[index].clone(),
+                ))
+            })
+            .collect::<Result<Vec<Coin>, ContractError>>()
+    };
+
+    Ok(AmpImpactResponse {
+        lp_value_before: lp_value(&pool_info)?,
+        lp_value_after: lp_value(&new_pool_info)?,
+    })
+}
+
+/// Encodes the given swap operations into a compact binary format, replacing the denoms with
+/// their index within the pool.
+pub fn query_encode_route(
+    deps: Deps,
+    operations: Vec<SwapOperation>,
+) -> Result<EncodedRouteResponse, ContractError> {
+    Ok(EncodedRouteResponse {
+        encoded: helpers::encode_route(&deps, &operations)?,
+    })
+}
+
+/// Decodes swap operations encoded with [query_encode_route].
+pub fn query_decode_route(
+    deps: Deps,
+    encoded: Binary,
+) -> Result<Vec<SwapOperation>, ContractError> {
+    helpers::decode_route(&deps, &encoded)
+}
+
+/// Computes the fees the pool charges on a trade volume of `offer_amount`, split by destination.
+/// The swap fee is retained by the pool and thus earned by the liquidity providers.
+pub fn query_fee_split(
+    deps: Deps,
+    pool_identifier: String,
+    offer_amount: Uint128,
+) -> Result<FeeSplitResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let pool_fees = pool_info.pool_fees;
+
+    pool_fees.is_valid()?;
+
+    let amount = Uint256::from(offer_amount);
+
+    Ok(FeeSplitResponse {
+        lp_fee: pool_fees.swap_fee.compute(amount)?.try_into()?,
+        protocol_fee: pool_fees.protocol_fee.compute(amount)?.try_into()?,
+        burn_fee: pool_fees.burn_fee.compute(amount)?.try_into()?,
+    })
+}
+
+/// Computes how much more of each asset the pool can hold, the other reserves staying the same,
+/// before the invariant computation of its pool type overflows.
+pub fn query_capacity_headroom(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<CapacityHeadroomResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    let max_additional_per_asset = (0..pool_info.assets.len())
+        .map(|index| {
+            Ok(coin(
+                helpers::compute_capacity_headroom(&pool_info, index)?.u128(),
+                pool_info.assets[index].denom.clone(),
+            ))
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?;
+
+    Ok(CapacityHeadroomResponse {
+        max_additional_per_asset,
+    })
+}
+
+/// Simulates a swap and returns the reserves of the pool after applying it. The reserves are
+/// updated the same way the swap execution does, so the swap fee is retained by the pool while the
+/// protocol and burn fees leave it.
+pub fn query_simulation_with_reserves_out(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<SimulationWithReservesResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    helpers::apply_swap_to_pool(
+        &mut pool_info,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        &swap_computation,
+    )?;
+
+    Ok(SimulationWithReservesResponse {
+        simulation: swap_computation.to_simulation_response(),
+        reserves_after: pool_info.assets,
+    })
+}
+
+/// Samples the output curve of a pool, i.e. the return amount of swapping `offer_denom` for
+/// `ask_denom` at `steps` evenly spaced offer amounts, the last of which is `max_offer`. The amount
+/// of steps is capped at [MAX_LIMIT]. Routers splitting a trade across pools can use the curves to
+/// allocate the offer amount client-side.
+pub fn query_marginal_output_curve(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    steps: u32,
+    max_offer: Uint128,
+) -> Result<MarginalCurveResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    let steps = steps.min(MAX_LIMIT);
+
+    let points = (1..=steps)
+        .map(|step| {
+            let offer_amount = max_offer.multiply_ratio(step, steps);
+            let swap_computation =
+                helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)?;
+
+            Ok((offer_amount, swap_computation.return_amount))
+        })
+        .collect::<Result<Vec<(Uint128, Uint128)>, ContractError>>()?;
+
+    Ok(MarginalCurveResponse { points })
+}
+
+/// Validates a pool creation specification the same way pool creation does, reporting every
+/// failure instead of only the first one. The pool creation fee payment is not checked.
+pub fn query_validate_pool_spec(
+    asset_denoms: Vec<String>,
+    asset_decimals: Vec<u8>,
+    pool_type: PoolType,
+    pool_fees: PoolFee,
+) -> ValidatePoolResponse {
+    let errors = validate_pool_spec(&asset_denoms, &asset_decimals, &pool_type, &pool_fees)
+        .into_iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<String>>();
+
+    ValidatePoolResponse {
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+/// Gets the asset denoms of a pool in the exact order they are stored in. This is the canonical
+/// ordering of the pool: asset indexes, reserves and the amounts returned when withdrawing
+/// liquidity all follow it, so clients building deposits should match it as well.
+pub fn query_canonical_asset_order(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<Vec<String>, ContractError> {
+    Ok(get_pool_by_identifier(&deps, &pool_identifier)?.asset_denoms)
+}
+
+/// Simulates a swap on a stableswap pool using the lesser of the amp swaps currently execute with
+/// and the target amp of its ramp. A lower amp means a higher slippage, so this is the most
+/// conservative output the pool can give while the amp is ramping. Only stableswap pools are
+/// supported.
+pub fn query_simulation_min_amp(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<SimulationResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let PoolType::StableSwap { amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let min_amp = match AMP_RAMPS.may_load(deps.storage, &pool_identifier)? {
+        Some(ramp) => amp.min(ramp.target_amp),
+        None => amp,
+    };
+    pool_info.pool_type = PoolType::StableSwap { amp: min_amp };
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    Ok(
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response(),
+    )
+}
+
+/// Computes the amount of `offer_denom` to swap for `ask_denom` so that the spot price of the
+/// offer asset, expressed in the ask asset, drops to `target_price`. Returns zero if the pool is
+/// already at or past the target in that direction.
+pub fn query_offer_to_reach_price(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    target_price: Decimal256,
+) -> Result<Uint128, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    helpers::compute_offer_to_reach_price(&pool_info, offer_index, ask_index, target_price)
+}
+
+/// Gets the identifiers of the shortest chain of pools connecting `denom` to `base_denom`, which
+/// are the pools to monitor to price `denom` against `base_denom`. Returns an empty vec if no path
+/// exists within `max_hops` pools, capped at [helpers::MAX_REACHABLE_HOPS].
+pub fn query_pricing_path(
+    deps: Deps,
+    denom: String,
+    base_denom: String,
+    max_hops: u32,
+) -> Result<Vec<String>, ContractError> {
+    helpers::find_shortest_pool_path(&deps, &denom, &base_denom, max_hops)
+}
+
+/// Computes the spread at which a round-trip swap of `offer_asset`, offer to ask and back, breaks
+/// even. Both legs are simulated the way they'd execute, the second one on the pool left by the
+/// first, so the spread recovers the fees, the price impact and the rounding of the round trip:
+/// `offer / returned - 1`, or zero if the round trip returns more than offered. Below this spread,
+/// arbitrage through the pool loses money.
+pub fn query_breakeven_spread(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_asset: Coin,
+    ask_denom: String,
+) -> Result<Decimal256, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_denom)?;
+
+    let forward =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    helpers::apply_swap_to_pool(
+        &mut pool_info,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        &forward,
+    )?;
+    let backward =
+        helpers::compute_swap_for_pool(&pool_info, ask_index, offer_index, forward.return_amount)?;
+
+    Ok(
+        Decimal256::checked_from_ratio(offer_asset.amount, backward.return_amount)?
+            .saturating_sub(Decimal256::one()),
+    )
+}
+
+/// Checks whether a swap would succeed if executed, running the same assertions the swap
+/// execution does on the pool priced the same way, see [get_pool_at]. Reports the first failing
+/// assertion, if any.
+pub fn query_swap_would_succeed(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    min_receive: Uint128,
+    max_spread: Decimal256,
+) -> SwapCheckResponse {
+    match check_swap(
+        deps,
+        env,
+        offer_asset,
+        ask_asset_denom,
+        pool_identifier,
+        min_receive,
+        max_spread,
+    ) {
+        Ok(()) => SwapCheckResponse {
+            would_succeed: true,
+            reason: None,
+        },
+        Err(err) => SwapCheckResponse {
+            would_succeed: false,
+            reason: Some(err.to_string()),
+        },
+    }
+}
+
+/// Runs the assertions performed when executing a swap, without changing any state.
+fn check_swap(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    min_receive: Uint128,
+    max_spread: Decimal256,
+) -> Result<(), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    ensure!(
+        config.feature_toggle.swaps_enabled,
+        ContractError::OperationDisabled("swap".to_string())
+    );
+
+    ensure!(
+        offer_asset.denom != ask_asset_denom,
+        ContractError::SameAsset
+    );
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    ensure!(
+        pool_info.assets.iter().all(|asset| !asset.amount.is_zero()),
+        ContractError::PoolHasNoAssets
+    );
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    // spreads beyond the range of a Decimal are capped to the maximum allowed slippage anyway
+    assert_max_spread(
+        None,
+        Some(Decimal::try_from(max_spread).unwrap_or(Decimal::MAX)),
+        offer_asset.amount,
+        swap_computation.return_amount,
+        swap_computation.spread_amount,
+    )?;
+
+    ensure!(
+        swap_computation.return_amount >= min_receive,
+        ContractError::MinimumReceiveAssertion {
+            minimum_receive: min_receive,
+            swap_amount: swap_computation.return_amount,
+        }
+    );
+
+    Ok(())
+}
+
+/// Computes the deviation of the spot price of `base_denom` in `quote_denom` from the 1:1 peg, in
+/// basis points, rounded towards zero. The spot price is normalized by the asset decimals. Only
+/// stableswap pools are supported, as 1:1 isn't a meaningful reference for constant product ones.
+pub fn query_peg_deviation(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<PegDeviationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    ensure!(
+        matches!(pool_info.pool_type, PoolType::StableSwap { .. }),
+        ContractError::NotStableSwapPool
+    );
+
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+
+    Ok(PegDeviationResponse {
+        deviation_bps: signed_deviation_bps(spot_price, Decimal256::one())?,
+    })
+}
+
+/// Computes the relative deviation of `value` from `reference` in basis points, rounded towards
+/// zero. Positive when `value` is above `reference`, saturating at the bounds of an [i64].
+fn signed_deviation_bps(value: Decimal256, reference: Decimal256) -> Result<i64, ContractError> {
+    let deviation = value
+        .abs_diff(reference)
+        .checked_mul(Decimal256::from_ratio(BASIS_POINTS, 1u128))?
+        .checked_div(reference)?
+        .to_uint_floor();
+    let deviation_bps = Uint128::try_from(deviation)
+        .ok()
+        .and_then(|deviation| i64::try_from(deviation.u128()).ok())
+        .unwrap_or(i64::MAX);
+
+    Ok(if value < reference {
+        -deviation_bps
+    } else {
+        deviation_bps
+    })
+}
+
+/// Simulates swapping each offer asset into `ask_denom` through its route, e.g. to liquidate a
+/// basket of assets. Each route must start with its offer asset and end in `ask_denom`. The routes
+/// are simulated one after the other, so routes sharing a pool see the reserves left by the
+/// previous ones, as they would if the swaps were executed in sequence.
+pub fn query_simulation_many_to_one(
+    deps: Deps,
+    env: Env,
+    offer_assets: Vec<Coin>,
+    ask_denom: String,
+    pool_routes: Vec<Vec<SwapOperation>>,
+) -> Result<ManyToOneResponse, ContractError> {
+    ensure!(
+        offer_assets.len() == pool_routes.len(),
+        ContractError::RouteCountMismatch {
+            offer_assets: offer_assets.len(),
+            routes: pool_routes.len(),
+        }
+    );
+
+    let mut pools: HashMap<String, PoolInfo> = HashMap::new();
+    let mut per_asset = Vec::with_capacity(offer_assets.len());
+
+    for (offer_asset, route) in offer_assets.into_iter().zip(pool_routes) {
+        validate_route(&route, &offer_asset.denom, &ask_denom)?;
+
+        per_asset.push(simulate_operations_in_memory(
+            deps,
+            &env,
+            &mut pools,
+            offer_asset.amount,
+            route,
+        )?);
+    }
+
+    let total_return = per_asset
+        .iter()
+        .try_fold(Uint128::zero(), |acc, amount| acc.checked_add(*amount))?;
+
+    Ok(ManyToOneResponse {
+        total_return,
+        per_asset,
+    })
+}
+
+/// Validates that the swap operations of `route` are consecutive, starting with `offer_denom` and
+/// ending in `ask_denom`.
+fn validate_route(
+    route: &[SwapOperation],
+    offer_denom: &str,
+    ask_denom: &str,
+) -> Result<(), ContractError> {
+    assert_operations(route.to_vec())?;
+
+    // the route was checked not to be empty above
+    let first_input = route[0].get_input_asset_info();
+    ensure!(
+        first_input == offer_denom,
+        ContractError::NonConsecutiveSwapOperations {
+            previous_output: offer_denom.to_string(),
+            next_input: first_input.clone(),
+        }
+    );
+
+    let last_output = route[route.len() - 1].get_target_asset_info();
+    ensure!(
+        last_output == ask_denom,
+        ContractError::NonConsecutiveSwapOperations {
+            previous_output: last_output,
+            next_input: ask_denom.to_string(),
+        }
+    );
+
+    Ok(())
+}
+
+/// Simulates a swap and converts its swap fee, charged in the ask asset, into the offer asset using
+/// the pool's spot price before the swap. Both assets are normalized by their decimals for the
+/// conversion, and the result is rounded down.
+pub fn query_swap_fee_in_offer(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<SwapFeeInOfferResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    let spot_price = helpers::compute_spot_price(&pool_info, offer_index, ask_index)?;
+
+    let swap_fee_in_offer =
+        Decimal256::decimal_with_precision(swap_computation.swap_fee_amount, ask_decimal)?
+            .checked_div(spot_price)?
+            .to_uint256_with_precision(u32::from(offer_decimal))?;
+
+    Ok(SwapFeeInOfferResponse {
+        swap_fee_amount: swap_computation.swap_fee_amount,
+        swap_fee_in_offer: Uint128::try_from(swap_fee_in_offer)?,
+    })
+}
+
+/// Gets the reserves of the pool after withdrawing its accumulated protocol fees.
+///
+/// Protocol fees are not held by the pool: they are deducted from the reserves and sent to the fee
+/// collector as part of every swap, same as the burn fees. Withdrawing them thus leaves the
+/// reserves unchanged, and the current reserves are returned.
+pub fn query_post_fee_withdrawal_reserves(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<Vec<Coin>, ContractError> {
+    Ok(get_pool_by_identifier(&deps, &pool_identifier)?.assets)
+}
+
+/// Computes the largest amount of `offer_denom` that can be swapped for `ask_denom` while the
+/// price impact, i.e. the relative drop of the offer asset's spot price after the swap, stays
+/// within `max_impact_bps`. Unlike the execution spread, the price impact measures how far the
+/// swap moves the pool's marginal price. Returns zero if a single unit exceeds the limit.
+pub fn query_safe_trade_size(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    max_impact_bps: u16,
+) -> Result<Uint128, ContractError> {
+    ensure!(
+        u128::from(max_impact_bps) < BASIS_POINTS,
+        ContractError::InvalidMaxPriceImpact(max_impact_bps)
+    );
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    if max_impact_bps == 0 {
+        return Ok(Uint128::zero());
+    }
+
+    let spot_price = helpers::compute_spot_price(&pool_info, offer_index, ask_index)?;
+    let min_price = spot_price.checked_mul(Decimal256::from_ratio(
+        BASIS_POINTS - u128::from(max_impact_bps),
+        BASIS_POINTS,
+    ))?;
+
+    let offer_amount =
+        helpers::compute_offer_to_reach_price(&pool_info, offer_index, ask_index, min_price)?;
+
+    // the offer amount can land right past the limit due to rounding, step back if it does
+    let mut pool = pool_info.clone();
+    let swap = helpers::compute_swap_for_pool(&pool, offer_index, ask_index, offer_amount)?;
+    helpers::apply_swap_to_pool(&mut pool, offer_index, ask_index, offer_amount, &swap)?;
+
+    if helpers::compute_spot_price(&pool, offer_index, ask_index)? < min_price {
+        return Ok(offer_amount.saturating_sub(Uint128::one()));
+    }
+
+    Ok(offer_amount)
+}
+
+/// Gets the pools sharing at least one asset denom with the given pool, excluding the pool itself.
+/// These are the pools a route can continue through after swapping in the given pool.
+pub fn query_adjacent_pools(
+    deps: Deps,
+    pool_identifier: String,
+    limit: Option<u32>,
+) -> Result<PoolsResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let pools = POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, pool)| {
+                pool.pool_identifier != pool_info.pool_identifier
+                    && pool
+                        .asset_denoms
+                        .iter()
+                        .any(|denom| pool_info.asset_denoms.contains(denom))
+            })
+        })
+        .take(limit)
+        .map(|item| {
+            let (_, pool) = item?;
+            let total_share = deps.querier.query_supply(&pool.lp_denom)?;
+
+            Ok(PoolInfoResponse {
+                pool_info: pool,
+                total_share,
+            })
+        })
+        .collect::<StdResult<Vec<PoolInfoResponse>>>()?;
+
+    Ok(PoolsResponse { pools })
+}
+
+/// Simulates swapping `probe_amount` of each asset of the pool into each of the other ones,
+/// against the current reserves. Returns an entry per ordered pair of distinct assets, i.e.
+/// N * (N - 1) entries for a pool with N assets, in the pool's asset order.
+pub fn query_price_matrix(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    probe_amount: Uint128,
+) -> Result<PriceMatrixResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let assets_len = pool_info.assets.len();
+
+    let mut entries = Vec::with_capacity(assets_len * assets_len.saturating_sub(1));
+    for offer_index in 0..assets_len {
+        for ask_index in (0..assets_len).filter(|ask_index| *ask_index != offer_index) {
+            let swap_computation =
+                helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, probe_amount)?;
+
+            entries.push((
+                pool_info.assets[offer_index].denom.clone(),
+                pool_info.assets[ask_index].denom.clone(),
+                swap_computation.return_amount,
+            ));
+        }
+    }
+
+    Ok(PriceMatrixResponse { entries })
+}
+
+/// Computes the amp at which the stableswap curve of the pool approximates the constant product
+/// curve at the current reserves. This is an approximation, see
+/// [helpers::compute_constant_product_equivalent_amp]. Returns the minimum amp if the ideal value
+/// is below it, which is the case for pools near balance.
+pub fn query_constant_product_equivalent_amp(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<Uint256, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    helpers::compute_constant_product_equivalent_amp(&pool_info)
+}
+
+/// Simulates a sandwich attack on a swap: the attacker front-runs it by swapping
+/// `attacker_capital` of the offer asset in the same direction, the swap executes at the worse
+/// price, and the attacker swaps the ask asset back right after. Returns the attacker's profit in
+/// the offer asset, zero if the attack isn't profitable, e.g. because of the fees.
+pub fn query_sandwich_risk(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    attacker_capital: Uint128,
+) -> Result<SandwichRiskResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    // front-run
+    let front_run =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, attacker_capital)?;
+    helpers::apply_swap_to_pool(
+        &mut pool_info,
+        offer_index,
+        ask_index,
+        attacker_capital,
+        &front_run,
+    )?;
+
+    // victim's swap
+    let swap =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    helpers::apply_swap_to_pool(
+        &mut pool_info,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        &swap,
+    )?;
+
+    // back-run
+    let back_run = helpers::compute_swap_for_pool(
+        &pool_info,
+        ask_index,
+        offer_index,
+        front_run.return_amount,
+    )?;
+
+    Ok(SandwichRiskResponse {
+        max_extractable: back_run.return_amount.saturating_sub(attacker_capital),
+    })
+}
+
+/// Gets the stableswap pools whose amp ramp window includes the current block time, i.e. the ones
+/// whose trading amp is moving. Each pool is returned with the amp swaps currently execute with.
+/// Pools that never ramped, or whose ramp is over, are skipped.
+pub fn query_ramping_pools(
+    deps: Deps,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<PoolsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+
+    let mut pools = vec![];
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        if pools.len() == limit {
+            break;
+        }
+
+        let (_, mut pool) = item?;
+        let PoolType::StableSwap { amp } = &mut pool.pool_type else {
+            continue;
+        };
+
+        let Some(ramp) = AMP_RAMPS
+            .may_load(deps.storage, &pool.pool_identifier)?
+            .filter(|ramp| ramp.start_time <= now && now < ramp.end_time)
+        else {
+            continue;
+        };
+        *amp = ramp.amp_at(now);
+
+        let total_share = deps.querier.query_supply(&pool.lp_denom)?;
+        pools.push(PoolInfoResponse {
+            pool_info: pool,
+            total_share,
+        });
+    }
+
+    Ok(PoolsResponse { pools })
+}
+
+/// Computes the concentration index of the pool's liquidity across its assets, from the reserves
+/// normalized by their decimals. The index is the Herfindahl index of the reserve shares,
+/// `Σ(s_i^2)`, rescaled from `[1 / n, 1]` to `[0, 1]`, so it's 0 for a perfectly balanced pool and
+/// approaches 1 as the liquidity concentrates in a single asset.
+pub fn query_liquidity_concentration(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<ConcentrationResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let normalized_reserves = pool_info
+        .assets
+        .iter()
+        .zip(pool_info.asset_decimals.iter())
+        .map(|(asset, decimals)| Decimal256::decimal_with_precision(asset.amount, *decimals))
+        .collect::<Result<Vec<Decimal256>, ContractError>>()?;
+
+    let total = normalized_reserves
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, reserve| acc.checked_add(*reserve))?;
+    ensure!(!total.is_zero(), ContractError::PoolHasNoAssets);
+
+    let herfindahl_index =
+        normalized_reserves
+            .iter()
+            .try_fold(Decimal256::zero(), |acc, reserve| {
+                let share = reserve.checked_div(total)?;
+                Ok::<_, ContractError>(acc.checked_add(share.checked_mul(share)?)?)
+            })?;
+
+    let min_index = Decimal256::from_ratio(1u128, normalized_reserves.len() as u128);
+    let index = herfindahl_index
+        .saturating_sub(min_index)
+        .checked_div(Decimal256::one().checked_sub(min_index)?)?;
+
+    Ok(ConcentrationResponse { index })
+}
+
+/// Simulates a swap after applying the pending deposits and withdrawals to an in-memory copy of
+/// the pool reserves, e.g. to preview the price after a batch is processed. Deposits are applied
+/// before withdrawals, which can't exceed the resulting reserves.
+pub fn query_simulation_with_pending(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    pending_deposits: Vec<Coin>,
+    pending_withdrawals: Vec<Coin>,
+) -> Result<SimulationResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    for deposit in pending_deposits {
+        let reserve = pool_info
+            .assets
+            .iter_mut()
+            .find(|asset| asset.denom == deposit.denom)
+            .ok_or(ContractError::AssetMismatch)?;
+        reserve.amount = reserve.amount.checked_add(deposit.amount)?;
+    }
+
+    for withdrawal in pending_withdrawals {
+        let reserve = pool_info
+            .assets
+            .iter_mut()
+            .find(|asset| asset.denom == withdrawal.denom)
+            .ok_or(ContractError::AssetMismatch)?;
+        reserve.amount = reserve.amount.checked_sub(withdrawal.amount).map_err(|_| {
+            ContractError::PendingWithdrawalExceedsReserves {
+                denom: withdrawal.denom.clone(),
+            }
+        })?;
+    }
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    Ok(
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response(),
+    )
+}
+
+/// Computes the maximum amount of `ask_denom` obtainable by swapping `offer_denom`, net of fees.
+///
+/// For constant product pools this is the asymptote of the curve: the whole ask reserve less the
+/// fees, which is approached as the offer grows but never reached. For stableswap pools the offer
+/// is bounded by the capacity of the pool, see [helpers::compute_capacity_headroom], and the
+/// output of swapping that amount is returned.
+pub fn query_max_possible_output(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+) -> Result<Uint128, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, ask_asset, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    match pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let fee_factor =
+                Decimal256::one().checked_sub(pool_info.pool_fees.total_fee_share())?;
+
+            Ok(Uint128::try_from(
+                Uint256::from(ask_asset.amount).mul_floor(fee_factor),
+            )?)
+        }
+        PoolType::StableSwap { .. } => {
+            let max_offer = helpers::compute_capacity_headroom(&pool_info, offer_index)?;
+
+            Ok(
+                helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, max_offer)?
+                    .return_amount,
+            )
+        }
+    }
+}
+
+/// Compares swapping the offer asset for `ask_asset_denom` directly in `direct_pool` against
+/// swapping it through `route`, which must start with the offer asset and end in
+/// `ask_asset_denom`. The direct swap is considered cheaper if both give the same output.
+pub fn query_direct_vs_route(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    direct_pool: String,
+    route: Vec<SwapOperation>,
+) -> Result<DirectVsRouteResponse, ContractError> {
+    validate_route(&route, &offer_asset.denom, &ask_asset_denom)?;
+
+    let route_output =
+        simulate_swap_operations(deps, env.clone(), offer_asset.amount, route)?.amount;
+    let direct_output =
+        query_simulation(deps, env.clone(), offer_asset, ask_asset_denom, direct_pool)?
+            .return_amount;
+
+    Ok(DirectVsRouteResponse {
+        direct_output,
+        route_output,
+        cheaper: if route_output > direct_output {
+            DirectOrRoute::Route
+        } else {
+            DirectOrRoute::Direct
+        },
+    })
+}
+
+/// Simulates a swap and compares its execution price, i.e. the amount of ask asset received per
+/// offer asset normalized by their decimals, against a reference price provided by the caller, e.g.
+/// from an external oracle. The premium is positive when the swap returns less than the reference
+/// price would, i.e. when the pool is more expensive.
+pub fn query_simulation_vs_reference(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    reference_price: Decimal256,
+) -> Result<ReferenceSimulationResponse, ContractError> {
+    ensure!(
+        !reference_price.is_zero(),
+        ContractError::InvalidTargetPrice
+    );
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    let execution_price =
+        Decimal256::decimal_with_precision(swap_computation.return_amount, ask_decimal)?
+            .checked_div(Decimal256::decimal_with_precision(
+                offer_asset.amount,
+                offer_decimal,
+            )?)?;
+
+    Ok(ReferenceSimulationResponse {
+        return_amount: swap_computation.return_amount,
+        premium_or_discount_bps: signed_deviation_bps(execution_price, reference_price)?
+            .saturating_neg(),
+    })
+}
+
+/// The number of increments [query_simulate_until_price] splits the total offer into.
+const CASCADE_INCREMENTS: u128 = 100;
+
+/// Simulates selling `total_offer` in [CASCADE_INCREMENTS] equal increments, each executed against
+/// the reserves left by the previous ones. Before each increment the marginal price of the offer
+/// asset is checked, and the selling stops once it has dropped below `stop_price`.
+pub fn query_simulate_until_price(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_asset_denom: String,
+    ask_asset_denom: String,
+    total_offer: Uint128,
+    stop_price: Decimal256,
+) -> Result<CascadeResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset_denom, ask_asset_denom)?;
+
+    let mut executed_offer = Uint128::zero();
+    let mut return_amount = Uint128::zero();
+
+    for increment in 1..=CASCADE_INCREMENTS {
+        if helpers::compute_spot_price(&pool_info, offer_index, ask_index)? < stop_price {
+            break;
+        }
+
+        // spread the remainder of the division across the increments
+        let offer_amount =
+            total_offer.multiply_ratio(increment, CASCADE_INCREMENTS) - executed_offer;
+        if offer_amount.is_zero() {
+            continue;
+        }
+
+        let swap_computation =
+            helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)?;
+        helpers::apply_swap_to_pool(
+            &mut pool_info,
+            offer_index,
+            ask_index,
+            offer_amount,
+            &swap_computation,
+        )?;
+
+        executed_offer = executed_offer.checked_add(offer_amount)?;
+        return_amount = return_amount.checked_add(swap_computation.return_amount)?;
+    }
+
+    Ok(CascadeResponse {
+        executed_offer,
+        return_amount,
+    })
+}
+
+/// Samples the effective fee stableswap pools charge on imbalanced deposits. The pool doesn't
+/// charge an explicit fee, but the invariant grows by less than the deposited amount when the
+/// deposit moves the pool away from balance, which is what LPs pay for the imbalance.
+///
+/// The samples are single-sided deposits of the pool's first asset, going from a fraction of its
+/// reserve up to the whole reserve. Each point pairs the deposit's size relative to the pool's total
+/// reserves with `1 - ΔD / deposit`, the share of the deposit that isn't credited to the depositor.
+/// The number of points is capped at [MAX_LIMIT].
+pub fn query_imbalance_fee_schedule(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    sample_points: u32,
+) -> Result<ImbalanceFeeResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let PoolType::StableSwap { amp: amp_factor } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let total_reserves = pool_info
+        .assets
+        .iter()
+        .try_fold(Uint128::zero(), |acc, asset| acc.checked_add(asset.amount))?;
+    ensure!(
+        !pool_info.assets.iter().any(|asset| asset.amount.is_zero()),
+        ContractError::PoolHasNoAssets
+    );
+
+    let d_0 = helpers::compute_d(&amp_factor, &pool_info.assets)
+        .ok_or(ContractError::StableInvariantError)?;
+
+    let sample_points = sample_points.min(MAX_LIMIT);
+    let mut points = Vec::with_capacity(sample_points as usize);
+
+    for point in 1..=sample_points {
+        let deposit = pool_info.assets[0]
+            .amount
+            .multiply_ratio(point, sample_points);
+        if deposit.is_zero() {
+            continue;
+        }
+
+        let mut new_assets = pool_info.assets.clone();
+        new_assets[0].amount = new_assets[0].amount.checked_add(deposit)?;
+
+        let d_1 = helpers::compute_d(&amp_factor, &new_assets)
+            .ok_or(ContractError::StableInvariantError)?;
+
+        // the invariant may grow by a unit more than the deposit because of the rounding
+        let credited = Uint128::try_from(d_1.saturating_sub(d_0))
+            .unwrap_or(Uint128::MAX)
+            .min(deposit);
+
+        points.push((
+            Decimal256::from_ratio(deposit, total_reserves),
+            Decimal256::one().checked_sub(Decimal256::from_ratio(credited, deposit))?,
+        ));
+    }
+
+    Ok(ImbalanceFeeResponse { points })
+}
+
+/// Computes the smallest amount of LP tokens to withdraw to get at least `desired.amount` of
+/// `desired.denom`. On constant product pools the withdrawal is proportional, so the amount is
+/// given by the closed form `desired.amount * total_share / reserve`, rounded up. On stableswap
+/// pools the withdrawal is single-sided through [query_simulate_zap_out], which has no closed form
+/// inverse but is monotonic in the LP amount, so a bounded binary search is performed over the
+/// total share.
+pub fn query_lp_to_withdraw(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    desired: Coin,
+) -> Result<Uint128, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    let target_index = pool_info
+        .asset_denoms
+        .iter()
+        .position(|denom| denom == &desired.denom)
+        .ok_or(ContractError::AssetMismatch)?;
+
+    let withdrawal_exceeds_reserves = || ContractError::WithdrawalExceedsReserves {
+        denom: desired.denom.clone(),
+        amount: desired.amount,
+    };
+    let reserve = pool_info.assets[target_index].amount;
+    ensure!(desired.amount < reserve, withdrawal_exceeds_reserves());
+
+    if pool_info.pool_type == PoolType::ConstantProduct {
+        let reserve = Uint256::from(reserve);
+        let lp_amount = Uint256::from(desired.amount)
+            .checked_mul(total_share.amount.into())?
+            .checked_add(reserve.checked_sub(Uint256::one())?)?
+            .checked_div(reserve)?;
+
+        return Ok(Uint128::try_from(lp_amount)?);
+    }
+
+    let returns_desired = |lp_amount: Uint128| {
+        helpers::compute_zap_out_amount(&pool_info, lp_amount, total_share.amount, target_index)
+            .is_ok_and(|return_amount| return_amount >= desired.amount)
+    };
+
+    let lp_amount =
+        helpers::binary_search_amount(Uint128::zero(), total_share.amount, returns_desired);
+
+    // the search returns the upper bound when no amount satisfies the predicate
+    ensure!(returns_desired(lp_amount), withdrawal_exceeds_reserves());
+
+    Ok(lp_amount)
+}
+
+/// Computes the swap through the pool that leaves the holder of `balance_a` and `balance_b` with
+/// equal value of both denoms, accounting for the price impact of the swap itself. The asset worth
+/// more at the current spot price is the one offered.
+pub fn query_rebalance_to_equal(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    balance_a: Coin,
+    balance_b: Coin,
+) -> Result<RebalanceResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, a_index, b_index, a_decimal, b_decimal) =
+        get_asset_indexes_in_pool(&pool_info, balance_a.denom.clone(), balance_b.denom.clone())?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, a_index, b_index)?;
+    let a_value =
+        Decimal256::decimal_with_precision(balance_a.amount, a_decimal)?.checked_mul(spot_price)?;
+    let b_value = Decimal256::decimal_with_precision(balance_b.amount, b_decimal)?;
+
+    let (offer, offer_index, ask, ask_index) = if a_value >= b_value {
+        (balance_a, a_index, balance_b, b_index)
+    } else {
+        (balance_b, b_index, balance_a, a_index)
+    };
+
+    Ok(RebalanceResponse {
+        offer_amount: helpers::compute_rebalance_to_equal_amount(
+            &pool_info,
+            offer_index,
+            ask_index,
+            offer.amount,
+            ask.amount,
+        ),
+        offer_denom: offer.denom,
+    })
+}
+
+/// Estimates the fee revenue of a pool over the given trade sizes. Each trade swaps the pool's
+/// first asset into its second one against the current reserves, independently of the others, and
+/// the fees are accumulated in the second asset. Only the first [MAX_LIMIT] trades are simulated.
+pub fn query_revenue_over_distribution(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    trade_sizes: Vec<Uint128>,
+) -> Result<RevenueDistributionResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+
+    trade_sizes.into_iter().take(MAX_LIMIT as usize).try_fold(
+        RevenueDistributionResponse {
+            total_swap_fee: Uint128::zero(),
+            total_protocol_fee: Uint128::zero(),
+        },
+        |acc, trade_size| {
+            let swap_computation = helpers::compute_swap_for_pool(&pool_info, 0, 1, trade_size)?;
+
+            Ok(RevenueDistributionResponse {
+                total_swap_fee: acc
+                    .total_swap_fee
+                    .checked_add(swap_computation.swap_fee_amount)?,
+                total_protocol_fee: acc
+                    .total_protocol_fee
+                    .checked_add(swap_computation.protocol_fee_amount)?,
+            })
+        },
+    )
+}
+
+/// Computes the band of external prices, for the pool's first asset in terms of its second, within
+/// which arbitraging the pool isn't profitable if its total fee were `new_total_fee`. Selling into
+/// the pool keeps `1 - fee` of the output and buying from it costs `1 / (1 - fee)` of the input, so
+/// the band spans from `spot * (1 - fee)` to `spot / (1 - fee)`.
+pub fn query_band_width_for_fees(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    new_total_fee: Decimal,
+) -> Result<BandWidthResponse, ContractError> {
+    Fee {
+        share: new_total_fee,
+    }
+    .is_valid()?;
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let spot_price = helpers::compute_spot_price(&pool_info, 0, 1)?;
+
+    let retention = Decimal256::one().checked_sub(Decimal256::from(new_total_fee))?;
+
+    Ok(BandWidthResponse {
+        lower: spot_price.checked_mul(retention)?,
+        upper: spot_price.checked_div(retention)?,
+    })
+}
+
+/// Computes the spot price of `base_denom` in `quote_denom` as a fraction reduced to its lowest
+/// terms, so it can be used in integer arithmetic without rounding. Both assets are normalized by
+/// their decimals. For constant product pools this is the exact ratio of the reserves, while for
+/// stableswap pools it's the ratio of the partial derivatives of the invariant.
+pub fn query_mid_price_rational(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<RationalPriceResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let (numerator, denominator) =
+        helpers::compute_spot_price_fraction(&pool_info, base_index, quote_index)?;
+
+    Ok(RationalPriceResponse {
+        numerator,
+        denominator,
+    })
+}
+
+/// Simulates a swap and computes the swap fee it leaves in the pool for the LPs, both as an amount
+/// of the ask asset and in basis points of the pool's total value locked. The TVL is valued in the
+/// ask asset at the spot prices before the swap.
+pub fn query_swap_apr_contribution(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<AprContributionResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    let pool_value = helpers::compute_pool_value(&pool_info, ask_index)?;
+
+    let fee_as_bps_of_tvl =
+        Decimal256::decimal_with_precision(swap_computation.swap_fee_amount, ask_decimal)?
+            .checked_mul(Decimal256::from_ratio(BASIS_POINTS, 1u8))?
+            .checked_div(pool_value)?;
+
+    Ok(AprContributionResponse {
+        fee_generated: swap_computation.swap_fee_amount,
+        fee_as_bps_of_tvl,
+    })
+}
+
+/// Computes the balanced reserves for which the stableswap invariant of the pool equals
+/// `target_d`. When all the reserves are equal the stableswap curve matches the constant sum, so
+/// `D = n * x` regardless of the amp factor, and each reserve is `target_d / n`. The invariant is
+/// the one computed on deposits, i.e. over the raw reserve amounts.
+pub fn query_reserves_for_invariant(
+    deps: Deps,
+    pool_identifier: String,
+    target_d: Uint256,
+) -> Result<Vec<Coin>, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    ensure!(
+        matches!(pool_info.pool_type, PoolType::StableSwap { .. }),
+        ContractError::NotStableSwapPool
+    );
+
+    let reserve = Uint128::try_from(
+        target_d.checked_div(Uint256::from(pool_info.asset_denoms.len() as u128))?,
+    )?;
+
+    Ok(pool_info
+        .asset_denoms
+        .iter()
+        .map(|denom| coin(reserve.u128(), denom))
+        .collect())
+}
+
+/// Checks whether a route of at most `max_hops` pools, capped at [helpers::MAX_REACHABLE_HOPS],
+/// connects `offer_denom` to `ask_denom`. No amounts are simulated, making it cheaper than
+/// computing a route for clients that only need to know whether one exists.
+pub fn query_reachable(
+    deps: Deps,
+    offer_denom: String,
+    ask_denom: String,
+    max_hops: u32,
+) -> Result<bool, ContractError> {
+    if offer_denom == ask_denom {
+        return Ok(true);
+    }
+
+    let path = helpers::find_shortest_pool_path(&deps, &offer_denom, &ask_denom, max_hops)?;
+
+    Ok(!path.is_empty())
+}
+
+/// The step used to differentiate the stableswap spot price, as a fraction of the base reserve.
+const SENSITIVITY_STEP_PERMILLE: u128 = 1;
+
+/// Computes the magnitude of the derivative of the spot price of `base_denom` in `quote_denom`
+/// with respect to the base reserve, both normalized by their decimals. Adding to the base reserve
+/// always lowers its price, so the response is how much the price drops per unit added.
+///
+/// For constant product pools the price is `y / x`, and the derivative `y / x^2` is computed in
+/// closed form. For stableswap pools a forward finite difference is taken over a step of
+/// [SENSITIVITY_STEP_PERMILLE] permille of the base reserve, and at least one unit of it.
+pub fn query_price_sensitivity(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<SensitivityResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (base_asset, quote_asset, base_index, quote_index, base_decimal, quote_decimal) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+
+    let price_per_unit = match pool_info.pool_type {
+        PoolType::ConstantProduct => {
+            let base_pool = Decimal256::decimal_with_precision(base_asset.amount, base_decimal)?;
+            let quote_pool = Decimal256::decimal_with_precision(quote_asset.amount, quote_decimal)?;
+
+            quote_pool.checked_div(base_pool)?.checked_div(base_pool)?
+        }
+        PoolType::StableSwap { .. } => {
+            let step = base_asset
+                .amount
+                .multiply_ratio(SENSITIVITY_STEP_PERMILLE, 1_000u128)
+                .max(Uint128::one());
+
+            let mut stepped_pool = pool_info.clone();
+            stepped_pool.assets[base_index].amount = base_asset.amount.checked_add(step)?;
+            let stepped_price =
+                helpers::compute_spot_price(&stepped_pool, base_index, quote_index)?;
+
+            spot_price
+                .saturating_sub(stepped_price)
+                .checked_div(Decimal256::decimal_with_precision(step, base_decimal)?)?
+        }
+    };
+
+    Ok(SensitivityResponse { price_per_unit })
+}
+
+/// Classifies a pool into a fee tier by comparing the total share of its fees against the fee tier
+/// thresholds in the [Config].
+pub fn query_fee_tier(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<FeeTierResponse, ContractError> {
+    let thresholds = CONFIG.load(deps.storage)?.fee_tier_thresholds;
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    Ok(FeeTierResponse {
+        tier: fee_tier(&pool_info, &thresholds),
+    })
+}
+
+/// Classifies a pool into a fee tier by comparing the total share of its fees against `thresholds`.
+fn fee_tier(pool_info: &PoolInfo, thresholds: &FeeTierThresholds) -> FeeTier {
+    let total_fee = pool_info.pool_fees.total_fee_share();
+
+    if total_fee <= Decimal256::from(thresholds.stable_max) {
+        FeeTier::Stable
+    } else if total_fee <= Decimal256::from(thresholds.standard_max) {
+        FeeTier::Standard
+    } else {
+        FeeTier::Exotic
+    }
+}
+
+/// Simulates swapping `amount` of `offer_denom` into `ask_denom`, and a swap of the same value in
+/// the opposite direction, i.e. `amount` valued in `ask_denom` at the current spot price. Both
+/// swaps are simulated against the current reserves, so their spreads can be compared to see how
+/// asymmetric the pool's pricing is.
+pub fn query_balanced_slippage(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    amount: Uint128,
+) -> Result<BalancedSlippageResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, offer_index, ask_index)?;
+    let matched_amount = Uint128::try_from(
+        Decimal256::decimal_with_precision(amount, offer_decimal)?
+            .checked_mul(spot_price)?
+            .to_uint256_with_precision(u32::from(ask_decimal))?,
+    )?;
+
+    let forward = helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, amount)?;
+    let reverse =
+        helpers::compute_swap_for_pool(&pool_info, ask_index, offer_index, matched_amount)?;
+
+    Ok(BalancedSlippageResponse {
+        forward_spread: forward.spread_amount,
+        reverse_spread: reverse.spread_amount,
+    })
+}
+
+/// The highest amp [query_min_amp_for_positive_spread] searches up to.
+const MAX_SEARCHED_AMP: u128 = 1_000_000;
+
+/// The typical trade sizes used by [query_min_amp_for_positive_spread], in basis points of the
+/// reserves.
+const TYPICAL_TRADE_SIZES_BPS: [u128; 3] = [1, 10, 100];
+
+/// Computes the highest amp for which reverse simulations against the pool have a non-negative
+/// spread, i.e. don't ask for less than the 1:1 conversion of the ask amount, for typical trade
+/// sizes. Past that amp the curve is flat enough for rounding to make the raw spread negative,
+/// which the reverse simulation then reports as zero.
+///
+/// Rounding is only of concern near balance, so the search is performed on a balanced version of the
+/// pool's first two assets, each holding their average normalized reserve. The trade sizes are
+/// [TYPICAL_TRADE_SIZES_BPS] of that reserve, without fees. Returns [MAX_SEARCHED_AMP] if no
+/// negative spread shows up below it.
+pub fn query_min_amp_for_positive_spread(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<Uint256, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    ensure!(
+        matches!(pool_info.pool_type, PoolType::StableSwap { .. }),
+        ContractError::NotStableSwapPool
+    );
+
+    let (offer_decimal, ask_decimal) = (pool_info.asset_decimals[0], pool_info.asset_decimals[1]);
+    let reserve = Decimal256::decimal_with_precision(pool_info.assets[0].amount, offer_decimal)?
+        .checked_add(Decimal256::decimal_with_precision(
+            pool_info.assets[1].amount,
+            ask_decimal,
+        )?)?
+        .checked_div(Decimal256::from_ratio(2u128, 1u128))?;
+    ensure!(!reserve.is_zero(), ContractError::PoolHasNoAssets);
+
+    let n_coins = Uint256::from(pool_info.assets.len() as u128);
+
+    // a reverse simulation that fails to compute is considered to have a negative spread
+    let has_negative_spread = |amp: Uint128| {
+        TYPICAL_TRADE_SIZES_BPS.iter().any(|trade_size_bps| {
+            reserve
+                .checked_mul(Decimal256::from_ratio(*trade_size_bps, BASIS_POINTS))
+                .map_err(ContractError::from)
+                .and_then(|ask_amount| {
+                    let offer_amount = helpers::compute_stableswap_reverse_offer_amount(
+                        n_coins,
+                        reserve,
+                        reserve,
+                        ask_amount,
+                        &(amp.u128() as u64),
+                        offer_decimal,
+                        ask_decimal,
+                    )?;
+
+                    Ok(Uint256::from(offer_amount)
+                        < ask_amount.to_uint256_with_precision(u32::from(offer_decimal))?)
+                })
+                .unwrap_or(true)
+        })
+    };
+
+    let first_negative_amp = helpers::binary_search_amount(
+        Uint128::from(MIN_AMP),
+        Uint128::new(MAX_SEARCHED_AMP),
+        has_negative_spread,
+    );
+
+    if !has_negative_spread(first_negative_amp) {
+        return Ok(Uint256::from(MAX_SEARCHED_AMP));
+    }
+
+    Ok(Uint256::from(
+        first_negative_amp.saturating_sub(Uint128::one()),
+    ))
+}
+
+/// Simulates a swap for a trader getting `rebate_bps` of the swap fee back, e.g. through an
+/// incentive program. The rebate is expressed in the same basis points as the pool's swap fee, so
+/// it can't exceed them, and is the matching share of the swap fee amount. It's added back to the
+/// return amount and deducted from the swap fee, the rest of the simulation staying the same.
+pub fn query_simulation_with_rebate(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    rebate_bps: u16,
+) -> Result<SimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let swap_fee_bps = Uint128::new(BASIS_POINTS).mul_floor(pool_info.pool_fees.swap_fee.share);
+    ensure!(
+        Uint128::from(rebate_bps) <= swap_fee_bps,
+        ContractError::InvalidRebate(rebate_bps, swap_fee_bps)
+    );
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+    let mut simulation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response();
+
+    if rebate_bps == 0 {
+        return Ok(simulation);
+    }
+
+    let rebate = simulation
+        .swap_fee_amount
+        .multiply_ratio(rebate_bps, swap_fee_bps);
+
+    simulation.return_amount = simulation.return_amount.checked_add(rebate)?;
+    simulation.swap_fee_amount = simulation.swap_fee_amount.checked_sub(rebate)?;
+
+    Ok(simulation)
+}
+
+/// Computes a second-order Taylor approximation of the swap's return amount, fees included, as a
+/// function of the offer amount around `around`. All the amounts are in the assets' base units.
+///
+/// The derivatives are estimated with forward finite differences over the offer amounts `around`,
+/// `around + h` and `around + 2h`, with `h` being 1% of `around`, and at least one unit. The
+/// output curve is concave, so the second-order coefficient is returned as a magnitude to be
+/// subtracted. The approximation is accurate for offer amounts within a few steps `h` of
+/// `around`, and degrades the further away, especially for stableswap pools past their flat region.
+pub fn query_price_curve_coefficients(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_asset_denom: String,
+    ask_asset_denom: String,
+    around: Uint128,
+) -> Result<CurveCoefficientsResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset_denom, ask_asset_denom)?;
+
+    let step = around.multiply_ratio(1u128, 100u128).max(Uint128::one());
+
+    let return_at = |offer_amount: Uint128| -> Result<Uint256, ContractError> {
+        Ok(
+            helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)?
+                .return_amount
+                .into(),
+        )
+    };
+
+    let f_0 = return_at(around)?;
+    let f_1 = return_at(around.checked_add(step)?)?;
+    let f_2 = return_at(around.checked_add(step.checked_mul(Uint128::new(2))?)?)?;
+
+    let step = Uint256::from(step);
+    let two = Uint256::from(2u128);
+
+    // f'(around) ≈ (4 * f_1 - 3 * f_0 - f_2) / 2h
+    let first_difference = f_1
+        .checked_mul(Uint256::from(4u128))?
+        .saturating_sub(f_0.checked_mul(Uint256::from(3u128))?)
+        .saturating_sub(f_2);
+    // -f''(around) / 2 ≈ (2 * f_1 - f_0 - f_2) / 2h^2
+    let second_difference = f_1
+        .checked_mul(two)?
+        .saturating_sub(f_0)
+        .saturating_sub(f_2);
+
+    Ok(CurveCoefficientsResponse {
+        c0: Decimal256::from_ratio(f_0, 1u128),
+        c1: Decimal256::checked_from_ratio(first_difference, step.checked_mul(two)?)?,
+        c2: Decimal256::checked_from_ratio(
+            second_difference,
+            step.checked_mul(step)?.checked_mul(two)?,
+        )?,
+    })
+}
+
+/// Gets the pools whose total fee, i.e. the sum of all their fee shares including the extra fees,
+/// is strictly above `min_total_fee`.
+pub fn query_pools_above_fee(
+    deps: Deps,
+    min_total_fee: Decimal,
+    limit: Option<u32>,
+) -> Result<PoolsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let min_total_fee = Decimal256::from(min_total_fee);
+
+    let mut pools = vec![];
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        if pools.len() == limit {
+            break;
+        }
+
+        let (_, pool) = item?;
+        if pool.pool_fees.total_fee_share() > min_total_fee {
+            let total_share = deps.querier.query_supply(&pool.lp_denom)?;
+            pools.push(PoolInfoResponse {
+                pool_info: pool,
+                total_share,
+            });
+        }
+    }
+
+    Ok(PoolsResponse { pools })
+}
+
+/// Simulates a swap on a stableswap pool at both the amp swaps currently execute with and the
+/// target amp of its ramp. As the amp moves monotonically between the two, the outputs bound what
+/// the trader can get if the swap executes anywhere in the rest of the ramp window. Only
+/// stableswap pools are supported.
+pub fn query_simulation_amp_range(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<AmpRangeSimulationResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let PoolType::StableSwap { amp: current_amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let target_amp = AMP_RAMPS
+        .may_load(deps.storage, &pool_identifier)?
+        .map_or(current_amp, |ramp| ramp.target_amp);
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let mut returns = Vec::with_capacity(2);
+    for amp in [current_amp, target_amp] {
+        pool_info.pool_type = PoolType::StableSwap { amp };
+        returns.push(
+            helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+                .return_amount,
+        );
+    }
+
+    Ok(AmpRangeSimulationResponse {
+        min_return: returns[0].min(returns[1]),
+        max_return: returns[0].max(returns[1]),
+    })
+}
+
+/// Gets each reserve of a stableswap pool as a fraction of its invariant D. The reserves are
+/// normalized to the largest precision among the assets before computing D, so a balanced pool
+/// gives a share of `1/n` for every asset. Only stableswap pools are supported.
+pub fn query_reserve_shares_of_d(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<Vec<(String, Decimal256)>, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let PoolType::StableSwap { amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    let normalized_reserves = helpers::normalize_reserves(&pool_info)?;
+    let d = Uint256::try_from(
+        helpers::compute_d(&amp, &normalized_reserves)
+            .ok_or(ContractError::StableInvariantError)?,
+    )?;
+    ensure!(!d.is_zero(), ContractError::PoolHasNoAssets);
+
+    Ok(normalized_reserves
+        .into_iter()
+        .map(|reserve| (reserve.denom, Decimal256::from_ratio(reserve.amount, d)))
+        .collect())
+}
+
+/// Gets the value of a unit of the pool's LP token in `quote_denom`, computed as the pool's total
+/// value locked in the quote asset divided by the LP token supply. Returns zero if no LP tokens
+/// have been minted.
+pub fn query_lp_price(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    quote_denom: String,
+) -> Result<LpPriceResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    let quote_index = pool_info
+        .asset_denoms
+        .iter()
+        .position(|denom| denom == &quote_denom)
+        .ok_or(ContractError::AssetMismatch)?;
+
+    Ok(LpPriceResponse {
+        price: helpers::compute_lp_price(&pool_info, quote_index, total_share.amount)?,
+    })
+}
+
+/// Checks whether the invariant computed from the pool reserves, K for constant product pools and
+/// D for stableswap pools, is at least the one implied by the LP token supply minus
+/// `tolerance_bps`. This is the same check as [query_pool_integrity], reduced to a boolean for
+/// monitoring.
+pub fn query_invariant_holds(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    tolerance_bps: u16,
+) -> Result<bool, ContractError> {
+    let IntegrityResponse {
+        expected_k,
+        actual_k,
+        ..
+    } = query_pool_integrity(deps, env, pool_identifier)?;
+
+    let tolerance = expected_k.multiply_ratio(tolerance_bps, BASIS_POINTS);
+
+    Ok(actual_k.checked_add(tolerance)? >= expected_k)
+}
+
+/// The amount of blocks up to the current one the volatility dynamic fees respond to is measured
+/// over, i.e. the window covered by the reserve snapshots kept.
+const DYNAMIC_FEE_VOLATILITY_WINDOW: u64 =
+    helpers::RESERVE_SNAPSHOT_INTERVAL * helpers::MAX_RESERVE_SNAPSHOTS;
+
+/// Simulates a swap as if the pool charged a dynamic swap fee of
+/// `base_fee * (1 + volatility_multiplier * volatility)` instead of its configured one, where the
+/// volatility is the [query_realized_volatility] of the offer asset in the ask asset over the
+/// [DYNAMIC_FEE_VOLATILITY_WINDOW] blocks up to the current one. The other fees of the pool are
+/// kept, and the response's fee amounts reflect the dynamic fee. Errors if the resulting total fee
+/// would reach 100%.
+pub fn query_simulation_dynamic_fee(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    base_fee: Decimal,
+    volatility_multiplier: Decimal,
+) -> Result<SimulationResponse, ContractError> {
+    let volatility = query_realized_volatility(
+        deps,
+        env.clone(),
+        pool_identifier.clone(),
+        offer_asset.denom.clone(),
+        ask_asset_denom.clone(),
+        DYNAMIC_FEE_VOLATILITY_WINDOW as u32,
+    )?
+    .volatility;
+    // a fee too large for a Decimal is well past 100%, and rejected below
+    let volatility_factor = Decimal256::one()
+        .checked_add(Decimal256::from(volatility_multiplier).checked_mul(volatility)?)?;
+    let swap_fee = Decimal::try_from(Decimal256::from(base_fee).checked_mul(volatility_factor)?)
+        .unwrap_or(Decimal::MAX);
+
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    pool_info.pool_fees.swap_fee = Fee { share: swap_fee };
+    ensure!(
+        pool_info.pool_fees.total_fee_share() < Decimal256::one(),
+        ContractError::InvalidDynamicFee(swap_fee)
+    );
+
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    Ok(
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response(),
+    )
+}
+
+/// Computes the slippage of selling `base_denom` worth `standard_notional` of `quote_denom`, valued
+/// at the current spot price. The slippage is the shortfall of the swap's output from the value of
+/// the offer at the spot price, as a fraction of that value, fees excluded, so trading the same
+/// value against pools of different depths gives comparable figures for both pool types.
+pub fn query_standard_slippage(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    standard_notional: Uint128,
+) -> Result<Decimal256, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, base_decimal, quote_decimal) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+    let offer_amount = Uint128::try_from(
+        Decimal256::decimal_with_precision(standard_notional, quote_decimal)?
+            .checked_div(spot_price)?
+            .to_uint256_with_precision(u32::from(base_decimal))?,
+    )?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, base_index, quote_index, offer_amount)?;
+    let gross_return_amount = [
+        swap_computation.swap_fee_amount,
+        swap_computation.protocol_fee_amount,
+        swap_computation.burn_fee_amount,
+        swap_computation.extra_fees_amount,
+    ]
+    .into_iter()
+    .try_fold(swap_computation.return_amount, |acc, fee| {
+        acc.checked_add(fee)
+    })?;
+
+    let spot_value =
+        Decimal256::decimal_with_precision(offer_amount, base_decimal)?.checked_mul(spot_price)?;
+    let output_value = Decimal256::decimal_with_precision(gross_return_amount, quote_decimal)?;
+
+    Ok(Decimal256::one()
+        .checked_sub(output_value.checked_div(spot_value)?)
+        .unwrap_or_default())
+}
+
+/// Reverse simulates getting `ask_asset` for `offer_denom` on each of the candidate pools, and
+/// returns the one requiring the least offer amount. Every candidate must hold both denoms, but the
+/// ones too shallow to return `ask_asset` are skipped. Only the first [MAX_LIMIT] candidates are
+/// considered.
+pub fn query_cheapest_for_output(
+    deps: Deps,
+    env: Env,
+    ask_asset: Coin,
+    offer_denom: String,
+    candidate_pools: Vec<String>,
+) -> Result<CheapestResponse, ContractError> {
+    let mut cheapest: Option<CheapestResponse> = None;
+
+    for pool_identifier in candidate_pools.into_iter().take(MAX_LIMIT as usize) {
+        let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+        ensure!(
+            pool_info.asset_denoms.contains(&offer_denom)
+                && pool_info.asset_denoms.contains(&ask_asset.denom),
+            ContractError::AssetMismatch
+        );
+
+        let Ok(reverse_simulation) = query_reverse_simulation(
+            deps,
+            env.clone(),
+            ask_asset.clone(),
+            offer_denom.clone(),
+            pool_identifier.clone(),
+        ) else {
+            continue;
+        };
+
+        if cheapest
+            .as_ref()
+            .is_none_or(|c| reverse_simulation.offer_amount < c.offer_amount)
+        {
+            cheapest = Some(CheapestResponse {
+                pool_identifier,
+                offer_amount: reverse_simulation.offer_amount,
+            });
+        }
+    }
+
+    cheapest.ok_or(ContractError::NoPoolForOutput)
+}
+
+/// Computes the effective prices, fees included, of trading `size` of `base_denom` against
+/// `quote_denom` in both directions. The bid is the price received when selling `size`, and the
+/// ask is the price paid when buying with the amount of `quote_denom` worth `size` at the spot
+/// price. The gap between the two is the cost of a round trip through the pool.
+pub fn query_effective_bid_ask(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    size: Uint128,
+) -> Result<BidAskResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, base_decimal, quote_decimal) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+    let base_size = Decimal256::decimal_with_precision(size, base_decimal)?;
+    let quote_size = Uint128::try_from(
+        base_size
+            .checked_mul(spot_price)?
+            .to_uint256_with_precision(u32::from(quote_decimal))?,
+    )?;
+
+    let sell = helpers::compute_swap_for_pool(&pool_info, base_index, quote_index, size)?;
+    let buy = helpers::compute_swap_for_pool(&pool_info, quote_index, base_index, quote_size)?;
+
+    Ok(BidAskResponse {
+        bid: Decimal256::decimal_with_precision(sell.return_amount, quote_decimal)?
+            .checked_div(base_size)?,
+        ask: Decimal256::decimal_with_precision(quote_size, quote_decimal)?.checked_div(
+            Decimal256::decimal_with_precision(buy.return_amount, base_decimal)?,
+        )?,
+    })
+}
+
+/// Finds the offer amount in `[0, max_offer]` maximizing the utility `return_amount -
+/// slippage_penalty * spread_amount` of the swap, with a ternary search as the utility is concave
+/// in the offer amount. Offer amounts the pool can't swap, or whose utility isn't positive, are
+/// given no utility, so zero is returned when no trade is worth making.
+pub fn query_optimal_trade_for_utility(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    slippage_penalty: Decimal256,
+    max_offer: Uint128,
+) -> Result<Uint128, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    let utility = |offer_amount: Uint128| -> Uint256 {
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)
+            .ok()
+            .and_then(|swap_computation| {
+                let penalty = Decimal256::from_ratio(swap_computation.spread_amount, 1u128)
+                    .checked_mul(slippage_penalty)
+                    .ok()?
+                    .to_uint_ceil();
+                Uint256::from(swap_computation.return_amount)
+                    .checked_sub(penalty)
+                    .ok()
+            })
+            .unwrap_or_default()
+    };
+
+    let (mut low, mut high) = (Uint128::zero(), max_offer);
+    while high - low > Uint128::new(2) {
+        let third = (high - low) / Uint128::new(3);
+        let (mid_low, mid_high) = (low + third, high - third);
+
+        if utility(mid_low) < utility(mid_high) {
+            low = mid_low;
+        } else {
+            high = mid_high;
+        }
+    }
+
+    // settle the remaining candidates, preferring the smallest offer among equal utilities
+    let (best_offer, best_utility) = (low.u128()..=high.u128())
+        .map(|offer_amount| {
+            (
+                Uint128::new(offer_amount),
+                utility(Uint128::new(offer_amount)),
+            )
+        })
+        .fold((Uint128::zero(), Uint256::zero()), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        });
+
+    Ok(if best_utility.is_zero() {
+        Uint128::zero()
+    } else {
+        best_offer
+    })
+}
+
+/// Simulates the swap operations like [simulate_swap_operations], and returns the reserves each
+/// pool touched by the route would have once all of them execute. Pools are listed in the order
+/// the route first touches them, and a pool swapped through several times reflects all its swaps.
+pub fn query_reserves_after_route(
+    deps: Deps,
+    env: Env,
+    offer_amount: Uint128,
+    operations: Vec<SwapOperation>,
+) -> Result<Vec<(String, Vec<Coin>)>, ContractError> {
+    assert_operations(operations.clone())?;
+
+    let mut touched_pools: Vec<String> = vec![];
+    for operation in operations.iter() {
+        let SwapOperation::MantraSwap {
+            pool_identifier, ..
+        } = operation;
+        if !touched_pools.contains(pool_identifier) {
+            touched_pools.push(pool_identifier.clone());
+        }
+    }
+
+    let mut pools = HashMap::new();
+    simulate_operations_in_memory(deps, &env, &mut pools, offer_amount, operations)?;
+
+    Ok(touched_pools
+        .into_iter()
+        .filter_map(|pool_identifier| {
+            pools
+                .remove(&pool_identifier)
+                .map(|pool_info| (pool_identifier, pool_info.assets))
+        })
+        .collect())
+}
+
+/// Gets a fee structure charging no fees, to compute swaps on a fee-less copy of a pool.
+fn zero_pool_fees() -> PoolFee {
+    let no_fee = || Fee {
+        share: Decimal::zero(),
+    };
+
+    PoolFee {
+        protocol_fee: no_fee(),
+        swap_fee: no_fee(),
+        burn_fee: no_fee(),
+        extra_fees: vec![],
+    }
+}
+
+/// Computes the swap fee at which LPs break even on a move of the base asset's price by
+/// `expected_volatility`, expressed as a fraction of the spot price.
+///
+/// The model assumes the move happens through a single arbitrage swap buying the base asset with
+/// the quote asset until the pool's spot price matches the new external price, and that this swap
+/// is the only volume the pool sees. The impermanent loss is the value, at the new price, of the
+/// pool's base and quote reserves before the move minus their value after it, computed on a
+/// fee-less copy of the pool. The breakeven fee is that loss divided by the arbitrage volume in
+/// the quote asset. Other assets of the pool are assumed not to move. Zero is returned if the move
+/// requires no volume.
+pub fn query_breakeven_fee(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    expected_volatility: Decimal256,
+) -> Result<Decimal256, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, base_decimal, quote_decimal) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    pool_info.pool_fees = zero_pool_fees();
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+    let target_price =
+        spot_price.checked_mul(Decimal256::one().checked_add(expected_volatility)?)?;
+
+    // buying the base asset lowers the price of the quote asset expressed in the base asset
+    let volume = helpers::compute_offer_to_reach_price(
+        &pool_info,
+        quote_index,
+        base_index,
+        Decimal256::one().checked_div(target_price)?,
+    )?;
+    if volume.is_zero() {
+        return Ok(Decimal256::zero());
+    }
+
+    let value_at = |pool_info: &PoolInfo, price: Decimal256| -> Result<Decimal256, ContractError> {
+        Ok(
+            Decimal256::decimal_with_precision(pool_info.assets[base_index].amount, base_decimal)?
+                .checked_mul(price)?
+                .checked_add(Decimal256::decimal_with_precision(
+                    pool_info.assets[quote_index].amount,
+                    quote_decimal,
+                )?)?,
+        )
+    };
+
+    let mut moved_pool = pool_info.clone();
+    let swap_computation =
+        helpers::compute_swap_for_pool(&moved_pool, quote_index, base_index, volume)?;
+    helpers::apply_swap_to_pool(
+        &mut moved_pool,
+        quote_index,
+        base_index,
+        volume,
+        &swap_computation,
+    )?;
+    let moved_price = helpers::compute_spot_price(&moved_pool, base_index, quote_index)?;
+
+    let impermanent_loss =
+        value_at(&pool_info, moved_price)?.saturating_sub(value_at(&moved_pool, moved_price)?);
+
+    Ok(impermanent_loss.checked_div(Decimal256::decimal_with_precision(volume, quote_decimal)?)?)
+}
+
+/// Gets the edges of the routing graph formed by the pools, so a router can build its route cache
+/// in a single query. Every pair of assets of a pool gives an undirected edge, in the order the
+/// assets are stored in. Up to `limit` pools are scanned, and pools with less than two assets are
+/// skipped.
+pub fn query_routing_graph(
+    deps: Deps,
+    limit: Option<u32>,
+) -> Result<RoutingGraphResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let mut edges = vec![];
+    for item in POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+    {
+        let (_, pool) = item?;
+        if pool.asset_denoms.len() < 2 {
+            continue;
+        }
+
+        for (i, denom_a) in pool.asset_denoms.iter().enumerate() {
+            for denom_b in pool.asset_denoms.iter().skip(i + 1) {
+                edges.push((
+                    denom_a.clone(),
+                    denom_b.clone(),
+                    pool.pool_identifier.clone(),
+                ));
+            }
+        }
+    }
+
+    Ok(RoutingGraphResponse { edges })
+}
+
+/// Simulates a swap on a fee-less copy of the pool, and expresses the fees the pool would have
+/// charged on it in `fee_denom`, valuing a unit of the ask asset at `fee_price` units of
+/// `fee_denom`. This models paying the fees in a dedicated token rather than in the swapped asset.
+/// The fee amount is rounded up.
+pub fn query_simulation_external_fee(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+    fee_denom: String,
+    fee_price: Decimal256,
+) -> Result<ExternalFeeSimulationResponse, ContractError> {
+    ensure!(
+        !fee_price.is_zero(),
+        ContractError::InvalidFeePrice { fee_denom }
+    );
+
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    let total_fee = [
+        swap_computation.swap_fee_amount,
+        swap_computation.protocol_fee_amount,
+        swap_computation.burn_fee_amount,
+        swap_computation.extra_fees_amount,
+    ]
+    .into_iter()
+    .try_fold(Uint128::zero(), |acc, fee| acc.checked_add(fee))?;
+
+    pool_info.pool_fees = zero_pool_fees();
+    let fee_less_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    Ok(ExternalFeeSimulationResponse {
+        return_amount: fee_less_computation.return_amount,
+        fee_in_fee_denom: Uint128::try_from(
+            Decimal256::from_ratio(total_fee, 1u128)
+                .checked_mul(fee_price)?
+                .to_uint_ceil(),
+        )?,
+    })
+}
+
+/// Computes the corrective swap a keeper would make to align the pool's spot price of
+/// `base_denom`, expressed in `quote_denom`, with `oracle_price`. When the oracle price lies within
+/// the band the pool's total fee makes unprofitable to arbitrage, i.e. between `spot * (1 - fee)`
+/// and `spot / (1 - fee)`, a zero-amount swap is returned. Otherwise the swap is the one computed by
+/// [query_rebalance_swap].
+pub fn query_oracle_arb_to_price(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    oracle_price: Decimal256,
+) -> Result<RebalanceResponse, ContractError> {
+    ensure!(!oracle_price.is_zero(), ContractError::InvalidTargetPrice);
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom.clone(), quote_denom.clone())?;
+
+    let spot_price = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+    let retention = Decimal256::one().checked_sub(pool_info.pool_fees.total_fee_share())?;
+
+    if oracle_price >= spot_price.checked_mul(retention)?
+        && oracle_price <= spot_price.checked_div(retention)?
+    {
+        return Ok(RebalanceResponse {
+            offer_denom: base_denom,
+            offer_amount: Uint128::zero(),
+        });
+    }
+
+    query_rebalance_swap(
+        deps,
+        env,
+        pool_identifier,
+        base_denom,
+        quote_denom,
+        oracle_price,
+    )
+}
+
+/// Simulates a swap and expresses each of its costs as a percentage of the fee-free output at the
+/// pool's spot price, like [query_canonical_simulation] does for the total cost. The spread is
+/// what remains of the total cost once the swap, protocol and burn fees are accounted for, so it
+/// also covers any extra fees, and the components always add up to the total cost. Returns zeros
+/// for a zero offer.
+pub fn query_fee_percentages(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<FeePercentagesResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    if offer_asset.amount.is_zero() {
+        return Ok(FeePercentagesResponse {
+            swap_pct: Decimal256::zero(),
+            protocol_pct: Decimal256::zero(),
+            burn_pct: Decimal256::zero(),
+            spread_pct: Decimal256::zero(),
+        });
+    }
+
+    let spot_price = helpers::compute_spot_price(&pool_info, offer_index, ask_index)?;
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+    let ideal_return = Decimal256::decimal_with_precision(offer_asset.amount, offer_decimal)?
+        .checked_mul(spot_price)?;
+    let percentage_of_ideal = |amount: Uint128| -> Result<Decimal256, ContractError> {
+        Ok(Decimal256::decimal_with_precision(amount, ask_decimal)?
+            .checked_div(ideal_return)?
+            .checked_mul(Decimal256::percent(10_000))?)
+    };
+
+    let swap_pct = percentage_of_ideal(swap_computation.swap_fee_amount)?;
+    let protocol_pct = percentage_of_ideal(swap_computation.protocol_fee_amount)?;
+    let burn_pct = percentage_of_ideal(swap_computation.burn_fee_amount)?;
+    let total_cost_pct = Decimal256::percent(10_000)
+        .saturating_sub(percentage_of_ideal(swap_computation.return_amount)?);
+
+    Ok(FeePercentagesResponse {
+        swap_pct,
+        protocol_pct,
+        burn_pct,
+        spread_pct: total_cost_pct
+            .saturating_sub(swap_pct)
+            .saturating_sub(protocol_pct)
+            .saturating_sub(burn_pct),
+    })
+}
+
+/// Gets the deepest pool of each distinct pair of denoms among the first `limit` pools, comparing
+/// their total value locked in `quote_denom`. Pools can only be valued in one of their own assets,
+/// so the pools not holding `quote_denom`, and the empty ones, are skipped. Pairs are returned in
+/// lexicographical order, and ties go to the pool scanned first.
+pub fn query_canonical_pools(
+    deps: Deps,
+    quote_denom: String,
+    limit: Option<u32>,
+) -> Result<CanonicalPoolsResponse, ContractError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let mut deepest: BTreeMap<(String, String), (String, Decimal256)> = BTreeMap::new();
+    for item in POOLS
+        .range(deps.storage, None, None, Order::Ascending)
+        .take(limit)
+    {
+        let (_, pool) = item?;
+        let Some(quote_index) = pool
+            .asset_denoms
+            .iter()
+            .position(|denom| denom == &quote_denom)
+        else {
+            continue;
+        };
+        let Ok(value) = helpers::compute_pool_value(&pool, quote_index) else {
+            continue;
+        };
+
+        for (i, denom_a) in pool.asset_denoms.iter().enumerate() {
+            for denom_b in pool.asset_denoms.iter().skip(i + 1) {
+                let pair = if denom_a <= denom_b {
+                    (denom_a.clone(), denom_b.clone())
+                } else {
+                    (denom_b.clone(), denom_a.clone())
+                };
+
+                match deepest.get(&pair) {
+                    Some((_, deepest_value)) if *deepest_value >= value => {}
+                    _ => {
+                        deepest.insert(pair, (pool.pool_identifier.clone(), value));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(CanonicalPoolsResponse {
+        pools: deepest
+            .into_iter()
+            .map(|((denom_a, denom_b), (pool_identifier, _))| (denom_a, denom_b, pool_identifier))
+            .collect(),
+    })
+}
+
+/// Simulates a withdrawal of `lp_amount` LP tokens and reports the pool's spot price of its first
+/// asset, in its second one, once it's done, along with the value of an LP token after the
+/// withdrawal relative to before it. The values are computed in the second asset at the spot
+/// prices of the respective pool states.
+///
+/// A proportional withdrawal leaves the spot price unchanged. Only a single-sided exit into
+/// `single_denom`, which swaps the other withdrawn assets into it, moves the price. The withdrawal
+/// must leave some LP tokens in the pool.
+pub fn query_whale_exit_impact(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    lp_amount: Uint128,
+    single_denom: Option<String>,
+) -> Result<WhaleExitResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+    ensure!(
+        lp_amount < total_share.amount,
+        ContractError::InvalidLpShareToWithdraw
+    );
+
+    let mut pool_after = pool_info.clone();
+    match single_denom {
+        Some(denom) => {
+            let target_index = pool_info
+                .asset_denoms
+                .iter()
+                .position(|d| d == &denom)
+                .ok_or(ContractError::AssetMismatch)?;
+            helpers::apply_zap_out_to_pool(
+                &mut pool_after,
+                lp_amount,
+                total_share.amount,
+                target_index,
+            )?;
+        }
+        None => {
+            let withdrawn_assets =
+                helpers::compute_lp_share_value(&pool_info, lp_amount, total_share.amount)?;
+            for (pool_asset, withdrawn_asset) in
+                pool_after.assets.iter_mut().zip(withdrawn_assets.iter())
+            {
+                pool_asset.amount = pool_asset.amount.checked_sub(withdrawn_asset.amount)?;
+            }
+        }
+    }
+
+    let value_per_share_before = helpers::compute_pool_value(&pool_info, 1)?
+        .checked_div(Decimal256::from_ratio(total_share.amount, 1u128))?;
+    let value_per_share_after = helpers::compute_pool_value(&pool_after, 1)?.checked_div(
+        Decimal256::from_ratio(total_share.amount.checked_sub(lp_amount)?, 1u128),
+    )?;
+
+    Ok(WhaleExitResponse {
+        price_after: helpers::compute_spot_price(&pool_after, 0, 1)?,
+        remaining_lp_value_change: value_per_share_after.checked_div(value_per_share_before)?,
+    })
+}
+
+/// Computes the swaps bringing a stableswap pool to perfect balance, i.e. every reserve normalized
+/// by its decimals equal to `D / n`. Each returned coin is the amount of the most underweight asset
+/// to offer for the most overweight one at that point of the sequence, and is capped so the
+/// offered asset doesn't rise above its own target. A pool of two assets needs a single swap.
+///
+/// The swaps are computed without fees, which keeps D, and so the target reserves, unchanged
+/// along the sequence. With fees the pool ends up marginally short of perfect balance. Only
+/// stableswap pools are supported.
+pub fn query_rebalance_to_balanced(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+) -> Result<Vec<Coin>, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let PoolType::StableSwap { amp } = pool_info.pool_type else {
+        return Err(ContractError::NotStableSwapPool);
+    };
+
+    // work on a fee-less copy of the pool with all its reserves at the same precision
+    let max_decimals = pool_info.asset_decimals.iter().max().copied().unwrap_or(0);
+    let mut balanced_pool = pool_info.clone();
+    balanced_pool.assets = helpers::normalize_reserves(&pool_info)?;
+    balanced_pool.asset_decimals = vec![max_decimals; pool_info.assets.len()];
+    balanced_pool.pool_fees = zero_pool_fees();
+
+    let n_coins = Uint128::from(pool_info.assets.len() as u128);
+    let d = Uint128::try_from(
+        helpers::compute_d(&amp, &balanced_pool.assets)
+            .ok_or(ContractError::StableInvariantError)?,
+    )?;
+    let target = d.checked_div(n_coins)?;
+
+    let mut swaps = vec![];
+    // every swap either fills a deficit or exhausts a surplus
+    for _ in 1..pool_info.assets.len() {
+        let reserve = |index: usize| balanced_pool.assets[index].amount;
+        // the most underweight asset is offered for the most overweight one
+        let (Some(offer_index), Some(ask_index)) = (
+            (0..balanced_pool.assets.len()).min_by_key(|index| reserve(*index)),
+            (0..balanced_pool.assets.len()).max_by_key(|index| reserve(*index)),
+        ) else {
+            break;
+        };
+
+        let deficit = target.saturating_sub(reserve(offer_index));
+        let surplus = reserve(ask_index).saturating_sub(target);
+        if deficit.is_zero() || surplus.is_zero() {
+            break;
+        }
+
+        let offer_amount = helpers::compute_stableswap_reverse_offer_amount(
+            Uint256::from(n_coins),
+            Decimal256::decimal_with_precision(reserve(offer_index), max_decimals)?,
+            Decimal256::decimal_with_precision(reserve(ask_index), max_decimals)?,
+            Decimal256::decimal_with_precision(surplus, max_decimals)?,
+            &amp,
+            max_decimals,
+            max_decimals,
+        )?
+        .min(deficit);
+
+        let swap_computation =
+            helpers::compute_swap_for_pool(&balanced_pool, offer_index, ask_index, offer_amount)?;
+        helpers::apply_swap_to_pool(
+            &mut balanced_pool,
+            offer_index,
+            ask_index,
+            offer_amount,
+            &swap_computation,
+        )?;
+
+        swaps.push(coin(
+            helpers::adjust_precision(
+                offer_amount,
+                max_decimals,
+                pool_info.asset_decimals[offer_index],
+            )?
+            .u128(),
+            &pool_info.asset_denoms[offer_index],
+        ));
+    }
+
+    Ok(swaps)
+}
+
+/// Computes the ratio of the fees charged on the pool's swaps to the theoretical fees implied by
+/// their cumulative volume times the pool's current total fee rate. Both are accumulated on every
+/// swap through a pool charging fees, even when they round down to zero, normalized by the
+/// decimals of the asset the swap was charged in. Returns one if the pool hasn't been swapped in
+/// yet, or charges no fees.
+pub fn query_fee_capture_ratio(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<FeeCaptureResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let accumulator = FEE_ACCUMULATORS
+        .may_load(deps.storage, &pool_identifier)?
+        .unwrap_or_default();
+
+    let theoretical_fees = accumulator
+        .volume
+        .checked_mul(pool_info.pool_fees.total_fee_share())?;
+    if theoretical_fees.is_zero() {
+        return Ok(FeeCaptureResponse {
+            ratio: Decimal256::one(),
+        });
+    }
+
+    Ok(FeeCaptureResponse {
+        ratio: accumulator.fees.checked_div(theoretical_fees)?,
+    })
+}
+
+/// Gets the current value of a unit of the pool's LP token in `quote_denom`, as computed by
+/// [query_lp_price], along with its high-water mark. The high-water mark is recorded on swaps and
+/// deposits, starting with the first deposit, and is never below the current value.
+pub fn query_share_price_hwm(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    quote_denom: String,
+) -> Result<SharePriceResponse, ContractError> {
+    let current = query_lp_price(deps, env, pool_identifier.clone(), quote_denom.clone())?.price;
+    let high_water_mark = SHARE_PRICE_HIGH_WATER_MARKS
+        .may_load(deps.storage, (&pool_identifier, &quote_denom))?
+        .unwrap_or_default()
+        .max(current);
+
+    Ok(SharePriceResponse {
+        current,
+        high_water_mark,
+    })
+}
+
+/// Counts the pools in the contract and sums their total value locked in the most preferred quote
+/// denom of the config, each valued at its own spot prices. Only the pools holding the quote asset
+/// can be valued in it, so the others are counted but add nothing to the total.
+///
+/// This iterates over every pool, so its cost grows with the number of pools. It's meant for
+/// occasional use, e.g. by dashboards, not to be called from other contracts.
+pub fn query_global_stats(deps: Deps, env: Env) -> Result<GlobalStatsResponse, ContractError> {
+    let quote_denom = CONFIG
+        .load(deps.storage)?
+        .quote_denom_priority
+        .into_iter()
+        .next()
+        .ok_or(ContractError::NoQuoteDenom)?;
+
+    let mut total_pools = 0u32;
+    let mut total_tvl_in_quote = Uint256::zero();
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, mut pool) = item?;
+        total_pools += 1;
+
+        let Some(quote_index) = pool
+            .asset_denoms
+            .iter()
+            .position(|denom| denom == &quote_denom)
+        else {
+            continue;
+        };
+        apply_amp_ramp(deps.storage, &mut pool, env.block.time.seconds())?;
+        let Ok(value) = helpers::compute_pool_value(&pool, quote_index) else {
+            continue;
+        };
+
+        // the pool value is in whole units of the quote asset, scale it back to its smallest unit
+        let quote_unit =
+            Uint256::from(10u128).checked_pow(pool.asset_decimals[quote_index].into())?;
+        total_tvl_in_quote = total_tvl_in_quote.checked_add(
+            value
+                .checked_mul(Decimal256::from_ratio(quote_unit, 1u8))?
+                .to_uint_floor(),
+        )?;
+    }
+
+    Ok(GlobalStatsResponse {
+        total_pools,
+        total_tvl_in_quote,
+        quote_denom,
+    })
+}
+
+/// Simulates a reverse swap for `ask_asset`, as [query_reverse_simulation] does. If the pool
+/// can't deliver the full amount, instead of erroring, the largest deliverable amount of the ask
+/// asset is found with a binary search, and returned along with the offer amount it requires.
+/// An ask amount is deemed deliverable when its reverse simulation succeeds.
+pub fn query_reverse_simulation_partial(
+    deps: Deps,
+    env: Env,
+    ask_asset: Coin,
+    offer_denom: String,
+    pool_identifier: String,
+) -> Result<PartialReverseResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, ask_asset_in_pool, _, _, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom.clone(), ask_asset.denom.clone())?;
+
+    let offer_for = |ask_amount: Uint128| -> Option<Uint128> {
+        query_reverse_simulation(
+            deps,
+            env.clone(),
+            coin(ask_amount.u128(), &ask_asset.denom),
+            offer_denom.clone(),
+            pool_identifier.clone(),
+        )
+        .ok()
+        .map(|simulation| simulation.offer_amount)
+    };
+
+    if let Some(offer_amount) = offer_for(ask_asset.amount) {
+        return Ok(PartialReverseResponse {
+            achievable_ask: ask_asset.amount,
+            offer_amount,
+        });
+    }
+
+    // the pool can never deliver its whole reserve, so the first undeliverable amount is within it
+    let high = ask_asset.amount.min(ask_asset_in_pool.amount);
+    let first_undeliverable = helpers::binary_search_amount(Uint128::zero(), high, |ask_amount| {
+        ask_amount > Uint128::zero() && offer_for(ask_amount).is_none()
+    });
+
+    let achievable_ask = first_undeliverable.saturating_sub(Uint128::one());
+    let offer_amount = if achievable_ask.is_zero() {
+        Uint128::zero()
+    } else {
+        offer_for(achievable_ask).unwrap_or_default()
+    };
+
+    Ok(PartialReverseResponse {
+        achievable_ask,
+        offer_amount,
+    })
+}
+
+/// Collects the spot price of `base_denom` in `quote_denom` of every pool holding both assets and
+/// computes the spread between the highest and the lowest of them, in basis points of the lowest.
+/// A large spread hints at a pool whose price is stale or being manipulated. The pools whose spot
+/// price can't be computed, e.g. empty ones, are skipped.
+///
+/// Errors if fewer than two pools have a price for the pair. This iterates over every pool, so
+/// it's meant for occasional use.
+pub fn query_price_consistency(
+    deps: Deps,
+    env: Env,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<ConsistencyResponse, ContractError> {
+    let mut prices = vec![];
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, mut pool) = item?;
+        let position = |denom: &String| pool.asset_denoms.iter().position(|d| d == denom);
+        let (Some(base_index), Some(quote_index)) = (position(&base_denom), position(&quote_denom))
+        else {
+            continue;
+        };
+        apply_amp_ramp(deps.storage, &mut pool, env.block.time.seconds())?;
+
+        if let Ok(price) = helpers::compute_spot_price(&pool, base_index, quote_index) {
+            if !price.is_zero() {
+                prices.push(price);
+            }
+        }
+    }
+
+    let pools = prices.len() as u32;
+    ensure!(
+        pools >= 2,
+        ContractError::NotEnoughPoolsForPair {
+            base_denom,
+            quote_denom,
+            pools,
+        }
+    );
+
+    let highest = prices.iter().max().copied().unwrap_or_default();
+    let lowest = prices.iter().min().copied().unwrap_or_default();
+    let spread_bps = highest
+        .checked_sub(lowest)?
+        .checked_mul(Decimal256::from_ratio(BASIS_POINTS, 1u128))?
+        .checked_div(lowest)?
+        .to_uint_floor();
+
+    Ok(ConsistencyResponse {
+        spread_bps: u64::try_from(Uint128::try_from(spread_bps)?.u128()).unwrap_or(u64::MAX),
+        pools,
+    })
+}
+
+/// Simulates depositing `assets` into a stableswap pool and shows how the deposit is treated. The
+/// effective assets are what the minted LP tokens could be withdrawn for right after the deposit,
+/// i.e. the balanced deposit it's equivalent to. Any value the deposit loses to its imbalance,
+/// taking the assets of the pool at par, is reported as a fee charged on the assets deposited in
+/// excess of their effective amount, pro rata to that excess.
+pub fn query_deposit_rebalance(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    assets: Vec<Coin>,
+) -> Result<DepositRebalanceResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    ensure!(
+        matches!(pool_info.pool_type, PoolType::StableSwap { .. }),
+        ContractError::NotStableSwapPool
+    );
+
+    let deposits = aggregate_coins(assets)?;
+    ensure!(
+        deposits
+            .iter()
+            .all(|deposit| pool_info.asset_denoms.contains(&deposit.denom)),
+        ContractError::AssetMismatch
+    );
+
+    let share = helpers::compute_deposit_share(&pool_info, &deposits, total_share.amount)?;
+
+    let mut pool = pool_info.clone();
+    pool.assets = add_coins(pool.assets, deposits.clone())?;
+    let effective_assets =
+        helpers::compute_lp_share_value(&pool, share, total_share.amount.checked_add(share)?)?;
+
+    let mut deposited_value = Decimal256::zero();
+    let mut effective_value = Decimal256::zero();
+    let mut excesses = vec![];
+    let mut excess_value = Decimal256::zero();
+    for (effective, decimals) in effective_assets.iter().zip(pool_info.asset_decimals.iter()) {
+        let deposit = deposits
+            .iter()
+            .find(|deposit| deposit.denom == effective.denom)
+            .map_or(Uint128::zero(), |deposit| deposit.amount);
+        let excess = deposit.saturating_sub(effective.amount);
+
+        deposited_value =
+            deposited_value.checked_add(Decimal256::decimal_with_precision(deposit, *decimals)?)?;
+        effective_value = effective_value.checked_add(Decimal256::decimal_with_precision(
+            effective.amount,
+            *decimals,
+        )?)?;
+        excess_value =
+            excess_value.checked_add(Decimal256::decimal_with_precision(excess, *decimals)?)?;
+        excesses.push(coin(excess.u128(), effective.denom.clone()));
+    }
+
+    // the share of each excess lost to the imbalance, which can't exceed the whole excess
+    let fee_ratio = if excess_value.is_zero() {
+        Decimal256::zero()
+    } else {
+        deposited_value
+            .saturating_sub(effective_value)
+            .checked_div(excess_value)?
+            .min(Decimal256::one())
+    };
+
+    let imbalance_fee = excesses
+        .into_iter()
+        .map(|excess| {
+            Ok(coin(
+                Uint128::try_from(
+                    Decimal256::from_ratio(excess.amount, 1u8)
+                        .checked_mul(fee_ratio)?
+                        .to_uint_floor(),
+                )?
+                .u128(),
+                excess.denom,
+            ))
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?;
+
+    Ok(DepositRebalanceResponse {
+        effective_assets,
+        imbalance_fee,
+    })
+}
+
+/// The share of the ask asset reserve above which [query_simulation_safe] flags a swap.
+const SAFE_RESERVE_SHARE: Decimal = Decimal::percent(50);
+
+/// Simulates a swap like [query_simulation] and flags it when its return amount exceeds
+/// [SAFE_RESERVE_SHARE] of the ask asset reserve, as such a swap incurs an extreme slippage. The
+/// simulation is reported as is either way.
+pub fn query_simulation_safe(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_denom: String,
+    pool_identifier: String,
+) -> Result<SafeSimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, ask_asset_in_pool, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_denom)?;
+
+    let simulation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?
+            .to_simulation_response();
+    let liquidity_warning =
+        simulation.return_amount > ask_asset_in_pool.amount.mul_floor(SAFE_RESERVE_SHARE);
+
+    Ok(SafeSimulationResponse {
+        simulation,
+        liquidity_warning,
+    })
+}
+
+/// Gets the length, in pools, of the shortest route from `offer_denom` to `ask_denom`, searching
+/// at most `max_search` pools deep, capped at [helpers::MAX_REACHABLE_HOPS]. Returns `None` if no
+/// route is found within that depth, and zero if both denoms are the same. Like
+/// [query_reachable], no amounts are simulated.
+pub fn query_min_hops(
+    deps: Deps,
+    offer_denom: String,
+    ask_denom: String,
+    max_search: u32,
+) -> Result<Option<u32>, ContractError> {
+    if offer_denom == ask_denom {
+        return Ok(Some(0));
+    }
+
+    let path = helpers::find_shortest_pool_path(&deps, &offer_denom, &ask_denom, max_search)?;
+
+    Ok((!path.is_empty()).then_some(path.len() as u32))
+}
+
+/// Computes the deposit, in the same ratio as the pool reserves, worth `target_value` of
+/// `value_denom` at the pool's spot prices, along with the LP tokens it would mint. The deposit is
+/// the share of each reserve matching the share of the pool's total value locked the target value
+/// represents, rounded down.
+pub fn query_lp_for_target_value(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    target_value: Uint128,
+    value_denom: String,
+) -> Result<LpForValueResponse, ContractError> {
+    let PoolInfoResponse {
+        pool_info,
+        total_share,
+    } = get_pool_response_at(deps, pool_identifier, env.block.time.seconds())?;
+
+    let quote_index = pool_info
+        .asset_denoms
+        .iter()
+        .position(|denom| denom == &value_denom)
+        .ok_or(ContractError::AssetMismatch)?;
+    ensure!(
+        !pool_info.assets.iter().any(|asset| asset.amount.is_zero()),
+        ContractError::PoolHasNoAssets
+    );
+
+    let pool_value = helpers::compute_pool_value(&pool_info, quote_index)?;
+    let share_of_pool =
+        Decimal256::decimal_with_precision(target_value, pool_info.asset_decimals[quote_index])?
+            .checked_div(pool_value)?;
+
+    let deposit = pool_info
+        .assets
+        .iter()
+        .map(|asset| {
+            Ok(coin(
+                Uint128::try_from(
+                    Decimal256::from_ratio(asset.amount, 1u8)
+                        .checked_mul(share_of_pool)?
+                        .to_uint_floor(),
+                )?
+                .u128(),
+                asset.denom.clone(),
+            ))
+        })
+        .collect::<Result<Vec<Coin>, ContractError>>()?;
+
+    let lp_amount = helpers::compute_deposit_share(&pool_info, &deposit, total_share.amount)?;
+
+    Ok(LpForValueResponse { lp_amount, deposit })
+}
+
+/// The maximum amount of iterations [query_simulation_bench] lets the solver perform.
+const MAX_BENCH_ITERATIONS: u32 = 256;
+
+/// Simulates a swap with the stableswap Newton-Raphson solver capped at `max_iterations`
+/// iterations, itself capped at [MAX_BENCH_ITERATIONS], for each of the invariant and the new ask
+/// pool amount. Reports the iterations performed by both approximations together, and whether
+/// both converged. A swap that doesn't converge reports the return amount of the last
+/// approximation instead of erroring.
+///
+/// Constant product swaps have a closed form, so they always converge without iterating.
+pub fn query_simulation_bench(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_denom: String,
+    pool_identifier: String,
+    max_iterations: u32,
+) -> Result<BenchSimulationResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (
+        offer_asset_in_pool,
+        ask_asset_in_pool,
+        offer_index,
+        ask_index,
+        offer_decimal,
+        ask_decimal,
+    ) = get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_denom)?;
+
+    let PoolType::StableSwap { amp } = &pool_info.pool_type else {
+        let simulation =
+            helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+
+        return Ok(BenchSimulationResponse {
+            return_amount: simulation.return_amount,
+            iterations_used: 0,
+            converged: true,
+        });
+    };
+
+    let approximation = helpers::compute_stableswap_swap_with_iteration_cap(
+        Uint256::from(pool_info.assets.len() as u128),
+        offer_asset_in_pool.amount,
+        ask_asset_in_pool.amount,
+        offer_asset.amount,
+        pool_info.pool_fees.clone(),
+        amp,
+        offer_decimal,
+        ask_decimal,
+        max_iterations.min(MAX_BENCH_ITERATIONS).into(),
+    )?;
+
+    Ok(BenchSimulationResponse {
+        return_amount: approximation.value.return_amount,
+        iterations_used: approximation.iterations as u32,
+        converged: approximation.converged,
+    })
+}
+
+/// Gets the latest snapshot of the pool reserves taken at or before `at_height`. Snapshots are
+/// taken on swaps, deposits and withdrawals, at most once every
+/// [helpers::RESERVE_SNAPSHOT_INTERVAL] blocks, and only the last
+/// [helpers::MAX_RESERVE_SNAPSHOTS] of them are kept. If `at_height` predates all the kept
+/// snapshots, the oldest one is returned instead.
+pub fn query_historical_reserves(
+    deps: Deps,
+    pool_identifier: String,
+    at_height: u64,
+) -> Result<HistoricalReservesResponse, ContractError> {
+    // make sure the pool exists
+    get_pool_by_identifier(&deps, &pool_identifier)?;
+
+    let snapshots = RESERVE_SNAPSHOTS.prefix(&pool_identifier);
+    let snapshot = match snapshots
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(at_height)),
+            Order::Descending,
+        )
+        .next()
+    {
+        Some(snapshot) => Some(snapshot),
+        None => snapshots
+            .range(deps.storage, None, None, Order::Ascending)
+            .next(),
+    }
+    .transpose()?;
+
+    let (height, reserves) =
+        snapshot.ok_or(ContractError::NoReserveSnapshots { pool_identifier })?;
+
+    Ok(HistoricalReservesResponse { reserves, height })
+}
+
+/// Computes the average price paid for a position of `existing_amount` of the ask asset bought at
+/// `existing_price` after swapping `new_offer` of the offer asset into it. The price is expressed
+/// in offer asset per ask asset, both normalized by their decimals, and the new swap is priced at
+/// its simulated return, after fees. With no existing position the price of the new swap is
+/// returned.
+#[allow(clippy::too_many_arguments)]
+pub fn query_blended_entry_price(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    offer_denom: String,
+    ask_denom: String,
+    existing_amount: Uint128,
+    existing_price: Decimal256,
+    new_offer: Uint128,
+) -> Result<Decimal256, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, _, _, offer_decimal, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom.clone(), ask_denom.clone())?;
+
+    let return_amount = query_simulation(
+        deps,
+        env.clone(),
+        coin(new_offer.u128(), offer_denom),
+        ask_denom,
+        pool_identifier,
+    )?
+    .return_amount;
+
+    let existing_amount = Decimal256::decimal_with_precision(existing_amount, ask_decimal)?;
+    let existing_cost = existing_amount.checked_mul(existing_price)?;
+    let new_amount = Decimal256::decimal_with_precision(return_amount, ask_decimal)?;
+    let new_cost = Decimal256::decimal_with_precision(new_offer, offer_decimal)?;
+
+    Ok(existing_cost
+        .checked_add(new_cost)?
+        .checked_div(existing_amount.checked_add(new_amount)?)?)
+}
+
+/// Computes the fee-free marginal price of `base_denom` in `quote_denom`, the execution price of
+/// selling `size` of the base asset, i.e. the amount of quote asset received after fees per base
+/// asset, and how far apart both are in basis points of the marginal price. The prices are
+/// normalized by the assets' decimals.
+pub fn query_price_summary(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    size: Uint128,
+) -> Result<PriceSummaryResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, base_decimal, quote_decimal) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let mid = helpers::compute_spot_price(&pool_info, base_index, quote_index)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, base_index, quote_index, size)?;
+    let execution =
+        Decimal256::decimal_with_precision(swap_computation.return_amount, quote_decimal)?
+            .checked_div(Decimal256::decimal_with_precision(size, base_decimal)?)?;
+
+    Ok(PriceSummaryResponse {
+        mid,
+        execution,
+        impact_bps: signed_deviation_bps(execution, mid)?.unsigned_abs(),
+    })
+}
+
+/// Classifies the pool's assets into the base and quote assets its price is displayed with. The
+/// quote is the pool's asset coming first in the configured quote denom priority list, and the base
+/// is the lexicographically first of the others. When none of the pool's assets is in the list,
+/// the lexicographically first denom is the base and the next one the quote.
+pub fn query_asset_roles(
+    deps: Deps,
+    pool_identifier: String,
+) -> Result<AssetRolesResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let quote_denom_priority = CONFIG.load(deps.storage)?.quote_denom_priority;
+
+    let (base, quote) = asset_roles(&pool_info, &quote_denom_priority);
+
+    Ok(AssetRolesResponse { base, quote })
+}
+
+/// Gets the base and quote assets of the pool given `quote_denom_priority`, as described in
+/// [query_asset_roles].
+fn asset_roles(pool_info: &PoolInfo, quote_denom_priority: &[String]) -> (String, String) {
+    let mut denoms = pool_info.asset_denoms.clone();
+    denoms.sort();
+
+    let quote_index = quote_denom_priority
+        .iter()
+        .find_map(|quote_denom| denoms.iter().position(|denom| denom == quote_denom))
+        .unwrap_or(1);
+    let quote = denoms.remove(quote_index);
+    let base = denoms.remove(0);
+
+    (base, quote)
+}
+
+/// Simulates swapping `offer_asset` in the given fractions, one after the other, each against the
+/// reserves left by the previous ones, e.g. to model an order split over time. The amount of each
+/// fraction is rounded down, and the cumulative return amount is reported after each of them. The
+/// fractions must sum to at most one.
+pub fn query_fractional_simulation(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_denom: String,
+    pool_identifier: String,
+    fractions: Vec<Decimal256>,
+) -> Result<FractionalSimulationResponse, ContractError> {
+    let total_fraction = fractions
+        .iter()
+        .try_fold(Decimal256::zero(), |acc, fraction| {
+            acc.checked_add(*fraction)
+        })?;
+    ensure!(
+        total_fraction <= Decimal256::one(),
+        ContractError::InvalidSwapFractions(total_fraction)
+    );
+
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_denom)?;
+
+    let mut return_amount = Uint128::zero();
+    let mut results = Vec::with_capacity(fractions.len());
+    for fraction in fractions {
+        let offer_amount = Uint128::try_from(
+            Decimal256::from_ratio(offer_asset.amount, 1u8)
+                .checked_mul(fraction)?
+                .to_uint_floor(),
+        )?;
+
+        let swap_computation =
+            helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)?;
+        helpers::apply_swap_to_pool(
+            &mut pool_info,
+            offer_index,
+            ask_index,
+            offer_amount,
+            &swap_computation,
+        )?;
+
+        return_amount = return_amount.checked_add(swap_computation.return_amount)?;
+        results.push(return_amount);
+    }
+
+    Ok(FractionalSimulationResponse { results })
+}
+
+/// Splits `total_offer` between two pools of the same pair so that the fees paid to each of them,
+/// i.e. the swap, protocol, burn and extra fees taken from the ask asset, are as equal as possible.
+/// This is an alternative allocation objective to maximizing the return amount.
+///
+/// The fees paid to a pool grow with the amount swapped through it, so the split is found with a
+/// binary search for the smallest amount sent to the first pool for which its fees reach the
+/// second pool's. A swap that fails to compute is treated as paying more fees than the other one,
+/// steering the search away from it.
+pub fn query_equal_fee_split(
+    deps: Deps,
+    env: Env,
+    pool_a: String,
+    pool_b: String,
+    offer_denom: String,
+    ask_denom: String,
+    total_offer: Uint128,
+) -> Result<EqualFeeSplitResponse, ContractError> {
+    let pools = [pool_a, pool_b]
+        .iter()
+        .map(|pool_identifier| {
+            let pool_info = get_pool_at(&deps, pool_identifier, env.block.time.seconds())?;
+            let (_, _, offer_index, ask_index, _, _) =
+                get_asset_indexes_in_pool(&pool_info, offer_denom.clone(), ask_denom.clone())?;
+            Ok((pool_info, offer_index, ask_index))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let fees_paid = |pool: usize, offer_amount: Uint128| -> Option<Uint128> {
+        let (pool_info, offer_index, ask_index) = &pools[pool];
+        let swap =
+            helpers::compute_swap_for_pool(pool_info, *offer_index, *ask_index, offer_amount)
+                .ok()?;
+
+        [
+            swap.swap_fee_amount,
+            swap.protocol_fee_amount,
+            swap.burn_fee_amount,
+            swap.extra_fees_amount,
+        ]
+        .into_iter()
+        .try_fold(Uint128::zero(), |acc, fee| acc.checked_add(fee).ok())
+    };
+
+    let split_a = helpers::binary_search_amount(Uint128::zero(), total_offer, |split_a| {
+        match (fees_paid(0, split_a), fees_paid(1, total_offer - split_a)) {
+            (Some(fees_a), Some(fees_b)) => fees_a >= fees_b,
+            (None, _) => true,
+            (_, None) => false,
+        }
+    });
+
+    Ok(EqualFeeSplitResponse {
+        split_a,
+        split_b: total_offer - split_a,
+    })
+}
+
+/// Computes the ratio between the reserves of `numerator_denom` and `denominator_denom`, both
+/// normalized by their decimals. Unlike the spot price this ignores the pool curve, it's the raw
+/// balance ratio, e.g. to gauge the health of a pool at a glance.
+pub fn query_reserve_ratio(
+    deps: Deps,
+    pool_identifier: String,
+    numerator_denom: String,
+    denominator_denom: String,
+) -> Result<ReserveRatioResponse, ContractError> {
+    let pool_info = get_pool_by_identifier(&deps, &pool_identifier)?;
+    let (numerator_reserve, denominator_reserve, _, _, numerator_decimal, denominator_decimal) =
+        get_asset_indexes_in_pool(&pool_info, numerator_denom, denominator_denom)?;
+    ensure!(
+        !denominator_reserve.amount.is_zero(),
+        ContractError::PoolHasNoAssets
+    );
+
+    let ratio = Decimal256::decimal_with_precision(numerator_reserve.amount, numerator_decimal)?
+        .checked_div(Decimal256::decimal_with_precision(
+            denominator_reserve.amount,
+            denominator_decimal,
+        )?)?;
+
+    Ok(ReserveRatioResponse { ratio })
+}
+
+/// Simulates swaps of `steps` evenly spaced offer amounts from `min_amount` to `max_amount`, both
+/// included, each against the current reserves. A single step simulates `min_amount` only. The
+/// amount of steps is capped at [MAX_LIMIT].
+#[allow(clippy::too_many_arguments)]
+pub fn query_simulation_range(
+    deps: Deps,
+    env: Env,
+    offer_denom: String,
+    ask_denom: String,
+    pool_identifier: String,
+    min_amount: Uint128,
+    max_amount: Uint128,
+    steps: u32,
+) -> Result<RangeSimulationResponse, ContractError> {
+    ensure!(
+        min_amount <= max_amount,
+        ContractError::InvalidSimulationRange {
+            min_amount,
+            max_amount,
+        }
+    );
+
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, offer_denom, ask_denom)?;
+
+    let steps = steps.min(MAX_LIMIT);
+    let range = max_amount.checked_sub(min_amount)?;
+
+    let points = (0..steps)
+        .map(|step| {
+            let offer_amount = if steps == 1 {
+                min_amount
+            } else {
+                min_amount.checked_add(range.multiply_ratio(step, steps - 1))?
+            };
+            let swap_computation =
+                helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_amount)?;
+
+            Ok((offer_amount, swap_computation.return_amount))
+        })
+        .collect::<Result<Vec<(Uint128, Uint128)>, ContractError>>()?;
+
+    Ok(RangeSimulationResponse { points })
+}
+
+/// Simulates swapping `standard_offer` of `offer_denom` into `ask_denom` through every pool holding
+/// both, and returns the pool with the highest return, fees included. Unlike the canonical pool of
+/// a pair, which is the deepest one, this compares the actual output for the given size. The pools
+/// the swap fails on are skipped, and ties go to the pool scanned first. This iterates over every
+/// pool, so it's meant for occasional use.
+pub fn query_best_pool_for_pair(
+    deps: Deps,
+    env: Env,
+    offer_denom: String,
+    ask_denom: String,
+    standard_offer: Uint128,
+) -> Result<BestPoolResponse, ContractError> {
+    let mut best: Option<BestPoolResponse> = None;
+
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, mut pool) = item?;
+        let position = |denom: &String| pool.asset_denoms.iter().position(|d| d == denom);
+        let (Some(offer_index), Some(ask_index)) = (position(&offer_denom), position(&ask_denom))
+        else {
+            continue;
+        };
+        if offer_index == ask_index {
+            continue;
+        }
+        apply_amp_ramp(deps.storage, &mut pool, env.block.time.seconds())?;
+
+        let Ok(swap_computation) =
+            helpers::compute_swap_for_pool(&pool, offer_index, ask_index, standard_offer)
+        else {
+            continue;
+        };
+
+        if best
+            .as_ref()
+            .is_none_or(|b| swap_computation.return_amount > b.return_amount)
+        {
+            best = Some(BestPoolResponse {
+                pool_identifier: pool.pool_identifier,
+                return_amount: swap_computation.return_amount,
+            });
+        }
+    }
+
+    best.ok_or(ContractError::NoPoolForPair {
+        offer_denom,
+        ask_denom,
+    })
+}
+
+/// Gets the slippage coefficient of the pool for selling `base_denom` against `quote_denom`, as
+/// computed analytically by [helpers::compute_slippage_coefficient] at the current reserves.
+pub fn query_slippage_coefficient(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+) -> Result<SlippageCoefficientResponse, ContractError> {
+    let pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    Ok(SlippageCoefficientResponse {
+        coefficient: helpers::compute_slippage_coefficient(&pool_info, base_index, quote_index)?,
+    })
+}
+
+/// Compares the `proposed` config against the current one, and warns about the changes that could
+/// disrupt the existing pools or their users: a new fee collector or farm manager address, swaps,
+/// deposits or withdrawals getting disabled, pools moving to another fee tier under the new
+/// thresholds, and pools whose quote asset changes under the new quote denom priority. The pool
+/// creation fee only affects pools yet to be created, so changing it is not flagged. The config
+/// holds no bounds on the pools' parameters, e.g. their amplification, so there are none to check.
+///
+/// This iterates over every pool, so it's meant for occasional use, e.g. ahead of a governance
+/// proposal.
+pub fn query_config_migration_check(
+    deps: Deps,
+    proposed: Config,
+) -> Result<MigrationCheckResponse, ContractError> {
+    let current = CONFIG.load(deps.storage)?;
+    let mut warnings = vec![];
+
+    if proposed.fee_collector_addr != current.fee_collector_addr {
+        warnings.push(format!(
+            "The fee collector address changes from {} to {}",
+            current.fee_collector_addr, proposed.fee_collector_addr
+        ));
+    }
+    if proposed.farm_manager_addr != current.farm_manager_addr {
+        warnings.push(format!(
+            "The farm manager address changes from {} to {}",
+            current.farm_manager_addr, proposed.farm_manager_addr
+        ));
+    }
+
+    for (feature, current_enabled, proposed_enabled) in [
+        (
+            "Swaps",
+            current.feature_toggle.swaps_enabled,
+            proposed.feature_toggle.swaps_enabled,
+        ),
+        (
+            "Deposits",
+            current.feature_toggle.deposits_enabled,
+            proposed.feature_toggle.deposits_enabled,
+        ),
+        (
+            "Withdrawals",
+            current.feature_toggle.withdrawals_enabled,
+            proposed.feature_toggle.withdrawals_enabled,
+        ),
+    ] {
+        if current_enabled && !proposed_enabled {
+            warnings.push(format!("{feature} get disabled"));
+        }
+    }
+
+    for item in POOLS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, pool) = item?;
+
+        let current_tier = fee_tier(&pool, &current.fee_tier_thresholds);
+        let proposed_tier = fee_tier(&pool, &proposed.fee_tier_thresholds);
+        if current_tier != proposed_tier {
+            warnings.push(format!(
+                "Pool {} moves from the {current_tier:?} to the {proposed_tier:?} fee tier",
+                pool.pool_identifier
+            ));
+        }
+
+        let (_, current_quote) = asset_roles(&pool, &current.quote_denom_priority);
+        let (_, proposed_quote) = asset_roles(&pool, &proposed.quote_denom_priority);
+        if current_quote != proposed_quote {
+            warnings.push(format!(
+                "Pool {} changes its quote asset from {current_quote} to {proposed_quote}",
+                pool.pool_identifier
+            ));
+        }
+    }
+
+    Ok(MigrationCheckResponse {
+        safe: warnings.is_empty(),
+        warnings,
+    })
+}
+
+/// Simulates a swap in a fee-compounding variant of the pool, where every fee is reinvested into
+/// it. When a swap is executed, the swap fee stays in the pool while the protocol and burn fees
+/// leave it, for the fee collector and to be burned. Here none of them do, so the ask reserve only
+/// loses the return amount. The return amount is the same in both models, as the fees are deducted
+/// from it either way; only their destination differs.
+///
+/// The share value increase is the value of the fees left in the pool relative to the value of
+/// the rest of the pool after the swap, both in the ask asset, i.e. how much they grow the value
+/// of each LP token.
+pub fn query_simulation_with_reinvest(
+    deps: Deps,
+    env: Env,
+    offer_asset: Coin,
+    ask_asset_denom: String,
+    pool_identifier: String,
+) -> Result<ReinvestSimulationResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, offer_index, ask_index, _, ask_decimal) =
+        get_asset_indexes_in_pool(&pool_info, offer_asset.denom, ask_asset_denom)?;
+
+    let swap_computation =
+        helpers::compute_swap_for_pool(&pool_info, offer_index, ask_index, offer_asset.amount)?;
+    let simulation = swap_computation.to_simulation_response();
+
+    let reinvested_fees = simulation
+        .swap_fee_amount
+        .checked_add(simulation.protocol_fee_amount)?
+        .checked_add(simulation.burn_fee_amount)?
+        .checked_add(simulation.extra_fees_amount)?;
+
+    pool_info.assets[offer_index].amount = pool_info.assets[offer_index]
+        .amount
+        .checked_add(offer_asset.amount)?;
+    pool_info.assets[ask_index].amount = pool_info.assets[ask_index]
+        .amount
+        .checked_sub(simulation.return_amount)?;
+
+    let reinvested_value = Decimal256::decimal_with_precision(reinvested_fees, ask_decimal)?;
+    let pool_value = helpers::compute_pool_value(&pool_info, ask_index)?;
+    let value_without_fees = pool_value.checked_sub(reinvested_value)?;
+
+    let share_value_increase = if value_without_fees.is_zero() {
+        Decimal256::zero()
+    } else {
+        reinvested_value.checked_div(value_without_fees)?
+    };
+
+    Ok(ReinvestSimulationResponse {
+        simulation,
+        share_value_increase,
+    })
+}
+
+/// Computes the realized volatility of the spot price of `base_denom` in `quote_denom`, i.e. the
+/// population standard deviation of the log-returns between consecutive observations. The
+/// observations are the snapshots of the pool reserves taken within the last `window_blocks`
+/// blocks, as kept by [helpers::snapshot_reserves], so the volatility is per
+/// [helpers::RESERVE_SNAPSHOT_INTERVAL] blocks at most and isn't annualized. The snapshots the
+/// pool had no price at are skipped. Returns zero when fewer than two observations are left.
+pub fn query_realized_volatility(
+    deps: Deps,
+    env: Env,
+    pool_identifier: String,
+    base_denom: String,
+    quote_denom: String,
+    window_blocks: u32,
+) -> Result<VolatilityResponse, ContractError> {
+    let mut pool_info = get_pool_at(&deps, &pool_identifier, env.block.time.seconds())?;
+    let (_, _, base_index, quote_index, _, _) =
+        get_asset_indexes_in_pool(&pool_info, base_denom, quote_denom)?;
+
+    let start_height = env.block.height.saturating_sub(window_blocks.into());
+
+    let mut log_prices = vec![];
+    for item in RESERVE_SNAPSHOTS.prefix(&pool_identifier).range(
+        deps.storage,
+        Some(Bound::inclusive(start_height)),
+        None,
+        Order::Ascending,
+    ) {
+        let (_, reserves) = item?;
+        pool_info.assets = reserves;
+
+        let Ok(price) = helpers::compute_spot_price(&pool_info, base_index, quote_index) else {
+            continue;
+        };
+        if price.is_zero() {
+            continue;
+        }
+
+        log_prices.push(price.checked_ln()?);
+    }
+
+    if log_prices.len() < 2 {
+        return Ok(VolatilityResponse {
+            volatility: Decimal256::zero(),
+        });
+    }
+
+    let log_returns = log_prices
+        .windows(2)
+        .map(|pair| pair[1].checked_sub(pair[0]))
+        .collect::<Result<Vec<SignedDecimal256>, _>>()?;
+    let count = SignedDecimal256::checked_from_ratio(log_returns.len() as u128, 1u128)?;
+
+    let mean = log_returns
+        .iter()
+        .try_fold(SignedDecimal256::zero(), |acc, log_return| {
+            acc.checked_add(*log_return)
+        })?
+        .checked_div(count)?;
+    let variance = log_returns
+        .iter()
+        .try_fold(SignedDecimal256::zero(), |acc, log_return| {
+            let deviation = log_return.checked_sub(mean)?;
+            acc.checked_add(deviation.checked_mul(deviation)?)
+        })?
+        .checked_div(count)?;
+
+    Ok(VolatilityResponse {
+        volatility: Decimal256::try_from(variance)
+            .map_err(|_| ContractError::DecimalOverflow)?
+            .sqrt(),
+    })
+}
@@ -1,5 +1,6 @@
 use cosmwasm_std::{
-    attr, coin, ensure, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, MessageInfo, Response, Uint128,
+    attr, coin, ensure, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo, Response,
+    Uint128,
 };
 
 use amm::coin::burn_coin_msg;
@@ -9,7 +10,7 @@ use amm::pool_manager::SwapOperation;
 use crate::{state::CONFIG, swap::perform_swap::perform_swap, ContractError};
 
 /// Checks that the output of each [`SwapOperation`] acts as the input of the next swap.
-fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError> {
+pub(crate) fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError> {
     // check that the output of each swap is the input of the next swap
     let mut previous_output_info = operations
         .first()
@@ -33,6 +34,7 @@ fn assert_operations(operations: Vec<SwapOperation>) -> Result<(), ContractError
 
 pub fn execute_swap_operations(
     mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     operations: Vec<SwapOperation>,
     minimum_receive: Option<Uint128>,
@@ -90,6 +92,7 @@ pub fn execute_swap_operations(
 
                 let swap_result = perform_swap(
                     deps.branch(),
+                    &env,
                     previous_swap_output.clone(),
                     token_out_denom,
                     pool_identifier,
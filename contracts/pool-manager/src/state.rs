@@ -1,8 +1,8 @@
 pub use amm::pool_manager::Config;
-use amm::pool_manager::{PoolInfo, SwapOperation};
+use amm::pool_manager::{PoolInfo, PoolType, SwapOperation};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Coin, Decimal, Deps};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, UniqueIndex};
+use cosmwasm_std::{Coin, Decimal, Decimal256, Deps, Fraction, Storage, Uint128, Uint256};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, UniqueIndex};
 
 use crate::ContractError;
 
@@ -80,6 +80,36 @@ pub fn get_pool_by_identifier(
         .ok_or(ContractError::UnExistingPool)
 }
 
+/// Gets the pool given its identifier, with the amp of a stableswap pool set to the one its amp
+/// ramp is at, at `time`. This is the amp swaps and deposits are priced with.
+pub fn get_pool_at(
+    deps: &Deps,
+    pool_identifier: &str,
+    time: u64,
+) -> Result<PoolInfo, ContractError> {
+    let mut pool = get_pool_by_identifier(deps, pool_identifier)?;
+    apply_amp_ramp(deps.storage, &mut pool, time)?;
+
+    Ok(pool)
+}
+
+/// Sets the amp of a stableswap `pool` loaded from [POOLS] to the one its amp ramp is at, at
+/// `time`, like [get_pool_at] does. Meant for the pools iterated over rather than loaded by their
+/// identifier.
+pub fn apply_amp_ramp(
+    storage: &dyn Storage,
+    pool: &mut PoolInfo,
+    time: u64,
+) -> Result<(), ContractError> {
+    if let PoolType::StableSwap { amp } = &mut pool.pool_type {
+        if let Some(ramp) = AMP_RAMPS.may_load(storage, &pool.pool_identifier)? {
+            *amp = ramp.amp_at(time);
+        }
+    }
+
+    Ok(())
+}
+
 /// Swap routes are used to establish defined routes for a given fee
 /// token to a desired fee token and is used for fee collection
 #[cw_serde]
@@ -89,5 +119,77 @@ pub struct SwapOperations {
     /// The operations to be executed for a given swap.
     pub swap_operations: Vec<SwapOperation>,
 }
+/// The schedule of a linear ramp of the amplification coefficient of a stableswap pool.
+///
+/// Swaps, deposits, withdrawals and the queries pricing the pool use the amp the ramp is at, see
+/// [get_pool_at], and the executions store it in the pool type when saving the pool.
+#[cw_serde]
+pub struct AmpRamp {
+    /// The amp when the ramp started.
+    pub initial_amp: u64,
+    /// The amp to reach at the end of the ramp.
+    pub target_amp: u64,
+    /// The timestamp, in seconds, at which the ramp started.
+    pub start_time: u64,
+    /// The timestamp, in seconds, at which the ramp ends.
+    pub end_time: u64,
+}
+
+impl AmpRamp {
+    /// Returns the fraction of the ramp window elapsed at the given time, capped at one.
+    pub fn progress_at(&self, time: u64) -> Decimal256 {
+        if time >= self.end_time || self.end_time <= self.start_time {
+            return Decimal256::one();
+        }
+
+        Decimal256::from_ratio(
+            time.saturating_sub(self.start_time),
+            self.end_time - self.start_time,
+        )
+    }
+
+    /// Returns the amp at the given time, linearly interpolated between the initial and the
+    /// target amp.
+    pub fn amp_at(&self, time: u64) -> u64 {
+        let progress = self.progress_at(time);
+        let (low, high) = (
+            self.initial_amp.min(self.target_amp),
+            self.initial_amp.max(self.target_amp),
+        );
+        // the delta is bounded by high - low, as the progress is capped at one
+        let delta =
+            Uint256::from(high - low).multiply_ratio(progress.numerator(), progress.denominator());
+        let delta = Uint128::try_from(delta).map_or(u64::MAX, |delta| delta.u128() as u64);
+
+        if self.target_amp >= self.initial_amp {
+            self.initial_amp.saturating_add(delta)
+        } else {
+            self.initial_amp.saturating_sub(delta)
+        }
+    }
+}
+
+/// The cumulative volume and fees of the swaps performed in a pool charging fees. Both are
+/// normalized by the decimals of the asset each swap was charged in.
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeAccumulator {
+    /// The sum of the swaps' outputs before fees, i.e. the amounts the fees were charged on.
+    pub volume: Decimal256,
+    /// The sum of the fees charged on the swaps.
+    pub fees: Decimal256,
+}
+
+pub const FEE_ACCUMULATORS: Map<&str, FeeAccumulator> = Map::new("fee_accumulators");
+/// The highest value a unit of a pool's LP token has reached, keyed by the pool identifier and the
+/// denom it's valued in. Recorded on swaps and deposits.
+pub const SHARE_PRICE_HIGH_WATER_MARKS: Map<(&str, &str), Decimal256> =
+    Map::new("share_price_high_water_marks");
+
+/// Snapshots of the pool reserves, keyed by the pool identifier and the block height they were
+/// taken at. Taken on swaps, deposits and withdrawals, at most once per snapshot interval.
+pub const RESERVE_SNAPSHOTS: Map<(&str, u64), Vec<Coin>> = Map::new("reserve_snapshots");
+
+pub const AMP_RAMPS: Map<&str, AmpRamp> = Map::new("amp_ramps");
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const POOL_COUNTER: Item<u64> = Item::new("pool_count");
@@ -1,5 +1,5 @@
 use cosmwasm_std::Decimal;
-use cosmwasm_std::{ensure, Addr, BankMsg, CosmosMsg, DepsMut, MessageInfo, Response};
+use cosmwasm_std::{ensure, Addr, BankMsg, CosmosMsg, DepsMut, Env, MessageInfo, Response};
 
 use amm::coin::burn_coin_msg;
 use amm::common::validate_addr_or_default;
@@ -12,6 +12,7 @@ use super::perform_swap::perform_swap;
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
     mut deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     ask_asset_denom: String,
@@ -50,6 +51,7 @@ pub fn swap(
     // perform the swap
     let swap_result = perform_swap(
         deps.branch(),
+        &env,
         offer_asset.clone(),
         ask_asset_denom,
         pool_identifier,
@@ -1,15 +1,16 @@
 use std::str::FromStr;
 
 use cosmwasm_std::{
-    Coin, Decimal, Decimal256, DepsMut, Fraction, StdError, StdResult, Uint128, Uint256,
+    Coin, Decimal, Decimal256, DepsMut, Env, Fraction, StdError, StdResult, Uint128, Uint256,
 };
 
 use amm::pool_manager::PoolInfo;
 
-use crate::helpers::{aggregate_outgoing_fees, get_asset_indexes_in_pool};
+use crate::helpers::{apply_swap_to_pool, get_asset_indexes_in_pool};
+use crate::math::Decimal256Helper;
 use crate::{
     helpers,
-    state::{get_pool_by_identifier, POOLS},
+    state::{get_pool_at, FeeAccumulator, FEE_ACCUMULATORS, POOLS},
     ContractError,
 };
 
@@ -39,13 +40,14 @@ pub struct SwapResult {
 /// of each field in [`SwapResult`] (besides fields like `spread_amount`).
 pub fn perform_swap(
     deps: DepsMut,
+    env: &Env,
     offer_asset: Coin,
     ask_asset_denom: String,
     pool_identifier: String,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
 ) -> Result<SwapResult, ContractError> {
-    let mut pool_info = get_pool_by_identifier(&deps.as_ref(), &pool_identifier)?;
+    let mut pool_info = get_pool_at(&deps.as_ref(), &pool_identifier, env.block.time.seconds())?;
 
     let (
         offer_asset_in_pool,
@@ -84,21 +86,46 @@ pub fn perform_swap(
     )?;
 
     // State changes to the pools balances
-    {
-        // add the offer amount to the pool
-        pool_info.assets[offer_index].amount = pool_info.assets[offer_index]
-            .amount
-            .checked_add(offer_asset.amount)?;
-
-        // Deduct the return amount and fees from the pool
-        let outgoing_fees = aggregate_outgoing_fees(&swap_computation.to_simulation_response())?;
-
-        pool_info.assets[ask_index].amount = pool_info.assets[ask_index]
-            .amount
-            .checked_sub(return_asset.amount)?
-            .checked_sub(outgoing_fees)?;
-
-        POOLS.save(deps.storage, &pool_identifier, &pool_info)?;
+    apply_swap_to_pool(
+        &mut pool_info,
+        offer_index,
+        ask_index,
+        offer_asset.amount,
+        &swap_computation,
+    )?;
+    POOLS.save(deps.storage, &pool_identifier, &pool_info)?;
+
+    let total_share = deps.querier.query_supply(&pool_info.lp_denom)?.amount;
+    helpers::update_share_price_high_water_marks(deps.storage, &pool_info, total_share)?;
+    helpers::snapshot_reserves(deps.storage, env.block.height, &pool_info)?;
+
+    let fees_amount = [
+        swap_computation.swap_fee_amount,
+        swap_computation.protocol_fee_amount,
+        swap_computation.burn_fee_amount,
+        swap_computation.extra_fees_amount,
+    ]
+    .into_iter()
+    .try_fold(Uint128::zero(), |acc, fee| acc.checked_add(fee))?;
+    // pools charging no fees have nothing to capture, saving a write on each of their swaps. The
+    // swaps whose fees round down to zero are still accounted for, that's the loss to capture
+    if !pool_info.pool_fees.total_fee_share().is_zero() {
+        let fees = Decimal256::decimal_with_precision(fees_amount, ask_decimal)?;
+        let volume = Decimal256::decimal_with_precision(
+            swap_computation.return_amount.checked_add(fees_amount)?,
+            ask_decimal,
+        )?;
+        FEE_ACCUMULATORS.update(
+            deps.storage,
+            &pool_identifier,
+            |accumulator| -> Result<_, ContractError> {
+                let accumulator = accumulator.unwrap_or_default();
+                Ok(FeeAccumulator {
+                    volume: accumulator.volume.checked_add(volume)?,
+                    fees: accumulator.fees.checked_add(fees)?,
+                })
+            },
+        )?;
     }
 
     let burn_fee_asset = Coin {
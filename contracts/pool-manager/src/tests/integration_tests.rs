@@ -287,7 +287,7 @@ mod pool_creation_failures {
                 &creator,
                 vec!["uom".to_string(), "uom".to_string()],
                 vec![6u8, 6u8],
-                pool_fees,
+                pool_fees.clone(),
                 PoolType::ConstantProduct,
                 None,
                 vec![coin(1000, "uusd"), coin(8888, "uom")],
@@ -298,6 +298,22 @@ mod pool_creation_failures {
                         _ => panic!("Wrong error type, should return ContractError::SameAsset"),
                     }
                 },
+            )
+            .create_pool(
+                &creator,
+                vec!["uom".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                pool_fees,
+                PoolType::StableSwap { amp: 0 },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidAmp { .. } => {}
+                        _ => panic!("Wrong error type, should return ContractError::InvalidAmp"),
+                    }
+                },
             );
     }
 
@@ -2899,16 +2915,107 @@ mod swapping {
                 assert_eq!(
                     result.unwrap().amount,
                     Uint128::new(
-                        300_000_000_000_000_000000000000000000u128
+                        300_000_000_000_000_000_000_000_000_000_000u128
                             + 72_265_093_054_925_102133454380390377u128
                     )
                 );
             });
     }
+
+    #[test]
+    fn reverse_simulation_stable_18_digits() {
+        let mut suite = TestingSuite::default_with_balances(
+            vec![
+                coin(1_000_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000_000u128, "uusd".to_string()),
+                coin(
+                    300_000_000_000_000_000_000_000_000_000_000u128,
+                    "ausdy".to_string(),
+                ),
+                coin(
+                    300_000_000_000_000_000_000_000_000_000_000u128,
+                    "pusdc".to_string(),
+                ),
+                coin(1_000_000_000_000u128, "uom".to_string()),
+            ],
+            StargateMock::new("uom".to_string(), "8888".to_string()),
+        );
+        let alice = suite.creator();
+
+        // both assets have the max precision, so the reverse simulation doesn't scale the amounts
+        suite
+            .instantiate_default()
+            .add_one_epoch()
+            .create_pool(
+                &alice,
+                vec!["ausdy".to_string(), "pusdc".to_string()],
+                vec![18u8, 18u8],
+                PoolFee {
+                    protocol_fee: Fee {
+                        share: Decimal::zero(),
+                    },
+                    swap_fee: Fee {
+                        share: Decimal::zero(),
+                    },
+                    burn_fee: Fee {
+                        share: Decimal::zero(),
+                    },
+                    extra_fees: vec![],
+                },
+                PoolType::StableSwap { amp: 100 },
+                None,
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &alice,
+                "p.1".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(
+                        1_000_000_000_000_000_000_000_000_000_000u128,
+                        "ausdy".to_string(),
+                    ),
+                    coin(
+                        1_000_000_000_000_000_000_000_000_000_000u128,
+                        "pusdc".to_string(),
+                    ),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let ask_amount = Uint128::new(10_000_000_000_000_000_000_000_000_000u128);
+        let offer_amount = RefCell::new(Uint128::zero());
+
+        suite
+            .query_reverse_simulation(
+                "p.1".to_string(),
+                coin(ask_amount.u128(), "ausdy".to_string()),
+                "pusdc".to_string(),
+                |result| {
+                    *offer_amount.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            .query_simulation(
+                "p.1".to_string(),
+                coin(offer_amount.borrow().u128(), "pusdc".to_string()),
+                "ausdy".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, ask_amount);
+                },
+            );
+    }
 }
 
 mod ownership {
-    use amm::pool_manager::FeatureToggle;
+    use amm::pool_manager::{FeatureToggle, FeeTierThresholds};
 
     use super::*;
 
@@ -2985,6 +3092,8 @@ mod ownership {
             None,
             None,
             None,
+            None,
+            None,
             |result| {
                 let err = result.unwrap_err().downcast::<ContractError>().unwrap();
 
@@ -3029,6 +3138,11 @@ mod ownership {
                 swaps_enabled: false,
                 withdrawals_enabled: false,
             }),
+            Some(FeeTierThresholds {
+                stable_max: Decimal::permille(2),
+                standard_max: Decimal::percent(1),
+            }),
+            Some(vec!["uusd".to_string()]),
             |res| {
                 res.unwrap();
             },
@@ -3039,6 +3153,14 @@ mod ownership {
         assert_ne!(config.pool_creation_fee, initial_config.pool_creation_fee);
         assert_ne!(config.feature_toggle, initial_config.feature_toggle);
         assert_ne!(config.farm_manager_addr, initial_config.farm_manager_addr);
+        assert_ne!(
+            config.fee_tier_thresholds,
+            initial_config.fee_tier_thresholds
+        );
+        assert_ne!(
+            config.quote_denom_priority,
+            initial_config.quote_denom_priority
+        );
     }
 }
 
@@ -1,2 +1,3 @@
 pub mod integration_tests;
+pub mod queries;
 pub mod suite;
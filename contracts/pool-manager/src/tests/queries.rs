@@ -0,0 +1,6698 @@
+use std::cell::RefCell;
+
+use cosmwasm_std::{coin, Coin, Decimal, Decimal256, Uint128, Uint256};
+
+use amm::fee::{Fee, PoolFee};
+use amm::pool_manager::{PoolType, PoolsResponse};
+use common_testing::multi_test::stargate_mock::StargateMock;
+
+use crate::ContractError;
+
+use super::suite::TestingSuite;
+
+const POOL_IDENTIFIER: &str = "o.whale.uluna";
+
+fn pool_fees(protocol_fee: Decimal, swap_fee: Decimal, burn_fee: Decimal) -> PoolFee {
+    PoolFee {
+        protocol_fee: Fee {
+            share: protocol_fee,
+        },
+        swap_fee: Fee { share: swap_fee },
+        burn_fee: Fee { share: burn_fee },
+        extra_fees: vec![],
+    }
+}
+
+fn zero_fees() -> PoolFee {
+    pool_fees(Decimal::zero(), Decimal::zero(), Decimal::zero())
+}
+
+/// Instantiates the suite and creates the `o.whale.uluna` pool, seeding it with the given liquidity.
+fn setup_pool(pool_type: PoolType, pool_fees: PoolFee, liquidity: Vec<Coin>) -> TestingSuite {
+    let mut suite = TestingSuite::default_with_balances(
+        vec![
+            coin(1_000_000_000_000u128, "uwhale".to_string()),
+            coin(1_000_000_000_000u128, "uluna".to_string()),
+            coin(1_000_000_000u128, "uusd".to_string()),
+            coin(1_000_000_000u128, "uom".to_string()),
+        ],
+        StargateMock::new("uom".to_string(), "8888".to_string()),
+    );
+    let creator = suite.creator();
+
+    suite
+        .instantiate_default()
+        .add_one_epoch()
+        .create_pool(
+            &creator,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            pool_fees,
+            pool_type,
+            Some("whale.uluna".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        )
+        .provide_liquidity(
+            &creator,
+            POOL_IDENTIFIER.to_string(),
+            None,
+            None,
+            None,
+            None,
+            liquidity,
+            |result| {
+                result.unwrap();
+            },
+        );
+
+    suite
+}
+
+mod rebalance_swap {
+    use super::*;
+
+    #[test]
+    fn constant_product_rebalance_reaches_target_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // price of uwhale in uluna is 1, bring it down to 0.64
+        suite.query_rebalance_swap(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Decimal256::percent(64),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.offer_denom, "uwhale");
+                assert_eq!(response.offer_amount, Uint128::new(250_000u128));
+            },
+        );
+
+        // bringing the price up offers the quote asset instead
+        suite.query_rebalance_swap(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Decimal256::from_ratio(1_000_000u128, 640_000u128),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.offer_denom, "uluna");
+                assert_eq!(response.offer_amount, Uint128::new(250_000u128));
+            },
+        );
+
+        suite
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(30)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(250_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+                let pool_info = result.unwrap().pools[0].pool_info.clone();
+                assert_eq!(
+                    pool_info.assets,
+                    vec![
+                        coin(1_250_000u128, "uwhale".to_string()),
+                        coin(800_000u128, "uluna".to_string()),
+                    ]
+                );
+            });
+
+        suite.query_rebalance_swap(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Decimal256::percent(64),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.offer_denom, "uwhale");
+                assert_eq!(response.offer_amount, Uint128::zero());
+            },
+        );
+    }
+
+    #[test]
+    fn stableswap_rebalance_reaches_target_price() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let offer_amount = RefCell::new(Uint128::zero());
+
+        suite.query_rebalance_swap(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Decimal256::percent(99),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.offer_denom, "uwhale");
+                assert!(response.offer_amount > Uint128::zero());
+                *offer_amount.borrow_mut() = response.offer_amount;
+            },
+        );
+
+        suite
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(10)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(offer_amount.borrow().u128(), "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_rebalance_swap(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(99),
+                |result| {
+                    // the pool sits at the target price, give or take the rounding of a few units
+                    let response = result.unwrap();
+                    assert!(response.offer_amount <= Uint128::new(10u128));
+                },
+            );
+    }
+
+    #[test]
+    fn rebalance_rejects_invalid_input() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_rebalance_swap(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::zero(),
+                |result| {
+                    assert!(result.is_err());
+                },
+            )
+            .query_rebalance_swap(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uusd".to_string(),
+                Decimal256::one(),
+                |result| {
+                    assert!(result.is_err());
+                },
+            )
+            .query_rebalance_swap(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::one(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_amount, Uint128::zero());
+                },
+            );
+    }
+}
+
+mod canonical_simulation {
+    use super::*;
+
+    #[test]
+    fn small_trade_costs_are_comparable_across_pool_types() {
+        let total_costs = RefCell::new(vec![]);
+
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::zero(), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000_000u128, "uluna".to_string()),
+                ],
+            );
+
+            suite.query_canonical_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.return_amount < Uint128::new(10_000u128));
+                    total_costs.borrow_mut().push(response.total_cost_bps);
+                },
+            );
+        }
+
+        // both pools charge the 30 bps swap fee, the spread of a small trade is negligible
+        for total_cost in total_costs.borrow().iter() {
+            assert!(*total_cost >= Decimal256::from_ratio(29u128, 1u128));
+            assert!(*total_cost <= Decimal256::from_ratio(32u128, 1u128));
+        }
+    }
+
+    #[test]
+    fn spread_is_included_in_the_total_cost() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // swapping the whole reserve in returns half of the ask reserve, a 50% cost
+        suite.query_canonical_simulation(
+            POOL_IDENTIFIER.to_string(),
+            coin(1_000_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.return_amount, Uint128::new(500_000u128));
+                assert_eq!(
+                    response.total_cost_bps,
+                    Decimal256::from_ratio(5_000u128, 1u128)
+                );
+            },
+        );
+    }
+}
+
+mod lp_value {
+    use super::*;
+
+    #[test]
+    fn lp_value_is_proportional_share_of_reserves() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let lp_denom = suite.get_lp_denom(POOL_IDENTIFIER.to_string());
+
+        // the total share is sqrt(1_000_000 * 4_000_000) = 2_000_000
+        suite
+            .query_lp_value(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(500_000u128),
+                |result| {
+                    assert_eq!(
+                        result.unwrap().assets,
+                        vec![
+                            coin(250_000u128, "uwhale".to_string()),
+                            coin(1_000_000u128, "uluna".to_string()),
+                        ]
+                    );
+                },
+            )
+            .query_lp_value(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(2_000_000u128),
+                |result| {
+                    assert_eq!(
+                        result.unwrap().assets,
+                        vec![
+                            coin(1_000_000u128, "uwhale".to_string()),
+                            coin(4_000_000u128, "uluna".to_string()),
+                        ]
+                    );
+                },
+            )
+            .query_lp_value(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(2_000_001u128),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The amount of LP shares to withdraw is invalid"));
+                },
+            );
+
+        // withdrawing returns the same amounts the query reports
+        suite
+            .withdraw_liquidity(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(500_000u128, lp_denom)],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+                assert_eq!(
+                    result.unwrap().pools[0].pool_info.assets,
+                    vec![
+                        coin(750_000u128, "uwhale".to_string()),
+                        coin(3_000_000u128, "uluna".to_string()),
+                    ]
+                );
+            });
+    }
+}
+
+mod simulation_sanity {
+    use super::*;
+
+    #[test]
+    fn simulation_sanity_rejects_prices_above_bound() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the pool is mispriced against the expectation that uluna is worth at most 0.5 uwhale
+        suite
+            .query_simulation_sanity(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uluna".to_string()),
+                "uwhale".to_string(),
+                Decimal256::percent(50),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("exceeds the maximum reasonable price"));
+                },
+            )
+            .query_simulation_sanity(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uluna".to_string()),
+                "uwhale".to_string(),
+                Decimal256::percent(150),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, Uint128::new(999u128));
+                },
+            );
+    }
+}
+
+mod pool_type_stats {
+    use super::*;
+
+    #[test]
+    fn pool_type_stats_counts_pools_by_type() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        for (pool_type, identifier) in [
+            (PoolType::ConstantProduct, "whale.uluna.2"),
+            (PoolType::StableSwap { amp: 100 }, "whale.uluna.stable"),
+        ] {
+            suite.create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                pool_type,
+                Some(identifier.to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite.query_pool_type_stats(|result| {
+            let response = result.unwrap();
+            assert_eq!(response.constant_product, 2);
+            assert_eq!(response.stableswap, 1);
+        });
+    }
+}
+
+mod simulate_zap_in {
+    use super::*;
+
+    #[test]
+    fn zap_in_uses_the_optimal_split() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the optimal swap is 1_000_000 * (sqrt(1.2) - 1) ~= 95_445 uwhale, minting ~95_445 LP
+        // tokens. Swapping half of the offer would only mint 90_909 LP tokens.
+        suite.query_simulate_zap_in(
+            POOL_IDENTIFIER.to_string(),
+            coin(200_000u128, "uwhale".to_string()),
+            |result| {
+                let response = result.unwrap();
+                assert!(response.lp_amount >= Uint128::new(95_440u128));
+                assert!(response.lp_amount <= Uint128::new(95_446u128));
+                assert!(response.swap_return > Uint128::new(87_000u128));
+                assert!(response.swap_return < Uint128::new(87_200u128));
+            },
+        );
+    }
+
+    #[test]
+    fn zap_in_into_stableswap_pool() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            pool_fees(Decimal::zero(), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_simulate_zap_in(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uluna".to_string()),
+                |result| {
+                    let response = result.unwrap();
+                    // close to 1:1 minus the fees paid on the swapped part
+                    assert!(response.lp_amount > Uint128::new(9_950u128));
+                    assert!(response.lp_amount < Uint128::new(10_000u128));
+                },
+            )
+            .query_simulate_zap_in(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uusd".to_string()),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The asset doesn't match the assets stored in contract"));
+                },
+            );
+    }
+}
+
+mod simulate_zap_out {
+    use super::*;
+
+    #[test]
+    fn zap_out_withdraws_and_swaps_into_target_denom() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // withdrawing 10% returns 100_000 of each, leaving 900_000 / 900_000 in the pool.
+        // Swapping 100_000 uluna returns 900_000 * 100_000 / 1_000_000 = 90_000 uwhale
+        suite
+            .query_simulate_zap_out(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(100_000u128),
+                "uwhale".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, Uint128::new(190_000u128));
+                },
+            )
+            .query_simulate_zap_out(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(100_000u128),
+                "uusd".to_string(),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The asset doesn't match the assets stored in contract"));
+                },
+            )
+            .query_simulate_zap_out(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(1_000_001u128),
+                "uwhale".to_string(),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The amount of LP shares to withdraw is invalid"));
+                },
+            );
+    }
+}
+
+mod amp_ramp_status {
+    use cosmwasm_std::Uint256;
+
+    use super::*;
+
+    #[test]
+    fn amp_ramp_status_interpolates_the_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let other = suite.senders[1].clone();
+        let now = suite.get_time();
+
+        suite
+            .query_amp_ramp_status(POOL_IDENTIFIER.to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.current_amp, Uint256::from(100u128));
+                assert_eq!(response.target_amp, Uint256::from(100u128));
+                assert_eq!(response.progress, Decimal256::one());
+            })
+            .ramp_amp(
+                &other,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert!(matches!(err, ContractError::OwnershipError { .. }));
+                },
+            )
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(err, ContractError::InvalidAmpRamp);
+                },
+            )
+            // ramps shorter than a day are rejected
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.plus_seconds(1_000).seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(err, ContractError::InvalidAmpRamp);
+                },
+            )
+            // so are the ones moving the amp more than 10 times up or down
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                1_001,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(err, ContractError::InvalidAmpRamp);
+                },
+            )
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                9,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(err, ContractError::InvalidAmpRamp);
+                },
+            )
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_time(now.plus_seconds(25_000))
+            .query_amp_ramp_status(POOL_IDENTIFIER.to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.current_amp, Uint256::from(125u128));
+                assert_eq!(response.target_amp, Uint256::from(200u128));
+                assert_eq!(response.progress, Decimal256::percent(25));
+            })
+            .set_time(now.plus_seconds(200_000))
+            .query_amp_ramp_status(POOL_IDENTIFIER.to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.current_amp, Uint256::from(200u128));
+                assert_eq!(response.progress, Decimal256::one());
+            });
+    }
+
+    #[test]
+    fn swaps_are_priced_with_the_ramped_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_500_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        let before_ramp = RefCell::new(Uint128::zero());
+        let after_ramp = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    *before_ramp.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                1_000,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_time(now.plus_seconds(100_000))
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    *after_ramp.borrow_mut() = result.unwrap().return_amount;
+                },
+            );
+
+        // a higher amp flattens the curve, so selling the abundant asset returns more
+        assert!(*after_ramp.borrow() > *before_ramp.borrow());
+
+        suite
+            .swap(
+                &creator,
+                "uwhale".to_string(),
+                None,
+                None,
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uluna".to_string())],
+                |result| {
+                    let return_amount = result
+                        .unwrap()
+                        .events
+                        .into_iter()
+                        .flat_map(|event| event.attributes)
+                        .find(|attribute| attribute.key == "return_amount")
+                        .unwrap()
+                        .value;
+                    assert_eq!(return_amount, after_ramp.borrow().to_string());
+                },
+            )
+            .query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+                assert_eq!(
+                    result.unwrap().pools[0].pool_info.pool_type,
+                    PoolType::StableSwap { amp: 1_000 }
+                );
+            });
+    }
+
+    #[test]
+    fn queries_are_priced_with_the_ramped_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_500_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        // halfway through the ramp, while the stored amp is still the initial one
+        suite
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                1_000,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_time(now.plus_seconds(50_000));
+
+        let returns = RefCell::new(vec![]);
+        let spread = RefCell::new(Uint128::zero());
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    *spread.borrow_mut() = result.unwrap().spread_amount;
+                },
+            )
+            .query_balanced_slippage(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    assert_eq!(result.unwrap().forward_spread, *spread.borrow());
+                },
+            )
+            .query_simulation_with_reserves_out(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    returns
+                        .borrow_mut()
+                        .push(result.unwrap().simulation.return_amount);
+                },
+            )
+            .query_canonical_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    returns.borrow_mut().push(result.unwrap().return_amount);
+                },
+            );
+
+        let expected = returns.borrow()[0];
+        suite
+            .query_effective_bid_ask(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    assert_eq!(
+                        result.unwrap().bid,
+                        Decimal256::from_ratio(expected, 100_000u128)
+                    );
+                },
+            )
+            // the pre-check agrees with the execution on the minimum it can receive
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                expected,
+                Decimal256::percent(50),
+                |result| {
+                    assert!(result.unwrap().would_succeed);
+                },
+            )
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                expected + Uint128::one(),
+                Decimal256::percent(50),
+                |result| {
+                    assert!(!result.unwrap().would_succeed);
+                },
+            )
+            .query_price_matrix(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    let (_, _, return_amount) = result
+                        .unwrap()
+                        .entries
+                        .into_iter()
+                        .find(|(offer, _, _)| offer == "uluna")
+                        .unwrap();
+                    returns.borrow_mut().push(return_amount);
+                },
+            )
+            .query_fractional_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                vec![Decimal256::one()],
+                |result| {
+                    returns.borrow_mut().push(result.unwrap().results[0]);
+                },
+            )
+            .query_best_pool_for_pair(
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    returns.borrow_mut().push(result.unwrap().return_amount);
+                },
+            )
+            .query_simulation_safe(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    returns
+                        .borrow_mut()
+                        .push(result.unwrap().simulation.return_amount);
+                },
+            )
+            .query_simulation_bench(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                256,
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.converged);
+                    returns.borrow_mut().push(response.return_amount);
+                },
+            )
+            .query_simulation_with_reinvest(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    returns
+                        .borrow_mut()
+                        .push(result.unwrap().simulation.return_amount);
+                },
+            )
+            .swap(
+                &creator,
+                "uwhale".to_string(),
+                None,
+                None,
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uluna".to_string())],
+                |result| {
+                    let return_amount = result
+                        .unwrap()
+                        .events
+                        .into_iter()
+                        .flat_map(|event| event.attributes)
+                        .find(|attribute| attribute.key == "return_amount")
+                        .unwrap()
+                        .value;
+                    for queried in returns.borrow().iter() {
+                        assert_eq!(return_amount, queried.to_string());
+                    }
+                },
+            );
+
+        assert_eq!(returns.borrow().len(), 8);
+    }
+
+    #[test]
+    fn amp_ramp_is_rejected_for_constant_product_pools() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        suite
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    assert_eq!(err, ContractError::NotStableSwapPool);
+                },
+            )
+            .query_amp_ramp_status(POOL_IDENTIFIER.to_string(), |result| {
+                let err = result.unwrap_err().to_string();
+                assert!(err.contains("The pool is not a stableswap pool"));
+            });
+    }
+}
+
+mod pool_integrity {
+    use cosmwasm_std::Uint256;
+
+    use super::*;
+
+    #[test]
+    fn pools_stay_consistent_through_swaps_and_withdrawals() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type.clone(),
+                pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+            let creator = suite.creator();
+            let lp_denom = suite.get_lp_denom(POOL_IDENTIFIER.to_string());
+
+            suite
+                .query_pool_integrity(POOL_IDENTIFIER.to_string(), |result| {
+                    let response = result.unwrap();
+                    assert!(response.consistent);
+                    if pool_type == PoolType::ConstantProduct {
+                        assert_eq!(response.expected_k, Uint256::from(1_000_000_000_000u128));
+                        assert_eq!(response.actual_k, Uint256::from(1_000_000_000_000u128));
+                    }
+                })
+                .swap(
+                    &creator,
+                    "uluna".to_string(),
+                    None,
+                    Some(Decimal::percent(20)),
+                    None,
+                    POOL_IDENTIFIER.to_string(),
+                    vec![coin(50_000u128, "uwhale".to_string())],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .withdraw_liquidity(
+                    &creator,
+                    POOL_IDENTIFIER.to_string(),
+                    vec![coin(333_333u128, lp_denom)],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .query_pool_integrity(POOL_IDENTIFIER.to_string(), |result| {
+                    let response = result.unwrap();
+                    assert!(response.consistent);
+                    assert!(response.actual_k > response.expected_k);
+                });
+        }
+    }
+}
+
+mod simulation_conservative {
+    use super::*;
+
+    #[test]
+    fn conservative_simulation_is_met_by_execution() {
+        for (pool_type, margin) in [
+            (PoolType::ConstantProduct, 4u128),
+            (PoolType::StableSwap { amp: 100 }, 8u128),
+        ] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+            let creator = suite.creator();
+
+            let simulated = RefCell::new(Uint128::zero());
+            let conservative = RefCell::new(Uint128::zero());
+
+            suite
+                .query_simulation(
+                    POOL_IDENTIFIER.to_string(),
+                    coin(10_000u128, "uwhale".to_string()),
+                    "uluna".to_string(),
+                    |result| {
+                        *simulated.borrow_mut() = result.unwrap().return_amount;
+                    },
+                )
+                .query_simulation_conservative(
+                    POOL_IDENTIFIER.to_string(),
+                    coin(10_000u128, "uwhale".to_string()),
+                    "uluna".to_string(),
+                    |result| {
+                        *conservative.borrow_mut() = result.unwrap().return_amount;
+                    },
+                );
+
+            assert_eq!(
+                *conservative.borrow(),
+                simulated
+                    .borrow()
+                    .checked_sub(Uint128::new(margin))
+                    .unwrap()
+            );
+
+            let balance_before = RefCell::new(Uint128::zero());
+            suite.query_balance(&creator.to_string(), "uluna", |result| {
+                *balance_before.borrow_mut() = result.unwrap().amount;
+            });
+
+            suite
+                .swap(
+                    &creator,
+                    "uluna".to_string(),
+                    None,
+                    Some(Decimal::percent(10)),
+                    None,
+                    POOL_IDENTIFIER.to_string(),
+                    vec![coin(10_000u128, "uwhale".to_string())],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .query_balance(&creator.to_string(), "uluna", |result| {
+                    let received = result.unwrap().amount - *balance_before.borrow();
+                    assert!(received >= *conservative.borrow());
+                });
+        }
+    }
+}
+
+mod cheaper_direction {
+    use super::*;
+
+    #[test]
+    fn cheaper_direction_offers_the_overpriced_asset() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::zero(), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+            let creator = suite.creator();
+
+            // balanced pool, the lexicographically smaller denom is offered
+            suite
+                .query_cheaper_direction(POOL_IDENTIFIER.to_string(), |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uluna");
+                    assert_eq!(response.ask_denom, "uwhale");
+                })
+                .swap(
+                    &creator,
+                    "uwhale".to_string(),
+                    None,
+                    Some(Decimal::percent(30)),
+                    None,
+                    POOL_IDENTIFIER.to_string(),
+                    vec![coin(200_000u128, "uluna".to_string())],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                // uwhale is now scarce, so offering it gets the better rate
+                .query_cheaper_direction(POOL_IDENTIFIER.to_string(), |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uwhale");
+                    assert_eq!(response.ask_denom, "uluna");
+                });
+        }
+    }
+}
+
+mod fee_recipients {
+    use super::*;
+
+    #[test]
+    fn fee_recipients_are_projected_from_config() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let fee_collector_addr = suite.fee_collector_addr.clone();
+
+        suite.query_fee_recipients(|result| {
+            let response = result.unwrap();
+            assert_eq!(response.protocol_fee_collector, fee_collector_addr);
+            assert_eq!(response.burn_address, None);
+        });
+    }
+}
+
+mod amp_change_lp_impact {
+    use cosmwasm_std::Uint256;
+
+    use super::*;
+
+    #[test]
+    fn lower_amp_lowers_single_asset_value_of_imbalanced_pool() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(10_000_000u128, "uwhale".to_string()),
+                coin(30_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_amp_change_lp_impact(
+                POOL_IDENTIFIER.to_string(),
+                Uint256::from(100u128),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.lp_value_before, response.lp_value_after);
+                },
+            )
+            .query_amp_change_lp_impact(
+                POOL_IDENTIFIER.to_string(),
+                Uint256::from(1u128),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.lp_value_before.len(), 2);
+                    // with a lower amp the curve gives a worse price for the abundant uluna
+                    assert!(response.lp_value_after[0].amount < response.lp_value_before[0].amount);
+                },
+            )
+            .query_amp_change_lp_impact(POOL_IDENTIFIER.to_string(), Uint256::zero(), |result| {
+                let err = result.unwrap_err().to_string();
+                assert!(err.contains("Invalid amp 0"));
+            });
+    }
+
+    #[test]
+    fn amp_change_lp_impact_is_rejected_for_constant_product_pools() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_amp_change_lp_impact(
+            POOL_IDENTIFIER.to_string(),
+            Uint256::from(10u128),
+            |result| {
+                let err = result.unwrap_err().to_string();
+                assert!(err.contains("The pool is not a stableswap pool"));
+            },
+        );
+    }
+}
+
+mod route_encoding {
+    use amm::pool_manager::SwapOperation;
+    use cosmwasm_std::Binary;
+
+    use super::*;
+
+    #[test]
+    fn route_round_trips_through_encoding() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let route = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: POOL_IDENTIFIER.to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uluna".to_string(),
+                token_out_denom: "uwhale".to_string(),
+                pool_identifier: POOL_IDENTIFIER.to_string(),
+            },
+        ];
+
+        let encoded = RefCell::new(Binary::default());
+
+        suite.query_encode_route(route.clone(), |result| {
+            let response = result.unwrap();
+            // 1 length byte + 13 identifier bytes + 2 index bytes per operation
+            assert_eq!(response.encoded.len(), 32);
+            *encoded.borrow_mut() = response.encoded;
+        });
+
+        suite
+            .query_decode_route(encoded.borrow().clone(), |result| {
+                assert_eq!(result.unwrap(), route);
+            })
+            .query_decode_route(Binary::from(vec![13u8, b'o']), |result| {
+                let err = result.unwrap_err().to_string();
+                assert!(err.contains("The encoded route is malformed"));
+            })
+            .query_encode_route(
+                vec![SwapOperation::MantraSwap {
+                    token_in_denom: "uusd".to_string(),
+                    token_out_denom: "uluna".to_string(),
+                    pool_identifier: POOL_IDENTIFIER.to_string(),
+                }],
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The asset doesn't match the assets stored in contract"));
+                },
+            );
+    }
+}
+
+mod simulate_swap_operations {
+    use amm::pool_manager::SwapOperation;
+
+    use super::*;
+
+    #[test]
+    fn repeated_pool_sees_reserves_left_by_previous_hops() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let hop =
+            |token_in: &str, token_out: &str, pool_identifier: &str| SwapOperation::MantraSwap {
+                token_in_denom: token_in.to_string(),
+                token_out_denom: token_out.to_string(),
+                pool_identifier: pool_identifier.to_string(),
+            };
+
+        // the first pool's uluna gets cheaper after the first hop, so going through it again in
+        // the same direction returns less than simulating each hop against the original reserves
+        let route = vec![
+            hop("uwhale", "uluna", POOL_IDENTIFIER),
+            hop("uluna", "uwhale", "o.whale.uluna.2"),
+            hop("uwhale", "uluna", POOL_IDENTIFIER),
+        ];
+
+        let naive_amount = RefCell::new(Uint128::new(100_000u128));
+        for operation in route.iter() {
+            let SwapOperation::MantraSwap {
+                token_in_denom,
+                token_out_denom,
+                pool_identifier,
+            } = operation.clone();
+
+            let offer_amount = *naive_amount.borrow();
+            suite.query_simulation(
+                pool_identifier,
+                coin(offer_amount.u128(), token_in_denom),
+                token_out_denom,
+                |result| {
+                    *naive_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            );
+        }
+
+        suite.query_simulate_swap_operations(Uint128::new(100_000u128), route, |result| {
+            let amount = result.unwrap().amount;
+            assert!(amount < *naive_amount.borrow());
+        });
+
+        // a round trip through a single pool can't return more than the offer
+        suite.query_simulate_swap_operations(
+            Uint128::new(100_000u128),
+            vec![
+                hop("uwhale", "uluna", POOL_IDENTIFIER),
+                hop("uluna", "uwhale", POOL_IDENTIFIER),
+            ],
+            |result| {
+                assert!(result.unwrap().amount <= Uint128::new(100_000u128));
+            },
+        );
+    }
+}
+
+mod fee_split {
+    use super::*;
+
+    #[test]
+    fn fee_split_by_destination() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(
+                Decimal::permille(1),
+                Decimal::permille(3),
+                Decimal::permille(2),
+            ),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_fee_split(
+            POOL_IDENTIFIER.to_string(),
+            Uint128::new(1_000_000u128),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.lp_fee, Uint128::new(3_000u128));
+                assert_eq!(response.protocol_fee, Uint128::new(1_000u128));
+                assert_eq!(response.burn_fee, Uint128::new(2_000u128));
+            },
+        );
+    }
+}
+
+mod capacity_headroom {
+    use super::*;
+
+    #[test]
+    fn constant_product_headroom_is_bounded_by_storage() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_capacity_headroom(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(
+                result.unwrap().max_additional_per_asset,
+                vec![
+                    coin(u128::MAX - 1_000_000u128, "uwhale"),
+                    coin(u128::MAX - 2_000_000u128, "uluna"),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn stableswap_headroom_is_bounded_by_invariant() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_capacity_headroom(POOL_IDENTIFIER.to_string(), |result| {
+            let response = result.unwrap();
+            for headroom in response.max_additional_per_asset {
+                assert!(!headroom.amount.is_zero());
+                assert!(headroom.amount < Uint128::MAX - Uint128::new(1_000_000u128));
+            }
+        });
+    }
+}
+
+mod simulation_with_reserves_out {
+    use super::*;
+
+    #[test]
+    fn reserves_after_match_executed_swap() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(
+                Decimal::permille(1),
+                Decimal::permille(3),
+                Decimal::permille(2),
+            ),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let reserves_after = RefCell::new(vec![]);
+
+        suite.query_simulation_with_reserves_out(
+            POOL_IDENTIFIER.to_string(),
+            coin(100_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.reserves_after[0], coin(1_100_000u128, "uwhale"));
+                assert_eq!(
+                    response.reserves_after[1].amount,
+                    Uint128::new(1_000_000u128)
+                        - response.simulation.return_amount
+                        - response.simulation.protocol_fee_amount
+                        - response.simulation.burn_fee_amount
+                );
+                *reserves_after.borrow_mut() = response.reserves_after;
+            },
+        );
+
+        suite.swap(
+            &creator,
+            "uluna".to_string(),
+            None,
+            Some(Decimal::percent(30)),
+            None,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(100_000u128, "uwhale".to_string())],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        suite.query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+            assert_eq!(
+                result.unwrap().pools[0].pool_info.assets,
+                *reserves_after.borrow()
+            );
+        });
+    }
+}
+
+mod marginal_output_curve {
+    use super::*;
+
+    #[test]
+    fn curve_is_increasing_and_concave() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_marginal_output_curve(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            4,
+            Uint128::new(1_000_000u128),
+            |result| {
+                let points = result.unwrap().points;
+                assert_eq!(
+                    points
+                        .iter()
+                        .map(|(offer, _)| offer.u128())
+                        .collect::<Vec<_>>(),
+                    vec![250_000u128, 500_000u128, 750_000u128, 1_000_000u128]
+                );
+                // x * y = k, so offering as much as the reserve returns half of the other one
+                assert_eq!(points[3].1, Uint128::new(500_000u128));
+
+                let mut previous = (Uint128::zero(), Uint128::zero());
+                let mut previous_increment = Uint128::MAX;
+                for (offer, return_amount) in points {
+                    assert!(offer > previous.0 && return_amount > previous.1);
+                    let increment = return_amount - previous.1;
+                    assert!(increment < previous_increment);
+                    previous_increment = increment;
+                    previous = (offer, return_amount);
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn steps_are_capped() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_marginal_output_curve(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            1_000,
+            Uint128::new(1_000_000u128),
+            |result| {
+                assert_eq!(result.unwrap().points.len(), 100);
+            },
+        );
+    }
+}
+
+mod validate_pool_spec {
+    use super::*;
+
+    #[test]
+    fn valid_spec_has_no_errors() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_validate_pool_spec(
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            |result| {
+                let response = result.unwrap();
+                assert!(response.valid);
+                assert!(response.errors.is_empty());
+            },
+        );
+    }
+
+    #[test]
+    fn reports_all_failures() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_validate_pool_spec(
+            vec!["uwhale".to_string(), "uwhale".to_string()],
+            vec![6u8],
+            PoolType::StableSwap { amp: 0 },
+            pool_fees(
+                Decimal::percent(10),
+                Decimal::percent(10),
+                Decimal::percent(10),
+            ),
+            |result| {
+                let response = result.unwrap();
+                assert!(!response.valid);
+                assert_eq!(response.errors.len(), 4);
+                assert_eq!(response.errors[0], ContractError::AssetMismatch.to_string());
+                assert_eq!(response.errors[1], ContractError::SameAsset.to_string());
+                assert_eq!(
+                    response.errors[3],
+                    ContractError::InvalidAmp(Uint256::zero()).to_string()
+                );
+            },
+        );
+    }
+}
+
+mod canonical_asset_order {
+    use super::*;
+
+    #[test]
+    fn returns_stored_denom_order() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uluna".to_string()),
+                coin(1_000_000u128, "uwhale".to_string()),
+            ],
+        );
+
+        suite
+            .query_canonical_asset_order(POOL_IDENTIFIER.to_string(), |result| {
+                assert_eq!(
+                    result.unwrap(),
+                    vec!["uwhale".to_string(), "uluna".to_string()]
+                );
+            })
+            .query_canonical_asset_order("o.unknown".to_string(), |result| {
+                assert!(result.is_err());
+            });
+    }
+}
+
+mod simulation_min_amp {
+    use super::*;
+
+    #[test]
+    fn simulation_uses_the_lesser_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        let current_amp_return = RefCell::new(Uint128::zero());
+        let mid_ramp_return = RefCell::new(Uint128::zero());
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *current_amp_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            // without a ramp the pool's amp is used
+            .query_simulation_min_amp(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().return_amount, *current_amp_return.borrow());
+                },
+            )
+            // ramping up, the amp swaps execute with is the lesser one
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                200,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .set_time(now.plus_seconds(50_000))
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *mid_ramp_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .query_simulation_min_amp(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let return_amount = result.unwrap().return_amount;
+                    assert_eq!(return_amount, *mid_ramp_return.borrow());
+                    assert!(return_amount > *current_amp_return.borrow());
+                },
+            )
+            // ramping down, the target amp is the lesser one and gives a worse output
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                20,
+                now.plus_seconds(150_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_simulation_min_amp(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    assert!(result.unwrap().return_amount < *current_amp_return.borrow());
+                },
+            );
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_simulation_min_amp(
+            POOL_IDENTIFIER.to_string(),
+            coin(1_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::NotStableSwapPool.to_string()));
+            },
+        );
+    }
+}
+
+mod offer_to_reach_price {
+    use super::*;
+
+    #[test]
+    fn constant_product_offer_to_reach_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_offer_to_reach_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(25),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::new(1_000_000u128));
+                },
+            )
+            // the price of uwhale is already below 4 uluna
+            .query_offer_to_reach_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(400),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::zero());
+                },
+            );
+    }
+
+    #[test]
+    fn stableswap_offer_to_reach_price() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let offer_amount = RefCell::new(Uint128::zero());
+
+        suite.query_offer_to_reach_price(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Decimal256::percent(90),
+            |result| {
+                let amount = result.unwrap();
+                assert!(!amount.is_zero());
+                *offer_amount.borrow_mut() = amount;
+            },
+        );
+
+        let offer_amount = *offer_amount.borrow();
+        suite
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(offer_amount.u128(), "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the target was reached, no more offer is needed
+            .query_offer_to_reach_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(90),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::zero());
+                },
+            );
+    }
+}
+
+mod pricing_path {
+    use super::*;
+
+    #[test]
+    fn finds_shortest_path_within_hop_limit() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        for (denoms, identifier) in [
+            (["uluna", "uusd"], "luna.uusd"),
+            (["uusd", "uom"], "uusd.uom"),
+            (["uwhale", "uusd"], "whale.uusd"),
+        ] {
+            suite.create_pool(
+                &creator,
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some(identifier.to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_pricing_path("uwhale".to_string(), "uluna".to_string(), 3, |result| {
+                assert_eq!(result.unwrap(), vec![POOL_IDENTIFIER.to_string()]);
+            })
+            .query_pricing_path("uluna".to_string(), "uom".to_string(), 3, |result| {
+                assert_eq!(
+                    result.unwrap(),
+                    vec!["o.luna.uusd".to_string(), "o.uusd.uom".to_string()]
+                );
+            })
+            // the direct uwhale-uusd pool gives a shorter path than going through uluna
+            .query_pricing_path("uwhale".to_string(), "uom".to_string(), 3, |result| {
+                assert_eq!(
+                    result.unwrap(),
+                    vec!["o.whale.uusd".to_string(), "o.uusd.uom".to_string()]
+                );
+            })
+            .query_pricing_path("uluna".to_string(), "uom".to_string(), 1, |result| {
+                assert!(result.unwrap().is_empty());
+            })
+            .query_pricing_path("uwhale".to_string(), "uatom".to_string(), 3, |result| {
+                assert!(result.unwrap().is_empty());
+            });
+    }
+
+    #[test]
+    fn path_search_is_capped_at_five_hops() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // uwhale -> uluna -> ua -> ub -> uc -> ud -> ue
+        for denoms in [
+            ["uluna", "ua"],
+            ["ua", "ub"],
+            ["ub", "uc"],
+            ["uc", "ud"],
+            ["ud", "ue"],
+        ] {
+            suite.create_pool(
+                &creator,
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some(denoms.join(".")),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_pricing_path("uwhale".to_string(), "ud".to_string(), u32::MAX, |result| {
+                assert_eq!(result.unwrap().len(), 5);
+            })
+            .query_pricing_path("uwhale".to_string(), "ue".to_string(), u32::MAX, |result| {
+                assert!(result.unwrap().is_empty());
+            });
+    }
+}
+
+mod breakeven_spread {
+    use super::*;
+
+    #[test]
+    fn breakeven_spread_recovers_the_round_trip() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(
+                Decimal::permille(2),
+                Decimal::permille(5),
+                Decimal::permille(3),
+            ),
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let spread = RefCell::new(Decimal256::zero());
+        let returned = RefCell::new(Uint128::zero());
+
+        suite
+            .query_breakeven_spread(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *spread.borrow_mut() = result.unwrap();
+                },
+            )
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                None,
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uwhale".to_string())],
+                |result| {
+                    let return_amount = result
+                        .unwrap()
+                        .events
+                        .into_iter()
+                        .flat_map(|event| event.attributes)
+                        .find(|attribute| attribute.key == "return_amount")
+                        .unwrap()
+                        .value;
+                    *returned.borrow_mut() = Uint128::new(return_amount.parse().unwrap());
+                },
+            );
+        let first_leg_return = *returned.borrow();
+        suite.swap(
+            &creator,
+            "uwhale".to_string(),
+            None,
+            None,
+            None,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(first_leg_return.u128(), "uluna".to_string())],
+            |result| {
+                let return_amount = result
+                    .unwrap()
+                    .events
+                    .into_iter()
+                    .flat_map(|event| event.attributes)
+                    .find(|attribute| attribute.key == "return_amount")
+                    .unwrap()
+                    .value;
+                *returned.borrow_mut() = Uint128::new(return_amount.parse().unwrap());
+            },
+        );
+
+        // the spread is what the executed round trip lost, the fees up to their rounding as the
+        // price impacts of the two legs cancel out
+        let round_trip_spread =
+            Decimal256::from_ratio(100_000u128, returned.borrow().u128()) - Decimal256::one();
+        assert_eq!(*spread.borrow(), round_trip_spread);
+        // 1 / 0.99^2 - 1
+        let fee_spread = Decimal256::one() / (Decimal256::percent(99) * Decimal256::percent(99))
+            - Decimal256::one();
+        assert!(spread.borrow().abs_diff(fee_spread) < Decimal256::bps(1));
+    }
+
+    #[test]
+    fn breakeven_spread_is_zero_without_fees() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_breakeven_spread(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap(), Decimal256::zero());
+                },
+            )
+            .query_breakeven_spread(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uatom".to_string(),
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+}
+
+mod swap_would_succeed {
+    use super::*;
+
+    #[test]
+    fn reports_the_failing_assertion() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Uint128::new(990u128),
+                Decimal256::percent(1),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.would_succeed);
+                    assert_eq!(response.reason, None);
+                },
+            )
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Uint128::new(1_000u128),
+                Decimal256::percent(1),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(!response.would_succeed);
+                    assert_eq!(
+                        response.reason,
+                        Some(
+                            ContractError::MinimumReceiveAssertion {
+                                minimum_receive: Uint128::new(1_000u128),
+                                swap_amount: Uint128::new(999u128),
+                            }
+                            .to_string()
+                        )
+                    );
+                },
+            )
+            // a 10% trade has a spread of roughly 9%
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Uint128::zero(),
+                Decimal256::percent(5),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(!response.would_succeed);
+                    assert!(response.reason.unwrap().contains("Spread limit exceeded"));
+                },
+            )
+            .query_swap_would_succeed(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uwhale".to_string()),
+                "uwhale".to_string(),
+                Uint128::zero(),
+                Decimal256::percent(1),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(!response.would_succeed);
+                    assert_eq!(response.reason, Some(ContractError::SameAsset.to_string()));
+                },
+            );
+    }
+}
+
+mod peg_deviation {
+    use super::*;
+
+    #[test]
+    fn deviation_is_signed_by_the_richer_asset() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 10 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .query_peg_deviation(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().deviation_bps, 0);
+                },
+            )
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(500_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let deviation = RefCell::new(0i64);
+        suite
+            // uwhale was sold into the pool, so it's cheaper than uluna
+            .query_peg_deviation(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                |result| {
+                    let deviation_bps = result.unwrap().deviation_bps;
+                    assert!(deviation_bps < 0);
+                    *deviation.borrow_mut() = deviation_bps;
+                },
+            )
+            .query_peg_deviation(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                |result| {
+                    let deviation_bps = result.unwrap().deviation_bps;
+                    assert!(deviation_bps > -*deviation.borrow());
+                },
+            );
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_peg_deviation(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::NotStableSwapPool.to_string()));
+            },
+        );
+    }
+}
+
+mod simulation_many_to_one {
+    use amm::pool_manager::SwapOperation;
+
+    use super::*;
+
+    fn hop(token_in: &str, token_out: &str) -> SwapOperation {
+        SwapOperation::MantraSwap {
+            token_in_denom: token_in.to_string(),
+            token_out_denom: token_out.to_string(),
+            pool_identifier: POOL_IDENTIFIER.to_string(),
+        }
+    }
+
+    #[test]
+    fn routes_sharing_a_pool_are_simulated_in_sequence() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_simulation_many_to_one(
+            vec![
+                coin(100_000u128, "uwhale".to_string()),
+                coin(100_000u128, "uwhale".to_string()),
+            ],
+            "uluna".to_string(),
+            vec![vec![hop("uwhale", "uluna")], vec![hop("uwhale", "uluna")]],
+            |result| {
+                let response = result.unwrap();
+                // 1_000_000 - 1e12 / 1_100_000 and 1e12 / 1_100_000 - 1e12 / 1_200_000
+                assert_eq!(
+                    response.per_asset,
+                    vec![Uint128::new(90_909u128), Uint128::new(75_757u128)]
+                );
+                assert_eq!(response.total_return, Uint128::new(166_666u128));
+            },
+        );
+    }
+
+    #[test]
+    fn routes_must_match_the_offer_and_ask_assets() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_simulation_many_to_one(
+                vec![coin(100_000u128, "uwhale".to_string())],
+                "uluna".to_string(),
+                vec![],
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::RouteCountMismatch {
+                            offer_assets: 1,
+                            routes: 0,
+                        }
+                        .to_string()
+                    ));
+                },
+            )
+            .query_simulation_many_to_one(
+                vec![coin(100_000u128, "uluna".to_string())],
+                "uluna".to_string(),
+                vec![vec![hop("uwhale", "uluna")]],
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::NonConsecutiveSwapOperations {
+                            previous_output: "uluna".to_string(),
+                            next_input: "uwhale".to_string(),
+                        }
+                        .to_string()
+                    ));
+                },
+            )
+            .query_simulation_many_to_one(
+                vec![coin(100_000u128, "uluna".to_string())],
+                "uluna".to_string(),
+                vec![vec![hop("uluna", "uwhale")]],
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::NonConsecutiveSwapOperations {
+                            previous_output: "uwhale".to_string(),
+                            next_input: "uluna".to_string(),
+                        }
+                        .to_string()
+                    ));
+                },
+            );
+    }
+}
+
+mod swap_fee_in_offer {
+    use super::*;
+
+    #[test]
+    fn swap_fee_is_converted_at_spot_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::zero(), Decimal::percent(1), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // 1 uwhale is worth 2 uluna
+        suite.query_swap_fee_in_offer(
+            POOL_IDENTIFIER.to_string(),
+            coin(10_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                let response = result.unwrap();
+                // 2_000_000 - 2e12 / 1_010_000 = 19_801, 1% of which is 198
+                assert_eq!(response.swap_fee_amount, Uint128::new(198u128));
+                assert_eq!(response.swap_fee_in_offer, Uint128::new(99u128));
+            },
+        );
+    }
+}
+
+mod post_fee_withdrawal_reserves {
+    use super::*;
+
+    #[test]
+    fn protocol_fees_are_not_held_by_the_pool() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::percent(1), Decimal::zero(), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite.swap(
+            &creator,
+            "uluna".to_string(),
+            None,
+            Some(Decimal::percent(30)),
+            None,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(100_000u128, "uwhale".to_string())],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let reserves = RefCell::new(vec![]);
+        suite.query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+            *reserves.borrow_mut() = result.unwrap().pools[0].pool_info.assets.clone();
+        });
+
+        suite.query_post_fee_withdrawal_reserves(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap(), *reserves.borrow());
+        });
+    }
+}
+
+mod safe_trade_size {
+    use super::*;
+
+    #[test]
+    fn safe_trade_size_keeps_price_impact_within_limit() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type.clone(),
+                zero_fees(),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+
+            let safe_size = RefCell::new(Uint128::zero());
+            let offer_to_limit = RefCell::new(Uint128::zero());
+            suite
+                .query_safe_trade_size(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    0,
+                    |result| {
+                        assert_eq!(result.unwrap(), Uint128::zero());
+                    },
+                )
+                .query_safe_trade_size(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    10_000,
+                    |result| {
+                        assert!(result
+                            .unwrap_err()
+                            .to_string()
+                            .contains(&ContractError::InvalidMaxPriceImpact(10_000).to_string()));
+                    },
+                )
+                .query_safe_trade_size(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    100,
+                    |result| {
+                        *safe_size.borrow_mut() = result.unwrap();
+                    },
+                )
+                // the pool is balanced, so a 1% impact brings the price down to 0.99
+                .query_offer_to_reach_price(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Decimal256::percent(99),
+                    |result| {
+                        *offer_to_limit.borrow_mut() = result.unwrap();
+                    },
+                );
+
+            let safe_size = *safe_size.borrow();
+            let offer_to_limit = *offer_to_limit.borrow();
+            assert!(!safe_size.is_zero());
+            assert!(safe_size <= offer_to_limit && offer_to_limit - safe_size <= Uint128::one());
+
+            if pool_type == PoolType::ConstantProduct {
+                // 1_000_000 / sqrt(0.99) - 1_000_000
+                assert_eq!(safe_size, Uint128::new(5_037u128));
+            }
+        }
+    }
+}
+
+mod adjacent_pools {
+    use super::*;
+
+    #[test]
+    fn returns_pools_sharing_a_denom() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        for (denoms, identifier) in [
+            (["uluna", "uusd"], "luna.uusd"),
+            (["uusd", "uom"], "uusd.uom"),
+            (["uwhale", "uom"], "whale.uom"),
+        ] {
+            suite.create_pool(
+                &creator,
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some(identifier.to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        let identifiers = |response: PoolsResponse| {
+            response
+                .pools
+                .into_iter()
+                .map(|pool| pool.pool_info.pool_identifier)
+                .collect::<Vec<_>>()
+        };
+
+        suite
+            .query_adjacent_pools(POOL_IDENTIFIER.to_string(), None, |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec!["o.luna.uusd".to_string(), "o.whale.uom".to_string()]
+                );
+            })
+            .query_adjacent_pools(POOL_IDENTIFIER.to_string(), Some(1), |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec!["o.luna.uusd".to_string()]
+                );
+            })
+            .query_adjacent_pools("o.uusd.uom".to_string(), None, |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec!["o.luna.uusd".to_string(), "o.whale.uom".to_string()]
+                );
+            });
+    }
+}
+
+mod price_matrix {
+    use super::*;
+
+    #[test]
+    fn price_matrix_covers_every_ordered_pair() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec![
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    "uusd".to_string(),
+                ],
+                vec![6u8, 6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.uusd".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(2_000_000u128, "uluna".to_string()),
+                    coin(4_000_000u128, "uusd".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        suite.query_price_matrix(
+            "o.whale.uluna.uusd".to_string(),
+            Uint128::new(1_000u128),
+            |result| {
+                let entries = result.unwrap().entries;
+                let pairs = entries
+                    .iter()
+                    .map(|(offer, ask, _)| (offer.as_str(), ask.as_str()))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    pairs,
+                    vec![
+                        ("uwhale", "uluna"),
+                        ("uwhale", "uusd"),
+                        ("uluna", "uwhale"),
+                        ("uluna", "uusd"),
+                        ("uusd", "uwhale"),
+                        ("uusd", "uluna"),
+                    ]
+                );
+                // 1_000 uwhale is worth roughly 2_000 uluna and 4_000 uusd
+                assert_eq!(entries[0].2, Uint128::new(1_998u128));
+                assert_eq!(entries[1].2, Uint128::new(3_996u128));
+                assert_eq!(entries[2].2, Uint128::new(499u128));
+            },
+        );
+    }
+}
+
+mod constant_product_equivalent_amp {
+    use cosmwasm_std::Uint256;
+
+    use super::*;
+
+    #[test]
+    fn balanced_pools_return_the_minimum_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_constant_product_equivalent_amp(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap(), Uint256::one());
+        });
+    }
+
+    #[test]
+    fn imbalanced_pools_tolerate_a_higher_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_constant_product_equivalent_amp(POOL_IDENTIFIER.to_string(), |result| {
+            assert!(result.unwrap() > Uint256::one());
+        });
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_constant_product_equivalent_amp(POOL_IDENTIFIER.to_string(), |result| {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::NotStableSwapPool.to_string()));
+        });
+    }
+}
+
+mod sandwich_risk {
+    use super::*;
+
+    #[test]
+    fn large_swaps_are_exposed_to_sandwiching() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_sandwich_risk(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    // the attacker gets 90_909 uluna, the victim's swap leaves 1_200_000 uwhale
+                    // and 833_334 uluna, and swapping the 90_909 uluna back returns 118_032 uwhale
+                    assert_eq!(result.unwrap().max_extractable, Uint128::new(18_032u128));
+                },
+            )
+            .query_sandwich_risk(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Uint128::zero(),
+                |result| {
+                    assert_eq!(result.unwrap().max_extractable, Uint128::zero());
+                },
+            );
+    }
+
+    #[test]
+    fn fees_make_small_sandwiches_unprofitable() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::zero(), Decimal::percent(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_sandwich_risk(
+            POOL_IDENTIFIER.to_string(),
+            coin(1_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            Uint128::new(10_000u128),
+            |result| {
+                assert_eq!(result.unwrap().max_extractable, Uint128::zero());
+            },
+        );
+    }
+}
+
+mod ramping_pools {
+    use super::*;
+
+    #[test]
+    fn returns_pools_within_their_ramp_window() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        suite.create_pool(
+            &creator,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            zero_fees(),
+            PoolType::StableSwap { amp: 100 },
+            Some("whale.uluna.2".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let identifiers = |response: PoolsResponse| {
+            response
+                .pools
+                .into_iter()
+                .map(|pool| pool.pool_info.pool_identifier)
+                .collect::<Vec<_>>()
+        };
+
+        suite
+            .query_ramping_pools(None, |result| {
+                assert!(result.unwrap().pools.is_empty());
+            })
+            .ramp_amp(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                200,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_ramping_pools(None, |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec!["o.whale.uluna.2".to_string()]
+                );
+            })
+            .query_ramping_pools(Some(0), |result| {
+                assert!(result.unwrap().pools.is_empty());
+            })
+            // the pools come with the amp swaps execute with
+            .set_time(now.plus_seconds(50_000))
+            .query_ramping_pools(None, |result| {
+                assert_eq!(
+                    result.unwrap().pools[0].pool_info.pool_type,
+                    PoolType::StableSwap { amp: 150 }
+                );
+            })
+            .set_time(now.plus_seconds(100_000))
+            .query_ramping_pools(None, |result| {
+                assert!(result.unwrap().pools.is_empty());
+            });
+    }
+}
+
+mod liquidity_concentration {
+    use super::*;
+
+    #[test]
+    fn balanced_pool_has_zero_concentration() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_liquidity_concentration(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap().index, Decimal256::zero());
+        });
+    }
+
+    #[test]
+    fn imbalanced_pool_concentration() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(3_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // shares of 0.25 and 0.75 give a Herfindahl index of 0.625, rescaled to 0.25
+        suite.query_liquidity_concentration(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap().index, Decimal256::percent(25));
+        });
+    }
+}
+
+mod simulation_with_pending {
+    use super::*;
+
+    #[test]
+    fn simulation_uses_the_pending_reserves() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // the pool would hold 1_500_000 uwhale and 2_000_000 uluna
+            .query_simulation_with_pending(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+                vec![coin(500_000u128, "uwhale".to_string())],
+                |result| {
+                    // 2_000_000 - 3e12 / 2_000_000
+                    assert_eq!(result.unwrap().return_amount, Uint128::new(500_000u128));
+                },
+            )
+            .query_simulation_with_pending(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                vec![],
+                vec![coin(1_000_001u128, "uluna".to_string())],
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::PendingWithdrawalExceedsReserves {
+                            denom: "uluna".to_string(),
+                        }
+                        .to_string()
+                    ));
+                },
+            )
+            // the live pool is untouched
+            .query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+                assert_eq!(
+                    result.unwrap().pools[0].pool_info.assets,
+                    vec![
+                        coin(1_000_000u128, "uwhale".to_string()),
+                        coin(1_000_000u128, "uluna".to_string()),
+                    ]
+                );
+            });
+    }
+}
+
+mod max_possible_output {
+    use super::*;
+
+    #[test]
+    fn constant_product_output_approaches_the_ask_reserve() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::zero(), Decimal::percent(1), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_max_possible_output(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                assert_eq!(result.unwrap(), Uint128::new(1_980_000u128));
+            },
+        );
+    }
+
+    #[test]
+    fn stableswap_output_is_bounded_by_the_ask_reserve() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_max_possible_output(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                let max_output = result.unwrap();
+                assert!(max_output <= Uint128::new(1_000_000u128));
+                assert!(max_output > Uint128::new(999_000u128));
+            },
+        );
+    }
+}
+
+mod direct_vs_route {
+    use amm::pool_manager::{DirectOrRoute, SwapOperation};
+
+    use super::*;
+
+    #[test]
+    fn deeper_route_beats_shallow_direct_pool() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(100_000u128, "uwhale".to_string()),
+                coin(100_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        for (denoms, identifier) in [
+            (["uwhale", "uusd"], "whale.uusd"),
+            (["uusd", "uluna"], "uusd.luna"),
+        ] {
+            suite
+                .create_pool(
+                    &creator,
+                    denoms.iter().map(|denom| denom.to_string()).collect(),
+                    vec![6u8, 6u8],
+                    zero_fees(),
+                    PoolType::ConstantProduct,
+                    Some(identifier.to_string()),
+                    vec![coin(1000, "uusd"), coin(8888, "uom")],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .provide_liquidity(
+                    &creator,
+                    format!("o.{identifier}"),
+                    None,
+                    None,
+                    None,
+                    None,
+                    denoms
+                        .iter()
+                        .map(|denom| coin(10_000_000u128, denom.to_string()))
+                        .collect(),
+                    |result| {
+                        result.unwrap();
+                    },
+                );
+        }
+
+        let hop =
+            |token_in: &str, token_out: &str, pool_identifier: &str| SwapOperation::MantraSwap {
+                token_in_denom: token_in.to_string(),
+                token_out_denom: token_out.to_string(),
+                pool_identifier: pool_identifier.to_string(),
+            };
+
+        suite
+            .query_direct_vs_route(
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                POOL_IDENTIFIER.to_string(),
+                vec![
+                    hop("uwhale", "uusd", "o.whale.uusd"),
+                    hop("uusd", "uluna", "o.uusd.luna"),
+                ],
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.direct_output, Uint128::new(9_090u128));
+                    assert!(response.route_output > response.direct_output);
+                    assert_eq!(response.cheaper, DirectOrRoute::Route);
+                },
+            )
+            // tiny swaps get the same output either way, and the direct pool wins ties
+            .query_direct_vs_route(
+                coin(1u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                POOL_IDENTIFIER.to_string(),
+                vec![
+                    hop("uwhale", "uusd", "o.whale.uusd"),
+                    hop("uusd", "uluna", "o.uusd.luna"),
+                ],
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.direct_output, response.route_output);
+                    assert_eq!(response.cheaper, DirectOrRoute::Direct);
+                },
+            )
+            .query_direct_vs_route(
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                POOL_IDENTIFIER.to_string(),
+                vec![hop("uwhale", "uusd", "o.whale.uusd")],
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+}
+
+mod simulation_vs_reference {
+    use super::*;
+
+    #[test]
+    fn premium_is_measured_against_the_reference() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // 10_000 uwhale return 9_900 uluna, 1% less than at a 1:1 price
+            .query_simulation_vs_reference(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal256::one(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.return_amount, Uint128::new(9_900u128));
+                    assert_eq!(response.premium_or_discount_bps, 100);
+                },
+            )
+            // against a reference of 0.9 uluna per uwhale the pool is 10% cheaper
+            .query_simulation_vs_reference(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal256::percent(90),
+                |result| {
+                    assert_eq!(result.unwrap().premium_or_discount_bps, -1_000);
+                },
+            );
+    }
+}
+
+mod simulate_until_price {
+    use super::*;
+
+    #[test]
+    fn stops_once_the_price_drops_below_the_stop_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // a stop price that's never hit sells everything, slightly below the 90_909 of a
+            // single swap because of the rounding on every increment
+            .query_simulate_until_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                Decimal256::percent(50),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.executed_offer, Uint128::new(100_000u128));
+                    assert_eq!(response.return_amount, Uint128::new(90_865u128));
+                },
+            )
+            // the spot price drops below 0.9 once ~54_000 uwhale got sold
+            .query_simulate_until_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                Decimal256::percent(90),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.executed_offer > Uint128::new(50_000u128));
+                    assert!(response.executed_offer < Uint128::new(60_000u128));
+                    assert!(response.return_amount < response.executed_offer);
+                },
+            )
+            // a stop price above the current price doesn't sell anything
+            .query_simulate_until_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                Decimal256::percent(101),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.executed_offer, Uint128::zero());
+                    assert_eq!(response.return_amount, Uint128::zero());
+                },
+            );
+    }
+}
+
+mod imbalance_fee_schedule {
+    use super::*;
+
+    #[test]
+    fn fee_rate_grows_with_the_imbalance() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_imbalance_fee_schedule(POOL_IDENTIFIER.to_string(), 4, |result| {
+            let points = result.unwrap().points;
+            assert_eq!(points.len(), 4);
+
+            // deposits of 1/8, 1/4, 3/8 and 1/2 of the total reserves
+            assert_eq!(points[0].0, Decimal256::permille(125));
+            assert_eq!(points[3].0, Decimal256::percent(50));
+
+            for window in points.windows(2) {
+                assert!(window[0].1 < window[1].1);
+            }
+            assert!(points[3].1 < Decimal256::percent(10));
+        });
+    }
+
+    #[test]
+    fn constant_product_pools_are_rejected() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_imbalance_fee_schedule(POOL_IDENTIFIER.to_string(), 4, |result| {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::NotStableSwapPool.to_string()));
+        });
+    }
+}
+
+mod lp_to_withdraw {
+    use super::*;
+
+    #[test]
+    fn lp_to_withdraw_is_the_inverse_of_the_withdrawal() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // withdrawing 10% of the 1_000_000 LP tokens returns 10% of the reserves
+        suite
+            .query_lp_to_withdraw(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::new(100_000u128));
+                },
+            )
+            .query_lp_to_withdraw(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000_000u128, "uwhale".to_string()),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err
+                        .contains("The pool can't return 1000000 of uwhale through a withdrawal"));
+                },
+            )
+            .query_lp_to_withdraw(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uusd".to_string()),
+                |result| {
+                    let err = result.unwrap_err().to_string();
+                    assert!(err.contains("The asset doesn't match the assets stored in contract"));
+                },
+            );
+    }
+
+    #[test]
+    fn lp_to_withdraw_is_the_inverse_of_the_zap_out_on_stableswap_pools() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let lp_amount = RefCell::new(Uint128::zero());
+
+        suite.query_lp_to_withdraw(
+            POOL_IDENTIFIER.to_string(),
+            coin(190_000u128, "uwhale".to_string()),
+            |result| {
+                *lp_amount.borrow_mut() = result.unwrap();
+            },
+        );
+
+        let lp_amount = *lp_amount.borrow();
+        suite
+            .query_simulate_zap_out(
+                POOL_IDENTIFIER.to_string(),
+                lp_amount,
+                "uwhale".to_string(),
+                |result| {
+                    assert!(result.unwrap().return_amount >= Uint128::new(190_000u128));
+                },
+            )
+            .query_simulate_zap_out(
+                POOL_IDENTIFIER.to_string(),
+                lp_amount - Uint128::one(),
+                "uwhale".to_string(),
+                |result| {
+                    assert!(result.unwrap().return_amount < Uint128::new(190_000u128));
+                },
+            );
+    }
+}
+
+mod rebalance_to_equal {
+    use super::*;
+
+    #[test]
+    fn rebalance_accounts_for_the_price_impact() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // swapping 95_446 uwhale returns 87_129 uluna, while the remaining 104_554 uwhale are worth
+        // 87_128 uluna at the spot price after the swap. Half of it would leave the holder short.
+        suite
+            .query_rebalance_to_equal(
+                POOL_IDENTIFIER.to_string(),
+                coin(200_000u128, "uwhale".to_string()),
+                coin(0u128, "uluna".to_string()),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uwhale".to_string());
+                    assert_eq!(response.offer_amount, Uint128::new(95_446u128));
+                },
+            )
+            // the side worth more is the one offered
+            .query_rebalance_to_equal(
+                POOL_IDENTIFIER.to_string(),
+                coin(0u128, "uwhale".to_string()),
+                coin(200_000u128, "uluna".to_string()),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uluna".to_string());
+                    assert_eq!(response.offer_amount, Uint128::new(95_446u128));
+                },
+            )
+            // balances already worth the same don't need a swap
+            .query_rebalance_to_equal(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_000u128, "uwhale".to_string()),
+                coin(1_000u128, "uluna".to_string()),
+                |result| {
+                    assert_eq!(result.unwrap().offer_amount, Uint128::zero());
+                },
+            );
+    }
+}
+
+mod revenue_over_distribution {
+    use super::*;
+
+    #[test]
+    fn fees_are_accumulated_over_the_trades() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::percent(1), Decimal::percent(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the trades return 9_900 and 19_607 uluna before fees, paying 198 + 392 in swap fees
+        // and 99 + 196 in protocol fees
+        suite
+            .query_revenue_over_distribution(
+                POOL_IDENTIFIER.to_string(),
+                vec![Uint128::new(10_000u128), Uint128::new(20_000u128)],
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.total_swap_fee, Uint128::new(590u128));
+                    assert_eq!(response.total_protocol_fee, Uint128::new(295u128));
+                },
+            )
+            // trades past the limit are ignored
+            .query_revenue_over_distribution(
+                POOL_IDENTIFIER.to_string(),
+                vec![Uint128::new(10_000u128); 150],
+                |result| {
+                    assert_eq!(result.unwrap().total_swap_fee, Uint128::new(19_800u128));
+                },
+            );
+    }
+}
+
+mod band_width_for_fees {
+    use super::*;
+
+    #[test]
+    fn band_widens_with_the_fee() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_band_width_for_fees(
+                POOL_IDENTIFIER.to_string(),
+                Decimal::percent(20),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.lower, Decimal256::from_ratio(8u128, 5u128));
+                    assert_eq!(response.upper, Decimal256::from_ratio(5u128, 2u128));
+                },
+            )
+            .query_band_width_for_fees(POOL_IDENTIFIER.to_string(), Decimal::zero(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.lower, Decimal256::from_ratio(2u128, 1u128));
+                assert_eq!(response.upper, Decimal256::from_ratio(2u128, 1u128));
+            })
+            .query_band_width_for_fees(
+                POOL_IDENTIFIER.to_string(),
+                Decimal::percent(100),
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains("Invalid fee"));
+                },
+            );
+    }
+}
+
+mod mid_price_rational {
+    use super::*;
+
+    #[test]
+    fn price_is_reduced_to_its_lowest_terms() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_500_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_mid_price_rational(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.numerator, Uint256::from(2u128));
+                    assert_eq!(response.denominator, Uint256::from(3u128));
+                },
+            )
+            .query_mid_price_rational(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.numerator, Uint256::from(3u128));
+                    assert_eq!(response.denominator, Uint256::from(2u128));
+                },
+            );
+    }
+
+    #[test]
+    fn stableswap_price_matches_the_spot_price() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_mid_price_rational(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.numerator, response.denominator);
+                assert_eq!(response.numerator, Uint256::one());
+            },
+        );
+    }
+}
+
+mod swap_apr_contribution {
+    use super::*;
+
+    #[test]
+    fn fee_is_measured_against_the_tvl() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::percent(1), Decimal::percent(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the swap returns 9_900 uluna before fees, leaving 198 uluna to the LPs out of a TVL
+        // worth 2_000_000 uluna, i.e. 0.99 bps
+        suite.query_swap_apr_contribution(
+            POOL_IDENTIFIER.to_string(),
+            coin(10_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.fee_generated, Uint128::new(198u128));
+                assert_eq!(response.fee_as_bps_of_tvl, Decimal256::permille(990));
+            },
+        );
+    }
+}
+
+mod reserves_for_invariant {
+    use super::*;
+
+    use cosmwasm_std::Uint512;
+
+    use crate::helpers::compute_d;
+
+    #[test]
+    fn balanced_reserves_produce_the_target_invariant() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_reserves_for_invariant(
+            POOL_IDENTIFIER.to_string(),
+            Uint256::from(5_000_000u128),
+            |result| {
+                let reserves = result.unwrap();
+                assert_eq!(
+                    reserves,
+                    vec![
+                        coin(2_500_000u128, "uwhale".to_string()),
+                        coin(2_500_000u128, "uluna".to_string()),
+                    ]
+                );
+
+                let d = compute_d(&100, &reserves).unwrap();
+                assert_eq!(d, Uint512::from(5_000_000u128));
+            },
+        );
+    }
+
+    #[test]
+    fn constant_product_pools_are_rejected() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_reserves_for_invariant(
+            POOL_IDENTIFIER.to_string(),
+            Uint256::from(5_000_000u128),
+            |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::NotStableSwapPool.to_string()));
+            },
+        );
+    }
+}
+
+mod reachable {
+    use super::*;
+
+    #[test]
+    fn reachability_is_capped_at_five_hops() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // uwhale -> uluna -> ua -> ub -> uc -> ud -> ue
+        for denoms in [
+            ["uluna", "ua"],
+            ["ua", "ub"],
+            ["ub", "uc"],
+            ["uc", "ud"],
+            ["ud", "ue"],
+        ] {
+            suite.create_pool(
+                &creator,
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some(denoms.join(".")),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_reachable("uwhale".to_string(), "uwhale".to_string(), 0, |result| {
+                assert!(result.unwrap());
+            })
+            .query_reachable("uwhale".to_string(), "ua".to_string(), 2, |result| {
+                assert!(result.unwrap());
+            })
+            .query_reachable("uwhale".to_string(), "ua".to_string(), 1, |result| {
+                assert!(!result.unwrap());
+            })
+            .query_reachable("uwhale".to_string(), "ud".to_string(), 10, |result| {
+                assert!(result.unwrap());
+            })
+            .query_reachable("uwhale".to_string(), "ue".to_string(), 10, |result| {
+                assert!(!result.unwrap());
+            })
+            .query_reachable("uwhale".to_string(), "uatom".to_string(), 5, |result| {
+                assert!(!result.unwrap());
+            });
+    }
+}
+
+mod price_sensitivity {
+    use super::*;
+
+    #[test]
+    fn constant_product_sensitivity_is_analytic() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(2_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // 4 / 2^2 = 1 per whole unit of uwhale
+        suite.query_price_sensitivity(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                assert_eq!(result.unwrap().price_per_unit, Decimal256::one());
+            },
+        );
+    }
+
+    #[test]
+    fn stableswap_is_less_sensitive_than_constant_product() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(2_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // a constant product pool with the same reserves would have a sensitivity of 0.5
+        suite.query_price_sensitivity(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                let price_per_unit = result.unwrap().price_per_unit;
+                assert!(!price_per_unit.is_zero());
+                assert!(price_per_unit < Decimal256::percent(5));
+            },
+        );
+    }
+}
+
+mod fee_tier {
+    use amm::pool_manager::{FeeTier, FeeTierThresholds};
+
+    use super::*;
+
+    #[test]
+    fn fee_tier_follows_the_config_thresholds() {
+        // 0.3% total fee
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .query_fee_tier(POOL_IDENTIFIER.to_string(), |result| {
+                assert_eq!(result.unwrap().tier, FeeTier::Standard);
+            })
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                Some(FeeTierThresholds {
+                    stable_max: Decimal::permille(3),
+                    standard_max: Decimal::percent(1),
+                }),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_fee_tier(POOL_IDENTIFIER.to_string(), |result| {
+                assert_eq!(result.unwrap().tier, FeeTier::Stable);
+            })
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                Some(FeeTierThresholds {
+                    stable_max: Decimal::zero(),
+                    standard_max: Decimal::permille(2),
+                }),
+                None,
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_fee_tier(POOL_IDENTIFIER.to_string(), |result| {
+                assert_eq!(result.unwrap().tier, FeeTier::Exotic);
+            })
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                Some(FeeTierThresholds {
+                    stable_max: Decimal::percent(1),
+                    standard_max: Decimal::permille(2),
+                }),
+                None,
+                |result| {
+                    let err = result.unwrap_err().downcast::<ContractError>().unwrap();
+                    match err {
+                        ContractError::InvalidFeeTierThresholds => {}
+                        _ => panic!("Wrong error type, should return InvalidFeeTierThresholds"),
+                    }
+                },
+            );
+    }
+}
+
+mod balanced_slippage {
+    use super::*;
+
+    #[test]
+    fn spreads_of_both_directions_are_reported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // 10_000 uwhale return 39_603 uluna instead of 40_000, while the 40_000 uluna they're worth
+        // return 9_900 uwhale instead of 10_000
+        suite.query_balanced_slippage(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Uint128::new(10_000u128),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.forward_spread, Uint128::new(397u128));
+                assert_eq!(response.reverse_spread, Uint128::new(100u128));
+            },
+        );
+    }
+}
+
+mod min_amp_for_positive_spread {
+    use super::*;
+
+    #[test]
+    fn rounding_keeps_the_spread_non_negative_up_to_the_searched_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_min_amp_for_positive_spread(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap(), Uint256::from(1_000_000u128));
+        });
+    }
+
+    #[test]
+    fn constant_product_pools_are_rejected() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_min_amp_for_positive_spread(POOL_IDENTIFIER.to_string(), |result| {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::NotStableSwapPool.to_string()));
+        });
+    }
+}
+
+mod simulation_with_rebate {
+    use super::*;
+
+    #[test]
+    fn rebate_is_added_back_to_the_return_amount() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::percent(1), Decimal::percent(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // 9_900 uluna before fees, 198 of swap fee and 99 of protocol fee, the swap fee is 200 bps
+        suite
+            .query_simulation_with_rebate(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                100,
+                |result| {
+                    let simulation = result.unwrap();
+                    assert_eq!(simulation.return_amount, Uint128::new(9_702u128));
+                    assert_eq!(simulation.swap_fee_amount, Uint128::new(99u128));
+                    assert_eq!(simulation.protocol_fee_amount, Uint128::new(99u128));
+                },
+            )
+            .query_simulation_with_rebate(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                200,
+                |result| {
+                    let simulation = result.unwrap();
+                    assert_eq!(simulation.return_amount, Uint128::new(9_801u128));
+                    assert_eq!(simulation.swap_fee_amount, Uint128::zero());
+                },
+            )
+            .query_simulation_with_rebate(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                201,
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::InvalidRebate(201, Uint128::new(200u128)).to_string()
+                    ));
+                },
+            );
+    }
+}
+
+mod price_curve_coefficients {
+    use super::*;
+
+    #[test]
+    fn approximation_matches_nearby_swaps() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000_000u128, "uwhale".to_string()),
+                coin(1_000_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let coefficients = RefCell::new(None);
+
+        suite.query_price_curve_coefficients(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Uint128::new(10_000_000u128),
+            |result| {
+                *coefficients.borrow_mut() = Some(result.unwrap());
+            },
+        );
+
+        let coefficients = coefficients.into_inner().unwrap();
+        // y = 1e9 * x / (1e9 + x) gives y(1e7) = 9_900_990, y' = 0.9803 and y'' / 2 = -9.7e-10
+        assert_eq!(
+            coefficients.c0,
+            Decimal256::from_ratio(9_900_990u128, 1u128)
+        );
+        assert!(coefficients.c1 > Decimal256::permille(979));
+        assert!(coefficients.c1 < Decimal256::permille(981));
+
+        // approximate a swap of 10_500_000 uwhale
+        let d = Decimal256::from_ratio(500_000u128, 1u128);
+        let approximation = coefficients.c0 + coefficients.c1 * d - coefficients.c2 * d * d;
+
+        suite.query_simulation(
+            POOL_IDENTIFIER.to_string(),
+            coin(10_500_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                let exact = Decimal256::from_ratio(result.unwrap().return_amount, 1u128);
+                // within 0.001% of the exact return
+                assert!(exact.abs_diff(approximation) < Decimal256::from_ratio(100u128, 1u128));
+            },
+        );
+    }
+}
+
+mod pools_above_fee {
+    use super::*;
+
+    #[test]
+    fn returns_pools_with_a_total_fee_above_the_threshold() {
+        // 0.3% total fee
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // 1% total fee, including an extra fee
+        let mut high_fees = pool_fees(Decimal::permille(3), Decimal::permille(5), Decimal::zero());
+        high_fees.extra_fees = vec![Fee {
+            share: Decimal::permille(2),
+        }];
+
+        suite.create_pool(
+            &creator,
+            vec!["uwhale".to_string(), "uluna".to_string()],
+            vec![6u8, 6u8],
+            high_fees,
+            PoolType::ConstantProduct,
+            Some("whale.uluna.high".to_string()),
+            vec![coin(1000, "uusd"), coin(8888, "uom")],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        let identifiers = |response: PoolsResponse| {
+            response
+                .pools
+                .into_iter()
+                .map(|pool| pool.pool_info.pool_identifier)
+                .collect::<Vec<_>>()
+        };
+
+        suite
+            .query_pools_above_fee(Decimal::permille(2), None, |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec![
+                        POOL_IDENTIFIER.to_string(),
+                        "o.whale.uluna.high".to_string()
+                    ]
+                );
+            })
+            // the threshold is exclusive
+            .query_pools_above_fee(Decimal::permille(3), None, |result| {
+                assert_eq!(
+                    identifiers(result.unwrap()),
+                    vec!["o.whale.uluna.high".to_string()]
+                );
+            })
+            .query_pools_above_fee(Decimal::permille(2), Some(1), |result| {
+                assert_eq!(result.unwrap().pools.len(), 1);
+            })
+            .query_pools_above_fee(Decimal::percent(1), None, |result| {
+                assert!(result.unwrap().pools.is_empty());
+            });
+    }
+}
+
+mod simulation_amp_range {
+    use super::*;
+
+    #[test]
+    fn range_spans_current_and_target_amp() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let now = suite.get_time();
+
+        let current_amp_return = RefCell::new(Uint128::zero());
+        let mid_ramp_return = RefCell::new(Uint128::zero());
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *current_amp_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            // without a ramp the range collapses to the pool's amp
+            .query_simulation_amp_range(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.min_return, *current_amp_return.borrow());
+                    assert_eq!(response.max_return, *current_amp_return.borrow());
+                },
+            )
+            // ramping down, the current amp gives the best output
+            .ramp_amp(
+                &creator,
+                POOL_IDENTIFIER.to_string(),
+                10,
+                now.plus_seconds(100_000).seconds(),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_simulation_amp_range(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.max_return, *current_amp_return.borrow());
+                    assert!(response.min_return < response.max_return);
+                },
+            )
+            // halfway through the ramp, the range starts at the amp swaps execute with
+            .set_time(now.plus_seconds(50_000))
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *mid_ramp_return.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            .query_simulation_amp_range(
+                POOL_IDENTIFIER.to_string(),
+                coin(500_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.max_return, *mid_ramp_return.borrow());
+                    assert!(response.max_return < *current_amp_return.borrow());
+                    assert!(response.min_return < response.max_return);
+                },
+            );
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_simulation_amp_range(
+            POOL_IDENTIFIER.to_string(),
+            coin(1_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::NotStableSwapPool.to_string()));
+            },
+        );
+    }
+}
+
+mod reserve_shares_of_d {
+    use super::*;
+
+    #[test]
+    fn balanced_pool_has_equal_shares() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_reserve_shares_of_d(POOL_IDENTIFIER.to_string(), |result| {
+            let shares = result.unwrap();
+            assert_eq!(shares.len(), 2);
+            assert_eq!(shares[0].0, "uwhale");
+            assert_eq!(shares[1].0, "uluna");
+            for (_, share) in shares {
+                assert_eq!(share, Decimal256::percent(50));
+            }
+        });
+    }
+
+    #[test]
+    fn imbalanced_pool_deviates_from_equal_shares() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_500_000u128, "uwhale".to_string()),
+                coin(500_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_reserve_shares_of_d(POOL_IDENTIFIER.to_string(), |result| {
+            let shares = result.unwrap();
+            assert!(shares[0].1 > Decimal256::percent(50));
+            assert!(shares[1].1 < Decimal256::percent(50));
+        });
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_reserve_shares_of_d(POOL_IDENTIFIER.to_string(), |result| {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::NotStableSwapPool.to_string()));
+        });
+    }
+}
+
+mod lp_price {
+    use super::*;
+
+    #[test]
+    fn lp_price_is_tvl_over_supply() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the pool is worth 8_000_000 uluna for 2_000_000 LP tokens
+        suite
+            .query_lp_price(POOL_IDENTIFIER.to_string(), "uluna".to_string(), |result| {
+                assert_eq!(result.unwrap().price, Decimal256::from_ratio(4u128, 1u128));
+            })
+            .query_lp_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().price, Decimal256::one());
+                },
+            )
+            .query_lp_price(POOL_IDENTIFIER.to_string(), "uusd".to_string(), |result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::AssetMismatch.to_string()));
+            });
+    }
+
+    #[test]
+    fn lp_price_is_zero_without_supply() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_lp_price(
+                "o.whale.uluna.2".to_string(),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().price, Decimal256::zero());
+                },
+            );
+    }
+}
+
+mod invariant_holds {
+    use super::*;
+
+    #[test]
+    fn invariant_holds_through_swaps() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+            let creator = suite.creator();
+
+            suite
+                .query_invariant_holds(POOL_IDENTIFIER.to_string(), 0, |result| {
+                    assert!(result.unwrap());
+                })
+                .swap(
+                    &creator,
+                    "uluna".to_string(),
+                    None,
+                    Some(Decimal::percent(20)),
+                    None,
+                    POOL_IDENTIFIER.to_string(),
+                    vec![coin(50_000u128, "uwhale".to_string())],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .query_invariant_holds(POOL_IDENTIFIER.to_string(), 0, |result| {
+                    assert!(result.unwrap());
+                })
+                .query_invariant_holds(POOL_IDENTIFIER.to_string(), 10_000, |result| {
+                    assert!(result.unwrap());
+                });
+        }
+    }
+}
+
+mod simulation_dynamic_fee {
+    use super::*;
+
+    #[test]
+    fn swap_fee_scales_with_volatility() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let simulation = RefCell::new(None);
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *simulation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            // without price moves the base fee is charged, whatever the multiplier
+            .query_simulation_dynamic_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal::permille(3),
+                Decimal::from_ratio(10u128, 1u128),
+                |result| {
+                    assert_eq!(&result.unwrap(), simulation.borrow().as_ref().unwrap());
+                },
+            );
+
+        for _ in 0..2 {
+            suite.add_blocks(100).swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *simulation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            // a zero multiplier keeps the base fee
+            .query_simulation_dynamic_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal::permille(3),
+                Decimal::zero(),
+                |result| {
+                    assert_eq!(&result.unwrap(), simulation.borrow().as_ref().unwrap());
+                },
+            )
+            // the price moved within the window, raising the fee
+            .query_simulation_dynamic_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal::permille(3),
+                Decimal::from_ratio(100u128, 1u128),
+                |result| {
+                    let response = result.unwrap();
+                    let baseline = simulation.borrow().clone().unwrap();
+                    assert!(response.swap_fee_amount > baseline.swap_fee_amount);
+                    assert_eq!(response.protocol_fee_amount, baseline.protocol_fee_amount);
+                    assert!(response.return_amount < baseline.return_amount);
+                },
+            )
+            .query_simulation_dynamic_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                Decimal::permille(999),
+                Decimal::zero(),
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::InvalidDynamicFee(Decimal::permille(999)).to_string()
+                    ));
+                },
+            );
+
+        // once the window has passed, the base fee is charged again
+        suite.add_blocks(20_000).query_simulation_dynamic_fee(
+            POOL_IDENTIFIER.to_string(),
+            coin(10_000u128, "uwhale".to_string()),
+            "uluna".to_string(),
+            Decimal::permille(3),
+            Decimal::from_ratio(100u128, 1u128),
+            |result| {
+                assert_eq!(&result.unwrap(), simulation.borrow().as_ref().unwrap());
+            },
+        );
+    }
+}
+
+mod standard_slippage {
+    use super::*;
+
+    #[test]
+    fn slippage_is_lower_on_deeper_pools() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type.clone(),
+                zero_fees(),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+            let creator = suite.creator();
+
+            suite
+                .create_pool(
+                    &creator,
+                    vec!["uwhale".to_string(), "uluna".to_string()],
+                    vec![6u8, 6u8],
+                    zero_fees(),
+                    pool_type.clone(),
+                    Some("whale.uluna.2".to_string()),
+                    vec![coin(1000, "uusd"), coin(8888, "uom")],
+                    |result| {
+                        result.unwrap();
+                    },
+                )
+                .provide_liquidity(
+                    &creator,
+                    "o.whale.uluna.2".to_string(),
+                    None,
+                    None,
+                    None,
+                    None,
+                    vec![
+                        coin(10_000_000u128, "uwhale".to_string()),
+                        coin(10_000_000u128, "uluna".to_string()),
+                    ],
+                    |result| {
+                        result.unwrap();
+                    },
+                );
+
+            let shallow_slippage = RefCell::new(Decimal256::zero());
+
+            suite
+                .query_standard_slippage(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Uint128::new(100_000),
+                    |result| {
+                        *shallow_slippage.borrow_mut() = result.unwrap();
+                    },
+                )
+                .query_standard_slippage(
+                    "o.whale.uluna.2".to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Uint128::new(100_000),
+                    |result| {
+                        let deep_slippage = result.unwrap();
+                        assert!(deep_slippage < *shallow_slippage.borrow());
+                    },
+                );
+
+            if pool_type == PoolType::ConstantProduct {
+                // selling 10% of the reserves slips by 1 - 1 / 1.1
+                suite.query_standard_slippage(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Uint128::new(100_000),
+                    |result| {
+                        assert_eq!(
+                            result.unwrap(),
+                            Decimal256::from_ratio(9_091u128, 100_000u128)
+                        );
+                    },
+                );
+            }
+        }
+    }
+}
+
+mod cheapest_for_output {
+    use super::*;
+
+    #[test]
+    fn picks_the_pool_requiring_the_least_input() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(10_000_000u128, "uwhale".to_string()),
+                    coin(10_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        suite
+            .query_cheapest_for_output(
+                coin(100_000u128, "uluna".to_string()),
+                "uwhale".to_string(),
+                vec![POOL_IDENTIFIER.to_string(), "o.whale.uluna.2".to_string()],
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.pool_identifier, "o.whale.uluna.2");
+                    assert!(response.offer_amount > Uint128::new(100_000));
+                },
+            )
+            .query_cheapest_for_output(
+                coin(100_000u128, "uluna".to_string()),
+                "uwhale".to_string(),
+                vec![POOL_IDENTIFIER.to_string(), "o.whale.uusd".to_string()],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains(&ContractError::AssetMismatch.to_string()));
+                },
+            )
+            .query_cheapest_for_output(
+                coin(100_000u128, "uluna".to_string()),
+                "uwhale".to_string(),
+                vec![],
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains(&ContractError::NoPoolForOutput.to_string()));
+                },
+            );
+    }
+}
+
+mod effective_bid_ask {
+    use super::*;
+
+    #[test]
+    fn bid_is_below_ask() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+
+            let spread = RefCell::new(Decimal256::zero());
+
+            suite
+                .query_effective_bid_ask(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Uint128::new(10_000),
+                    |result| {
+                        let response = result.unwrap();
+                        assert!(response.bid < Decimal256::one());
+                        assert!(response.ask > Decimal256::one());
+                        *spread.borrow_mut() = response.ask - response.bid;
+                    },
+                )
+                // larger trades see a wider spread
+                .query_effective_bid_ask(
+                    POOL_IDENTIFIER.to_string(),
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    Uint128::new(100_000),
+                    |result| {
+                        let response = result.unwrap();
+                        assert!(response.ask - response.bid > *spread.borrow());
+                    },
+                );
+        }
+    }
+}
+
+mod optimal_trade_for_utility {
+    use super::*;
+
+    #[test]
+    fn finds_the_utility_maximizing_offer() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // with a penalty of 1 the utility peaks at an offer of (sqrt(2) - 1) * reserve, and is
+            // flat to within a unit of the ask asset a couple thousand units around it
+            .query_optimal_trade_for_utility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::one(),
+                Uint128::new(1_000_000),
+                |result| {
+                    let offer = result.unwrap().u128();
+                    assert!(offer.abs_diff(414_213) <= 2_000, "offer: {offer}");
+                },
+            )
+            // the optimum is capped by the max offer
+            .query_optimal_trade_for_utility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::one(),
+                Uint128::new(100_000),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::new(100_000));
+                },
+            )
+            .query_optimal_trade_for_utility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::one(),
+                Uint128::zero(),
+                |result| {
+                    assert_eq!(result.unwrap(), Uint128::zero());
+                },
+            );
+    }
+}
+
+mod reserves_after_route {
+    use amm::pool_manager::SwapOperation;
+
+    use super::*;
+
+    #[test]
+    fn reserves_reflect_every_swap_of_the_route() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // a round trip through the same pool
+        let route = vec![
+            SwapOperation::MantraSwap {
+                token_in_denom: "uwhale".to_string(),
+                token_out_denom: "uluna".to_string(),
+                pool_identifier: POOL_IDENTIFIER.to_string(),
+            },
+            SwapOperation::MantraSwap {
+                token_in_denom: "uluna".to_string(),
+                token_out_denom: "uwhale".to_string(),
+                pool_identifier: POOL_IDENTIFIER.to_string(),
+            },
+        ];
+
+        suite
+            .query_reserves_after_route(Uint128::new(100_000), route.clone(), |result| {
+                assert_eq!(
+                    result.unwrap(),
+                    vec![(
+                        POOL_IDENTIFIER.to_string(),
+                        vec![
+                            coin(1_000_001u128, "uwhale".to_string()),
+                            coin(1_000_000u128, "uluna".to_string()),
+                        ]
+                    )]
+                );
+            })
+            .query_reserves_after_route(
+                Uint128::new(100_000),
+                vec![route[0].clone(), route[0].clone()],
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::NonConsecutiveSwapOperations {
+                            previous_output: "uluna".to_string(),
+                            next_input: "uwhale".to_string(),
+                        }
+                        .to_string()
+                    ));
+                },
+            );
+    }
+}
+
+mod breakeven_fee {
+    use super::*;
+
+    #[test]
+    fn breakeven_fee_grows_with_volatility() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // a 21% move is bought with 100_000 uluna, from which the LPs lose 10_000 uluna
+            .query_breakeven_fee(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(21),
+                |result| {
+                    let fee = result.unwrap();
+                    assert!(fee > Decimal256::permille(99) && fee < Decimal256::permille(101));
+                },
+            )
+            .query_breakeven_fee(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(5),
+                |result| {
+                    assert!(result.unwrap() < Decimal256::permille(30));
+                },
+            )
+            .query_breakeven_fee(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::zero(),
+                |result| {
+                    assert_eq!(result.unwrap(), Decimal256::zero());
+                },
+            );
+    }
+}
+
+mod routing_graph {
+    use super::*;
+
+    #[test]
+    fn lists_an_edge_per_pair_of_assets() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec![
+                    "uwhale".to_string(),
+                    "uluna".to_string(),
+                    "uusd".to_string(),
+                ],
+                vec![6u8, 6u8, 6u8],
+                zero_fees(),
+                PoolType::StableSwap { amp: 100 },
+                Some("whale.uluna.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_routing_graph(None, |result| {
+                let edge =
+                    |a: &str, b: &str, pool: &str| (a.to_string(), b.to_string(), pool.to_string());
+
+                assert_eq!(
+                    result.unwrap().edges,
+                    vec![
+                        edge("uwhale", "uluna", POOL_IDENTIFIER),
+                        edge("uwhale", "uluna", "o.whale.uluna.uusd"),
+                        edge("uwhale", "uusd", "o.whale.uluna.uusd"),
+                        edge("uluna", "uusd", "o.whale.uluna.uusd"),
+                    ]
+                );
+            })
+            .query_routing_graph(Some(1), |result| {
+                assert_eq!(result.unwrap().edges.len(), 1);
+            });
+    }
+}
+
+mod simulation_external_fee {
+    use super::*;
+
+    #[test]
+    fn fees_are_charged_in_the_fee_denom() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let simulation = RefCell::new(None);
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *simulation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            .query_simulation_external_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                "uom".to_string(),
+                Decimal256::from_ratio(2u128, 1u128),
+                |result| {
+                    let response = result.unwrap();
+                    let simulation = simulation.borrow().clone().unwrap();
+                    let in_kind_fee = simulation.swap_fee_amount + simulation.protocol_fee_amount;
+
+                    // the fee-less swap returns what the in-kind fee would have taken
+                    assert_eq!(
+                        response.return_amount,
+                        simulation.return_amount + in_kind_fee
+                    );
+                    assert_eq!(response.fee_in_fee_denom, in_kind_fee * Uint128::new(2));
+                },
+            )
+            .query_simulation_external_fee(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                "uom".to_string(),
+                Decimal256::zero(),
+                |result| {
+                    assert!(result.unwrap_err().to_string().contains(
+                        &ContractError::InvalidFeePrice {
+                            fee_denom: "uom".to_string()
+                        }
+                        .to_string()
+                    ));
+                },
+            );
+    }
+}
+
+mod oracle_arb_to_price {
+    use super::*;
+
+    #[test]
+    fn arbitrages_only_outside_the_fee_band() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(9), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // a 0.5% deviation is within the 1% fee
+            .query_oracle_arb_to_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::permille(1005),
+                |result| {
+                    assert_eq!(result.unwrap().offer_amount, Uint128::zero());
+                },
+            )
+            // the oracle prices uwhale higher, so it's bought with uluna
+            .query_oracle_arb_to_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(121),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uluna");
+                    assert_eq!(response.offer_amount, Uint128::new(100_000));
+                },
+            )
+            .query_oracle_arb_to_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Decimal256::percent(81),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.offer_denom, "uwhale");
+                    assert!(!response.offer_amount.is_zero());
+                },
+            );
+    }
+}
+
+mod fee_percentages {
+    use super::*;
+
+    #[test]
+    fn components_add_up_to_the_total_cost() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(
+                Decimal::permille(1),
+                Decimal::permille(3),
+                Decimal::permille(2),
+            ),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let total_cost_bps = RefCell::new(Decimal256::zero());
+
+        suite
+            .query_canonical_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    *total_cost_bps.borrow_mut() = result.unwrap().total_cost_bps;
+                },
+            )
+            .query_fee_percentages(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+
+                    // the fees are charged on the output after the spread, so they're slightly
+                    // below their nominal percentage of the fee-free output
+                    assert!(response.swap_pct < Decimal256::percent(30));
+                    assert!(response.swap_pct > Decimal256::percent(27));
+                    assert!(response.protocol_pct < Decimal256::percent(10));
+                    assert!(response.burn_pct < Decimal256::percent(20));
+
+                    let total_pct = response.swap_pct
+                        + response.protocol_pct
+                        + response.burn_pct
+                        + response.spread_pct;
+                    assert_eq!(
+                        total_pct,
+                        *total_cost_bps.borrow() / Decimal256::from_ratio(100u128, 1u128)
+                    );
+                },
+            )
+            .query_fee_percentages(
+                POOL_IDENTIFIER.to_string(),
+                coin(0u128, "uwhale".to_string()),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().spread_pct, Decimal256::zero());
+                },
+            );
+    }
+}
+
+mod canonical_pools {
+    use super::*;
+
+    #[test]
+    fn picks_the_deepest_pool_of_each_pair() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::StableSwap { amp: 100 },
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(5_000_000u128, "uwhale".to_string()),
+                    coin(5_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_canonical_pools("uwhale".to_string(), None, |result| {
+                // the uwhale-uusd pool is empty, so it's skipped
+                assert_eq!(
+                    result.unwrap().pools,
+                    vec![(
+                        "uluna".to_string(),
+                        "uwhale".to_string(),
+                        "o.whale.uluna.2".to_string()
+                    )]
+                );
+            })
+            .query_canonical_pools("uusd".to_string(), None, |result| {
+                assert!(result.unwrap().pools.is_empty());
+            });
+    }
+}
+
+mod whale_exit_impact {
+    use super::*;
+
+    #[test]
+    fn only_single_sided_exits_move_the_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_whale_exit_impact(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(500_000),
+                None,
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.price_after, Decimal256::one());
+                    assert_eq!(response.remaining_lp_value_change, Decimal256::one());
+                },
+            )
+            .query_whale_exit_impact(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(500_000),
+                Some("uwhale".to_string()),
+                |result| {
+                    // the uluna swapped into uwhale makes uwhale scarcer
+                    assert!(result.unwrap().price_after > Decimal256::one());
+                },
+            )
+            .query_whale_exit_impact(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(1_000_000),
+                None,
+                |result| {
+                    assert!(result
+                        .unwrap_err()
+                        .to_string()
+                        .contains(&ContractError::InvalidLpShareToWithdraw.to_string()));
+                },
+            );
+    }
+}
+
+mod rebalance_to_balanced {
+    use super::*;
+
+    #[test]
+    fn swap_balances_the_pool() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_500_000u128, "uwhale".to_string()),
+                coin(500_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let offer = RefCell::new(coin(0u128, "uluna"));
+
+        suite.query_rebalance_to_balanced(POOL_IDENTIFIER.to_string(), |result| {
+            let swaps = result.unwrap();
+            assert_eq!(swaps.len(), 1);
+            assert_eq!(swaps[0].denom, "uluna");
+            *offer.borrow_mut() = swaps[0].clone();
+        });
+
+        let offer = offer.borrow().clone();
+        suite
+            .swap(
+                &creator,
+                "uwhale".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![offer],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_pools(Some(POOL_IDENTIFIER.to_string()), None, None, |result| {
+                let assets = result.unwrap().pools[0].pool_info.assets.clone();
+                assert!(
+                    assets[0].amount.abs_diff(assets[1].amount) <= Uint128::new(2),
+                    "{assets:?}"
+                );
+            });
+
+        suite.query_rebalance_to_balanced(POOL_IDENTIFIER.to_string(), |result| {
+            let swaps = result.unwrap();
+            assert!(swaps.iter().all(|swap| swap.amount <= Uint128::new(2)));
+        });
+    }
+
+    #[test]
+    fn constant_product_pools_are_not_supported() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_rebalance_to_balanced(POOL_IDENTIFIER.to_string(), |result| {
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::NotStableSwapPool.to_string()));
+        });
+    }
+}
+
+mod fee_capture_ratio {
+    use super::*;
+
+    #[test]
+    fn swaps_capture_their_theoretical_fees() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .query_fee_capture_ratio(POOL_IDENTIFIER.to_string(), |result| {
+                assert_eq!(result.unwrap().ratio, Decimal256::one());
+            })
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .swap(
+                &creator,
+                "uwhale".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(12_345u128, "uluna".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_fee_capture_ratio(POOL_IDENTIFIER.to_string(), |result| {
+                // the fees are rounded down on each swap
+                let ratio = result.unwrap().ratio;
+                assert!(ratio <= Decimal256::one());
+                assert!(ratio > Decimal256::percent(99));
+            });
+    }
+
+    #[test]
+    fn dust_swaps_flag_the_fees_rounded_away() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // the fees on a 99 uluna return round down to zero
+        for _ in 0..3 {
+            suite.swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite.query_fee_capture_ratio(POOL_IDENTIFIER.to_string(), |result| {
+            assert_eq!(result.unwrap().ratio, Decimal256::zero());
+        });
+    }
+}
+
+mod share_price_hwm {
+    use super::*;
+
+    #[test]
+    fn high_water_mark_keeps_the_highest_share_price() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        let high_water_mark = RefCell::new(Decimal256::zero());
+
+        suite
+            // recorded on the first deposit
+            .query_share_price_hwm(POOL_IDENTIFIER.to_string(), "uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.current, Decimal256::from_ratio(2u128, 1u128));
+                assert_eq!(response.high_water_mark, response.current);
+            })
+            // selling uluna makes it the cheaper asset, so the LP token is worth more uluna
+            .swap(
+                &creator,
+                "uwhale".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uluna".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_share_price_hwm(POOL_IDENTIFIER.to_string(), "uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.current > Decimal256::from_ratio(2u128, 1u128));
+                assert_eq!(response.high_water_mark, response.current);
+                *high_water_mark.borrow_mut() = response.high_water_mark;
+            })
+            // selling uwhale back lowers the value in uluna, but not the high-water mark
+            .swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(200_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_share_price_hwm(POOL_IDENTIFIER.to_string(), "uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert!(response.current < *high_water_mark.borrow());
+                assert_eq!(response.high_water_mark, *high_water_mark.borrow());
+            });
+    }
+}
+
+mod global_stats {
+    use super::*;
+
+    #[test]
+    fn sums_the_tvl_of_the_pools_holding_the_quote_asset() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(500_000u128, "uwhale".to_string()),
+                    coin(500_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // a pool without the quote asset is counted but can't be valued in it
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uusd".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uusd".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_global_stats(|result| {
+                assert!(result
+                    .unwrap_err()
+                    .to_string()
+                    .contains(&ContractError::NoQuoteDenom.to_string()));
+            })
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(vec!["uluna".to_string(), "uwhale".to_string()]),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_global_stats(|result| {
+                let response = result.unwrap();
+                assert_eq!(response.total_pools, 3);
+                assert_eq!(response.total_tvl_in_quote, Uint256::from(3_000_000u128));
+                assert_eq!(response.quote_denom, "uluna");
+            });
+    }
+}
+
+mod reverse_simulation_partial {
+    use super::*;
+
+    #[test]
+    fn returns_full_ask_when_deliverable() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let offer_amount = RefCell::new(Uint128::zero());
+
+        suite
+            .query_reverse_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    *offer_amount.borrow_mut() = result.unwrap().offer_amount;
+                },
+            )
+            .query_reverse_simulation_partial(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uluna"),
+                "uwhale".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.achievable_ask, Uint128::new(100_000u128));
+                    assert_eq!(response.offer_amount, *offer_amount.borrow());
+                },
+            );
+    }
+
+    #[test]
+    fn caps_ask_near_the_reserve_limit() {
+        for pool_type in [PoolType::ConstantProduct, PoolType::StableSwap { amp: 100 }] {
+            let mut suite = setup_pool(
+                pool_type,
+                pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+            );
+
+            let achievable = RefCell::new(coin(0u128, "uluna"));
+            let offer_amount = RefCell::new(Uint128::zero());
+
+            suite
+                .query_reverse_simulation_partial(
+                    POOL_IDENTIFIER.to_string(),
+                    coin(1_000_000u128, "uluna"),
+                    "uwhale".to_string(),
+                    |result| {
+                        let response = result.unwrap();
+                        assert!(response.achievable_ask < Uint128::new(1_000_000u128));
+                        assert!(response.achievable_ask > Uint128::new(990_000u128));
+                        assert!(response.offer_amount > Uint128::new(1_000_000u128));
+                        achievable.borrow_mut().amount = response.achievable_ask;
+                        *offer_amount.borrow_mut() = response.offer_amount;
+                    },
+                )
+                // the offer amount does deliver the achievable ask
+                .query_simulation(
+                    POOL_IDENTIFIER.to_string(),
+                    coin(offer_amount.borrow().u128(), "uwhale"),
+                    "uluna".to_string(),
+                    |result| {
+                        assert!(result.unwrap().return_amount >= achievable.borrow().amount);
+                    },
+                );
+        }
+    }
+}
+
+mod price_consistency {
+    use super::*;
+
+    #[test]
+    fn reports_spread_between_pools() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .query_price_consistency("uwhale".to_string(), "uluna".to_string(), |result| {
+                assert_eq!(
+                    result.unwrap_err().to_string(),
+                    "Generic error: Querier contract error: At least two pools with a price for \
+                     uwhale in uluna are needed, found 1"
+                );
+            })
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the empty pool has no price
+            .query_price_consistency("uwhale".to_string(), "uluna".to_string(), |result| {
+                assert!(result.is_err());
+            })
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_050_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_price_consistency("uwhale".to_string(), "uluna".to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.pools, 2);
+                assert_eq!(response.spread_bps, 500);
+            });
+    }
+}
+
+mod deposit_rebalance {
+    use super::*;
+
+    fn stableswap_suite() -> TestingSuite {
+        setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        )
+    }
+
+    #[test]
+    fn balanced_deposit_pays_no_imbalance_fee() {
+        stableswap_suite().query_deposit_rebalance(
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(100_000u128, "uwhale"), coin(100_000u128, "uluna")],
+            |result| {
+                let response = result.unwrap();
+                for effective in response.effective_assets {
+                    assert!(effective.amount.abs_diff(Uint128::new(100_000u128)) <= Uint128::one());
+                }
+                for fee in response.imbalance_fee {
+                    assert!(fee.amount <= Uint128::one());
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn single_sided_deposit_is_rebalanced_and_charged() {
+        stableswap_suite()
+            .query_deposit_rebalance(
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(500_000u128, "uwhale")],
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(response.effective_assets.len(), 2);
+                    assert_eq!(response.imbalance_fee.len(), 2);
+
+                    let effective_whale = response.effective_assets[0].amount;
+                    let effective_luna = response.effective_assets[1].amount;
+                    assert!(effective_whale < Uint128::new(500_000u128));
+                    assert!(!effective_luna.is_zero());
+
+                    // the fee is charged on the whale deposited in excess, accounting for the
+                    // value lost by the deposit
+                    assert_eq!(response.imbalance_fee[0].denom, "uwhale");
+                    assert!(!response.imbalance_fee[0].amount.is_zero());
+                    assert_eq!(response.imbalance_fee[1].amount, Uint128::zero());
+                    let lost = Uint128::new(500_000u128) - effective_whale - effective_luna;
+                    assert!(response.imbalance_fee[0].amount.abs_diff(lost) <= Uint128::one());
+                },
+            )
+            .query_deposit_rebalance(
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(500_000u128, "uusd")],
+                |result| {
+                    assert_eq!(
+                        result.unwrap_err().to_string(),
+                        "Generic error: Querier contract error: The asset doesn't match the assets \
+                         stored in contract"
+                    );
+                },
+            );
+    }
+}
+
+mod simulation_safe {
+    use super::*;
+
+    #[test]
+    fn flags_swaps_draining_half_the_ask_reserve() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_simulation_safe(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(!response.liquidity_warning);
+                    assert_eq!(response.simulation.return_amount, Uint128::new(90_909u128));
+                },
+            )
+            // returns 1_000_000 - 1_000_000 * 1_000_000 / 2_100_000 = 523_809 uluna
+            .query_simulation_safe(
+                POOL_IDENTIFIER.to_string(),
+                coin(1_100_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.liquidity_warning);
+                    assert_eq!(response.simulation.return_amount, Uint128::new(523_809u128));
+                },
+            );
+    }
+}
+
+mod min_hops {
+    use super::*;
+
+    #[test]
+    fn finds_shortest_route_length() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // uwhale -> uluna -> ua -> ub -> uc -> ud -> ue, with a shortcut from uwhale to uc
+        for denoms in [
+            ["uluna", "ua"],
+            ["ua", "ub"],
+            ["ub", "uc"],
+            ["uc", "ud"],
+            ["ud", "ue"],
+            ["uwhale", "uc"],
+        ] {
+            suite.create_pool(
+                &creator,
+                denoms.iter().map(|denom| denom.to_string()).collect(),
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some(denoms.join(".")),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            .query_min_hops("uwhale".to_string(), "uwhale".to_string(), 0, |result| {
+                assert_eq!(result.unwrap(), Some(0));
+            })
+            .query_min_hops("uwhale".to_string(), "ua".to_string(), 5, |result| {
+                assert_eq!(result.unwrap(), Some(2));
+            })
+            .query_min_hops("uwhale".to_string(), "ue".to_string(), 5, |result| {
+                assert_eq!(result.unwrap(), Some(3));
+            })
+            .query_min_hops("uwhale".to_string(), "ue".to_string(), 2, |result| {
+                assert_eq!(result.unwrap(), None);
+            })
+            .query_min_hops("uwhale".to_string(), "uatom".to_string(), 5, |result| {
+                assert_eq!(result.unwrap(), None);
+            });
+    }
+}
+
+mod lp_for_target_value {
+    use super::*;
+
+    #[test]
+    fn balanced_deposit_of_target_value() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // the pool is worth 8_000_000 uluna, so 800_000 uluna is a tenth of it
+        suite
+            .query_lp_for_target_value(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(800_000u128),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(
+                        response.deposit,
+                        vec![coin(100_000u128, "uwhale"), coin(400_000u128, "uluna")]
+                    );
+                    // a tenth of the 2_000_000 LP tokens
+                    assert_eq!(response.lp_amount, Uint128::new(200_000u128));
+                },
+            )
+            .query_lp_for_target_value(
+                POOL_IDENTIFIER.to_string(),
+                Uint128::new(800_000u128),
+                "uusd".to_string(),
+                |result| {
+                    assert_eq!(
+                        result.unwrap_err().to_string(),
+                        "Generic error: Querier contract error: The asset doesn't match the assets \
+                         stored in contract"
+                    );
+                },
+            );
+    }
+}
+
+mod simulation_bench {
+    use super::*;
+
+    #[test]
+    fn reports_solver_iterations() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            pool_fees(Decimal::permille(1), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let return_amount = RefCell::new(Uint128::zero());
+        let iterations_used = RefCell::new(0u32);
+
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *return_amount.borrow_mut() = result.unwrap().return_amount;
+                },
+            )
+            // with enough iterations it matches the regular simulation
+            .query_simulation_bench(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale"),
+                "uluna".to_string(),
+                32,
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.converged);
+                    assert!(response.iterations_used > 2);
+                    assert_eq!(response.return_amount, *return_amount.borrow());
+                    *iterations_used.borrow_mut() = response.iterations_used;
+                },
+            )
+            // a larger cap doesn't change the outcome
+            .query_simulation_bench(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale"),
+                "uluna".to_string(),
+                1_000,
+                |result| {
+                    let response = result.unwrap();
+                    assert!(response.converged);
+                    assert_eq!(response.iterations_used, *iterations_used.borrow());
+                    assert_eq!(response.return_amount, *return_amount.borrow());
+                },
+            )
+            .query_simulation_bench(
+                POOL_IDENTIFIER.to_string(),
+                coin(100_000u128, "uwhale"),
+                "uluna".to_string(),
+                1,
+                |result| {
+                    let response = result.unwrap();
+                    assert!(!response.converged);
+                    assert_eq!(response.iterations_used, 2);
+                },
+            );
+    }
+
+    #[test]
+    fn constant_product_does_not_iterate() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite.query_simulation_bench(
+            POOL_IDENTIFIER.to_string(),
+            coin(100_000u128, "uwhale"),
+            "uluna".to_string(),
+            0,
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.return_amount, Uint128::new(90_909u128));
+                assert_eq!(response.iterations_used, 0);
+                assert!(response.converged);
+            },
+        );
+    }
+}
+
+mod historical_reserves {
+    use super::*;
+
+    #[test]
+    fn returns_nearest_snapshot_at_or_before_height() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let deposit_height = suite.block_height();
+
+        // the swap is within the snapshot interval of the deposit, so it's not snapshotted
+        suite.swap(
+            &creator,
+            "uluna".to_string(),
+            None,
+            Some(Decimal::percent(20)),
+            None,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(100_000u128, "uwhale".to_string())],
+            |result| {
+                result.unwrap();
+            },
+        );
+
+        suite.add_blocks(100).swap(
+            &creator,
+            "uluna".to_string(),
+            None,
+            Some(Decimal::percent(20)),
+            None,
+            POOL_IDENTIFIER.to_string(),
+            vec![coin(100_000u128, "uwhale".to_string())],
+            |result| {
+                result.unwrap();
+            },
+        );
+        let swap_height = suite.block_height();
+
+        suite
+            .query_historical_reserves(POOL_IDENTIFIER.to_string(), deposit_height + 50, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.height, deposit_height);
+                assert_eq!(
+                    response.reserves,
+                    vec![coin(1_000_000u128, "uwhale"), coin(1_000_000u128, "uluna")]
+                );
+            })
+            .query_historical_reserves(POOL_IDENTIFIER.to_string(), swap_height, |result| {
+                let response = result.unwrap();
+                assert_eq!(response.height, swap_height);
+                // 1_000_000 - 1_000_000 * 1_000_000 / 1_100_000 then
+                // 909_091 - 1_100_000 * 909_091 / 1_200_000 swapped out
+                assert_eq!(
+                    response.reserves,
+                    vec![coin(1_200_000u128, "uwhale"), coin(833_334u128, "uluna")]
+                );
+            })
+            // predating all the snapshots returns the oldest one
+            .query_historical_reserves(POOL_IDENTIFIER.to_string(), 0, |result| {
+                assert_eq!(result.unwrap().height, deposit_height);
+            });
+    }
+
+    #[test]
+    fn keeps_a_bounded_amount_of_snapshots() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+        let deposit_height = suite.block_height();
+
+        for _ in 0..100 {
+            suite.add_blocks(100).swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(50)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(1_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        // the deposit snapshot was pruned, the first swap is now the oldest
+        suite.query_historical_reserves(POOL_IDENTIFIER.to_string(), deposit_height, |result| {
+            assert_eq!(result.unwrap().height, deposit_height + 100);
+        });
+    }
+}
+
+mod blended_entry_price {
+    use super::*;
+
+    #[test]
+    fn averages_new_swap_into_position() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // swapping 250_000 uwhale returns 1_000_000 - 1_000_000 * 1_000_000 / 1_250_000 = 200_000
+        // uluna, i.e. a price of 1.25 uwhale per uluna
+        suite
+            .query_blended_entry_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::zero(),
+                Decimal256::percent(90),
+                Uint128::new(250_000u128),
+                |result| {
+                    assert_eq!(result.unwrap(), Decimal256::percent(125));
+                },
+            )
+            // (200_000 * 1 + 250_000) / (200_000 + 200_000) = 1.125
+            .query_blended_entry_price(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(200_000u128),
+                Decimal256::one(),
+                Uint128::new(250_000u128),
+                |result| {
+                    assert_eq!(result.unwrap(), Decimal256::permille(1125));
+                },
+            );
+    }
+}
+
+mod price_summary {
+    use super::*;
+
+    #[test]
+    fn bundles_mid_execution_and_impact() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(2_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        // selling 250_000 uwhale returns 2_000_000 - 2_000_000 * 1_000_000 / 1_250_000 = 400_000
+        // uluna, i.e. 1.6 uluna per uwhale against a mid price of 2
+        suite.query_price_summary(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            Uint128::new(250_000u128),
+            |result| {
+                let response = result.unwrap();
+                assert_eq!(response.mid, Decimal256::from_ratio(2u128, 1u128));
+                assert_eq!(response.execution, Decimal256::percent(160));
+                assert_eq!(response.impact_bps, 2_000);
+            },
+        );
+    }
+}
+
+mod asset_roles {
+    use super::*;
+
+    #[test]
+    fn quote_follows_configured_priority() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            // no quote denom is configured, so the roles follow the lexicographic order
+            .query_asset_roles(POOL_IDENTIFIER.to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.base, "uluna");
+                assert_eq!(response.quote, "uwhale");
+            })
+            .update_config(
+                &creator,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(vec![
+                    "uusd".to_string(),
+                    "uluna".to_string(),
+                    "uwhale".to_string(),
+                ]),
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_asset_roles(POOL_IDENTIFIER.to_string(), |result| {
+                let response = result.unwrap();
+                assert_eq!(response.base, "uwhale");
+                assert_eq!(response.quote, "uluna");
+            });
+    }
+}
+
+mod fractional_simulation {
+    use super::*;
+
+    #[test]
+    fn reports_cumulative_return_of_each_fraction() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            // without fees, swapping in fractions returns the same as swapping all at once:
+            // 1_000_000 - 1_000_000 * 1_000_000 / 1_250_000 = 200_000
+            .query_fractional_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(250_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![Decimal256::percent(40), Decimal256::percent(60)],
+                |result| {
+                    let results = result.unwrap().results;
+                    assert_eq!(results.len(), 2);
+                    // 1_000_000 - 1_000_000 * 1_000_000 / 1_100_000 = 90_909
+                    assert_eq!(results[0], Uint128::new(90_909u128));
+                    assert!(results[1].abs_diff(Uint128::new(200_000u128)) <= Uint128::one());
+                },
+            )
+            .query_fractional_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(250_000u128, "uwhale"),
+                "uluna".to_string(),
+                vec![Decimal256::percent(60), Decimal256::percent(60)],
+                |result| {
+                    assert_eq!(
+                        result.unwrap_err().to_string(),
+                        "Generic error: Querier contract error: The fractions to execute the swap \
+                         in must sum to at most one, got 1.2"
+                    );
+                },
+            );
+    }
+}
+
+mod equal_fee_split {
+    use super::*;
+
+    #[test]
+    fn pays_equal_fees_to_both_pools() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::zero(), Decimal::percent(1), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        // the second pool charges three times the fee
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                pool_fees(Decimal::zero(), Decimal::percent(3), Decimal::zero()),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_000_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            );
+
+        let split = RefCell::new((Uint128::zero(), Uint128::zero()));
+        let fees_b = RefCell::new(Uint128::zero());
+
+        suite
+            .query_equal_fee_split(
+                POOL_IDENTIFIER.to_string(),
+                "o.whale.uluna.2".to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(
+                        response.split_a + response.split_b,
+                        Uint128::new(100_000u128)
+                    );
+                    // the cheaper pool takes roughly three times as much of the offer
+                    assert!(response.split_a > response.split_b * Uint128::new(2));
+                    *split.borrow_mut() = (response.split_a, response.split_b);
+                },
+            )
+            .query_simulation(
+                "o.whale.uluna.2".to_string(),
+                coin(split.borrow().1.u128(), "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *fees_b.borrow_mut() = result.unwrap().swap_fee_amount;
+                },
+            )
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(split.borrow().0.u128(), "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    let fees_a = result.unwrap().swap_fee_amount;
+                    assert!(fees_a.abs_diff(*fees_b.borrow()) <= Uint128::one());
+                },
+            )
+            .query_equal_fee_split(
+                POOL_IDENTIFIER.to_string(),
+                "o.whale.uluna.2".to_string(),
+                "uwhale".to_string(),
+                "uusd".to_string(),
+                Uint128::new(100_000u128),
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+}
+
+mod reserve_ratio {
+    use super::*;
+
+    #[test]
+    fn ratio_ignores_the_curve() {
+        let mut suite = setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_reserve_ratio(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().ratio, Decimal256::percent(25));
+                },
+            )
+            .query_reserve_ratio(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                |result| {
+                    assert_eq!(result.unwrap().ratio, Decimal256::percent(400));
+                },
+            )
+            .query_reserve_ratio(
+                POOL_IDENTIFIER.to_string(),
+                "uluna".to_string(),
+                "uusd".to_string(),
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+}
+
+mod simulation_range {
+    use super::*;
+
+    #[test]
+    fn simulates_evenly_spaced_points() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        suite
+            .query_simulation_range(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(100_000u128),
+                Uint128::new(300_000u128),
+                3,
+                |result| {
+                    // 1_000_000 - 1_000_000 * 1_000_000 / (1_000_000 + offer)
+                    assert_eq!(
+                        result.unwrap().points,
+                        vec![
+                            (Uint128::new(100_000u128), Uint128::new(90_909u128)),
+                            (Uint128::new(200_000u128), Uint128::new(166_666u128)),
+                            (Uint128::new(300_000u128), Uint128::new(230_769u128)),
+                        ]
+                    );
+                },
+            )
+            .query_simulation_range(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(1_000u128),
+                Uint128::new(100_000u128),
+                1_000,
+                |result| {
+                    let points = result.unwrap().points;
+                    assert_eq!(points.len(), 100);
+                    assert_eq!(points.last().unwrap().0, Uint128::new(100_000u128));
+                },
+            )
+            .query_simulation_range(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(2u128),
+                Uint128::new(1u128),
+                10,
+                |result| {
+                    assert_eq!(
+                        result.unwrap_err().to_string(),
+                        "Generic error: Querier contract error: The minimum amount 2 of the range \
+                         is above its maximum amount 1"
+                    );
+                },
+            );
+    }
+}
+
+mod best_pool_for_pair {
+    use super::*;
+    use amm::pool_manager::BestPoolResponse;
+
+    #[test]
+    fn picks_the_pool_with_the_highest_return() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        suite
+            .create_pool(
+                &creator,
+                vec!["uwhale".to_string(), "uluna".to_string()],
+                vec![6u8, 6u8],
+                zero_fees(),
+                PoolType::ConstantProduct,
+                Some("whale.uluna.2".to_string()),
+                vec![coin(1000, "uusd"), coin(8888, "uom")],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            // the empty pool can't be swapped on
+            .query_best_pool_for_pair(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(1_000u128),
+                |result| {
+                    assert_eq!(result.unwrap().pool_identifier, POOL_IDENTIFIER);
+                },
+            )
+            .provide_liquidity(
+                &creator,
+                "o.whale.uluna.2".to_string(),
+                None,
+                None,
+                None,
+                None,
+                vec![
+                    coin(1_000_000u128, "uwhale".to_string()),
+                    coin(1_050_000u128, "uluna".to_string()),
+                ],
+                |result| {
+                    result.unwrap();
+                },
+            )
+            .query_best_pool_for_pair(
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                Uint128::new(1_000u128),
+                |result| {
+                    // 1_050_000 - 1_000_000 * 1_050_000 / 1_001_000
+                    assert_eq!(
+                        result.unwrap(),
+                        BestPoolResponse {
+                            pool_identifier: "o.whale.uluna.2".to_string(),
+                            return_amount: Uint128::new(1_048u128),
+                        }
+                    );
+                },
+            )
+            .query_best_pool_for_pair(
+                "uluna".to_string(),
+                "uwhale".to_string(),
+                Uint128::new(1_000u128),
+                |result| {
+                    assert_eq!(result.unwrap().pool_identifier, POOL_IDENTIFIER);
+                },
+            )
+            .query_best_pool_for_pair(
+                "uwhale".to_string(),
+                "uusd".to_string(),
+                Uint128::new(1_000u128),
+                |result| {
+                    assert_eq!(
+                        result.unwrap_err().to_string(),
+                        "Generic error: Querier contract error: No pool can swap uwhale into uusd"
+                    );
+                },
+            );
+    }
+}
+
+mod slippage_coefficient {
+    use super::*;
+
+    #[test]
+    fn constant_product_coefficient_is_two_over_the_base_reserve() {
+        setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(4_000_000u128, "uluna".to_string()),
+            ],
+        )
+        .query_slippage_coefficient(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                assert_eq!(
+                    result.unwrap().coefficient,
+                    Decimal256::from_ratio(2u128, 1u128)
+                );
+            },
+        )
+        .query_slippage_coefficient(
+            POOL_IDENTIFIER.to_string(),
+            "uluna".to_string(),
+            "uwhale".to_string(),
+            |result| {
+                assert_eq!(result.unwrap().coefficient, Decimal256::percent(50));
+            },
+        );
+    }
+
+    #[test]
+    fn stableswap_coefficient_shrinks_with_the_amplification() {
+        setup_pool(
+            PoolType::StableSwap { amp: 100 },
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        )
+        .query_slippage_coefficient(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uluna".to_string(),
+            |result| {
+                // balanced pools give 4 / (x * (ann + 2)), with ann = 200
+                let expected = Decimal256::from_ratio(4u128, 202u128);
+                let coefficient = result.unwrap().coefficient;
+                assert!(
+                    coefficient.abs_diff(expected) < Decimal256::from_ratio(1u128, 10u128.pow(12))
+                );
+            },
+        )
+        .query_slippage_coefficient(
+            POOL_IDENTIFIER.to_string(),
+            "uwhale".to_string(),
+            "uusd".to_string(),
+            |result| {
+                assert!(result.is_err());
+            },
+        );
+    }
+}
+
+mod config_migration_check {
+    use super::*;
+    use amm::pool_manager::FeeTierThresholds;
+    use cosmwasm_std::Addr;
+
+    #[test]
+    fn flags_disruptive_changes() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::zero(), Decimal::permille(3), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let current = suite.query_config();
+
+        let mut proposed = current.clone();
+        proposed.pool_creation_fee = coin(1u128, "uusd");
+
+        suite.query_config_migration_check(proposed, |result| {
+            let response = result.unwrap();
+            assert!(response.safe);
+            assert!(response.warnings.is_empty());
+        });
+
+        let mut proposed = current.clone();
+        proposed.fee_collector_addr = Addr::unchecked("new_fee_collector");
+        proposed.feature_toggle.swaps_enabled = false;
+        proposed.fee_tier_thresholds = FeeTierThresholds {
+            stable_max: Decimal::permille(1),
+            standard_max: Decimal::permille(2),
+        };
+        proposed.quote_denom_priority = vec!["uluna".to_string()];
+
+        suite.query_config_migration_check(proposed, |result| {
+            let response = result.unwrap();
+            assert!(!response.safe);
+            assert_eq!(
+                response.warnings,
+                vec![
+                    format!(
+                        "The fee collector address changes from {} to new_fee_collector",
+                        current.fee_collector_addr
+                    ),
+                    "Swaps get disabled".to_string(),
+                    "Pool o.whale.uluna moves from the Standard to the Exotic fee tier".to_string(),
+                    "Pool o.whale.uluna changes its quote asset from uwhale to uluna".to_string(),
+                ]
+            );
+        });
+    }
+}
+
+mod simulation_with_reinvest {
+    use super::*;
+
+    #[test]
+    fn retains_every_fee_in_the_pool() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            pool_fees(Decimal::percent(1), Decimal::percent(2), Decimal::zero()),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+
+        let simulation = RefCell::new(None);
+        suite
+            .query_simulation(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    *simulation.borrow_mut() = Some(result.unwrap());
+                },
+            )
+            .query_simulation_with_reinvest(
+                POOL_IDENTIFIER.to_string(),
+                coin(10_000u128, "uwhale"),
+                "uluna".to_string(),
+                |result| {
+                    let response = result.unwrap();
+                    assert_eq!(Some(response.simulation.clone()), *simulation.borrow());
+                    assert_eq!(response.simulation.return_amount, Uint128::new(9_603u128));
+
+                    // the 297 uluna of fees against the 2 * 990_397 - 297 uluna the rest is worth
+                    let expected = Decimal256::from_ratio(297u128, 1_980_497u128);
+                    assert!(
+                        response.share_value_increase.abs_diff(expected)
+                            < Decimal256::from_ratio(1u128, 10u128.pow(15))
+                    );
+                },
+            );
+    }
+}
+
+mod realized_volatility {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn computes_the_deviation_of_log_returns() {
+        let mut suite = setup_pool(
+            PoolType::ConstantProduct,
+            zero_fees(),
+            vec![
+                coin(1_000_000u128, "uwhale".to_string()),
+                coin(1_000_000u128, "uluna".to_string()),
+            ],
+        );
+        let creator = suite.creator();
+
+        for _ in 0..2 {
+            suite.add_blocks(100).swap(
+                &creator,
+                "uluna".to_string(),
+                None,
+                Some(Decimal::percent(20)),
+                None,
+                POOL_IDENTIFIER.to_string(),
+                vec![coin(100_000u128, "uwhale".to_string())],
+                |result| {
+                    result.unwrap();
+                },
+            );
+        }
+
+        suite
+            // only the snapshot of the last swap is in the window
+            .query_realized_volatility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                50,
+                |result| {
+                    assert_eq!(result.unwrap().volatility, Decimal256::zero());
+                },
+            )
+            .query_realized_volatility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uluna".to_string(),
+                1_000,
+                |result| {
+                    // the prices 1, 909_091 / 1_100_000 and 833_334 / 1_200_000 give the
+                    // log-returns -0.190620259608654720 and -0.174022053979574532
+                    let expected = Decimal256::from_str("0.008299102814540093").unwrap();
+                    let volatility = result.unwrap().volatility;
+                    assert!(
+                        volatility.abs_diff(expected)
+                            < Decimal256::from_ratio(1u128, 10u128.pow(12))
+                    );
+                },
+            )
+            .query_realized_volatility(
+                POOL_IDENTIFIER.to_string(),
+                "uwhale".to_string(),
+                "uusd".to_string(),
+                1_000,
+                |result| {
+                    assert!(result.is_err());
+                },
+            );
+    }
+}
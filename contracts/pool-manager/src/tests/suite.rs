@@ -1,12 +1,33 @@
 use amm::pool_manager::{
-    Config, FeatureToggle, PoolsResponse, ReverseSimulateSwapOperationsResponse,
-    ReverseSimulationResponse, SimulateSwapOperationsResponse, SimulationResponse, SwapOperation,
+    AmpImpactResponse, AmpRampResponse, AmpRangeSimulationResponse, AprContributionResponse,
+    AssetRolesResponse, BalancedSlippageResponse, BandWidthResponse, BenchSimulationResponse,
+    BestPoolResponse, BidAskResponse, CanonicalPoolsResponse, CanonicalSimulationResponse,
+    CapacityHeadroomResponse, CascadeResponse, CheaperDirectionResponse, CheapestResponse,
+    ConcentrationResponse, Config, ConsistencyResponse, CurveCoefficientsResponse,
+    DepositRebalanceResponse, DirectVsRouteResponse, EncodedRouteResponse, EqualFeeSplitResponse,
+    ExternalFeeSimulationResponse, FeatureToggle, FeeCaptureResponse, FeePercentagesResponse,
+    FeeRecipientsResponse, FeeSplitResponse, FeeTierResponse, FeeTierThresholds,
+    FractionalSimulationResponse, GlobalStatsResponse, HistoricalReservesResponse,
+    ImbalanceFeeResponse, IntegrityResponse, LpForValueResponse, LpPriceResponse, LpValueResponse,
+    ManyToOneResponse, MarginalCurveResponse, MigrationCheckResponse, PartialReverseResponse,
+    PegDeviationResponse, PoolTypeStatsResponse, PoolsResponse, PriceMatrixResponse,
+    PriceSummaryResponse, RangeSimulationResponse, RationalPriceResponse, RebalanceResponse,
+    ReferenceSimulationResponse, ReinvestSimulationResponse, ReserveRatioResponse,
+    RevenueDistributionResponse, ReverseSimulateSwapOperationsResponse, ReverseSimulationResponse,
+    RoutingGraphResponse, SafeSimulationResponse, SandwichRiskResponse, SensitivityResponse,
+    SharePriceResponse, SimulateSwapOperationsResponse, SimulationResponse,
+    SimulationWithReservesResponse, SlippageCoefficientResponse, SwapCheckResponse,
+    SwapFeeInOfferResponse, SwapOperation, ValidatePoolResponse, VolatilityResponse,
+    WhaleExitResponse, ZapInResponse, ZapOutResponse,
 };
 use amm::pool_manager::{InstantiateMsg, PoolType};
 use cosmwasm_std::testing::MockStorage;
 use std::cell::RefCell;
 
-use cosmwasm_std::{coin, Addr, Coin, Decimal, Empty, StdResult, Timestamp, Uint128, Uint64};
+use cosmwasm_std::{
+    coin, Addr, Binary, Coin, Decimal, Decimal256, Empty, StdResult, Timestamp, Uint128, Uint256,
+    Uint64,
+};
 use cw_multi_test::{
     App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, DistributionKeeper,
     Executor, FailingModule, GovFailingModule, IbcFailingModule, MockApiBech32, StakeKeeper,
@@ -95,6 +116,10 @@ impl TestingSuite {
         self.senders.first().unwrap().clone()
     }
 
+    pub(crate) fn get_time(&self) -> Timestamp {
+        self.app.block_info().time
+    }
+
     pub(crate) fn set_time(&mut self, timestamp: Timestamp) -> &mut Self {
         let mut block_info = self.app.block_info();
         block_info.time = timestamp;
@@ -115,6 +140,18 @@ impl TestingSuite {
         self
     }
 
+    pub(crate) fn add_blocks(&mut self, blocks: u64) -> &mut Self {
+        let mut block_info = self.app.block_info();
+        block_info.height += blocks;
+        self.app.set_block(block_info);
+
+        self
+    }
+
+    pub(crate) fn block_height(&self) -> u64 {
+        self.app.block_info().height
+    }
+
     pub(crate) fn get_lp_denom(&self, pool_identifier: String) -> String {
         format!(
             "factory/{}/{}.{}",
@@ -473,6 +510,7 @@ impl TestingSuite {
     /// Any parameters which are set to `None` when passed will not update
     /// the current configuration.
     #[track_caller]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn update_config(
         &mut self,
         sender: &Addr,
@@ -480,6 +518,8 @@ impl TestingSuite {
         new_farm_manager_addr: Option<Addr>,
         new_pool_creation_fee: Option<Coin>,
         new_feature_toggle: Option<FeatureToggle>,
+        new_fee_tier_thresholds: Option<FeeTierThresholds>,
+        new_quote_denom_priority: Option<Vec<String>>,
         result: impl Fn(Result<AppResponse, anyhow::Error>),
     ) -> &mut Self {
         result(self.app.execute_contract(
@@ -490,6 +530,30 @@ impl TestingSuite {
                 farm_manager_addr: new_farm_manager_addr.map(|addr| addr.to_string()),
                 pool_creation_fee: new_pool_creation_fee,
                 feature_toggle: new_feature_toggle,
+                fee_tier_thresholds: new_fee_tier_thresholds,
+                quote_denom_priority: new_quote_denom_priority,
+            },
+            &[],
+        ));
+
+        self
+    }
+
+    pub(crate) fn ramp_amp(
+        &mut self,
+        sender: &Addr,
+        pool_identifier: String,
+        target_amp: u64,
+        ramp_end_time: u64,
+        result: impl Fn(Result<AppResponse, anyhow::Error>),
+    ) -> &mut Self {
+        result(self.app.execute_contract(
+            sender.clone(),
+            self.pool_manager_addr.clone(),
+            &amm::pool_manager::ExecuteMsg::RampAmp {
+                pool_identifier,
+                target_amp,
+                ramp_end_time,
             },
             &[],
         ));
@@ -763,4 +827,2037 @@ impl TestingSuite {
 
         self
     }
+
+    pub(crate) fn query_rebalance_swap(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        target_price: Decimal256,
+        result: impl Fn(StdResult<RebalanceResponse>),
+    ) -> &mut Self {
+        let rebalance_response: StdResult<RebalanceResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::RebalanceSwap {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                target_price,
+            },
+        );
+
+        result(rebalance_response);
+
+        self
+    }
+
+    pub(crate) fn query_canonical_simulation(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<CanonicalSimulationResponse>),
+    ) -> &mut Self {
+        let canonical_simulation_response: StdResult<CanonicalSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::CanonicalSimulation {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(canonical_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_lp_value(
+        &mut self,
+        pool_identifier: String,
+        lp_amount: Uint128,
+        result: impl Fn(StdResult<LpValueResponse>),
+    ) -> &mut Self {
+        let lp_value_response: StdResult<LpValueResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::LpValue {
+                pool_identifier,
+                lp_amount,
+            },
+        );
+
+        result(lp_value_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_sanity(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        max_reasonable_price: Decimal256,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationSanity {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                max_reasonable_price,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_pool_type_stats(
+        &mut self,
+        result: impl Fn(StdResult<PoolTypeStatsResponse>),
+    ) -> &mut Self {
+        let pool_type_stats_response: StdResult<PoolTypeStatsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PoolTypeStats {},
+            );
+
+        result(pool_type_stats_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulate_zap_in(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        result: impl Fn(StdResult<ZapInResponse>),
+    ) -> &mut Self {
+        let zap_in_response: StdResult<ZapInResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulateZapIn {
+                pool_identifier,
+                offer_asset,
+            },
+        );
+
+        result(zap_in_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulate_zap_out(
+        &mut self,
+        pool_identifier: String,
+        lp_amount: Uint128,
+        target_denom: String,
+        result: impl Fn(StdResult<ZapOutResponse>),
+    ) -> &mut Self {
+        let zap_out_response: StdResult<ZapOutResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulateZapOut {
+                pool_identifier,
+                lp_amount,
+                target_denom,
+            },
+        );
+
+        result(zap_out_response);
+
+        self
+    }
+
+    pub(crate) fn query_amp_ramp_status(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<AmpRampResponse>),
+    ) -> &mut Self {
+        let amp_ramp_response: StdResult<AmpRampResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::AmpRampStatus { pool_identifier },
+        );
+
+        result(amp_ramp_response);
+
+        self
+    }
+
+    pub(crate) fn query_pool_integrity(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<IntegrityResponse>),
+    ) -> &mut Self {
+        let integrity_response: StdResult<IntegrityResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::PoolIntegrity { pool_identifier },
+        );
+
+        result(integrity_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_conservative(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationConservative {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_cheaper_direction(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<CheaperDirectionResponse>),
+    ) -> &mut Self {
+        let cheaper_direction_response: StdResult<CheaperDirectionResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::CheaperDirection { pool_identifier },
+            );
+
+        result(cheaper_direction_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_recipients(
+        &mut self,
+        result: impl Fn(StdResult<FeeRecipientsResponse>),
+    ) -> &mut Self {
+        let fee_recipients_response: StdResult<FeeRecipientsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::FeeRecipients {},
+            );
+
+        result(fee_recipients_response);
+
+        self
+    }
+
+    pub(crate) fn query_amp_change_lp_impact(
+        &mut self,
+        pool_identifier: String,
+        new_amp: Uint256,
+        result: impl Fn(StdResult<AmpImpactResponse>),
+    ) -> &mut Self {
+        let amp_impact_response: StdResult<AmpImpactResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::AmpChangeLpImpact {
+                pool_identifier,
+                new_amp,
+            },
+        );
+
+        result(amp_impact_response);
+
+        self
+    }
+
+    pub(crate) fn query_encode_route(
+        &mut self,
+        operations: Vec<SwapOperation>,
+        result: impl Fn(StdResult<EncodedRouteResponse>),
+    ) -> &mut Self {
+        let encoded_route_response: StdResult<EncodedRouteResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::EncodeRoute { operations },
+            );
+
+        result(encoded_route_response);
+
+        self
+    }
+
+    pub(crate) fn query_decode_route(
+        &mut self,
+        encoded: Binary,
+        result: impl Fn(StdResult<Vec<SwapOperation>>),
+    ) -> &mut Self {
+        let decoded_route_response: StdResult<Vec<SwapOperation>> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::DecodeRoute { encoded },
+            );
+
+        result(decoded_route_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_split(
+        &mut self,
+        pool_identifier: String,
+        offer_amount: Uint128,
+        result: impl Fn(StdResult<FeeSplitResponse>),
+    ) -> &mut Self {
+        let fee_split_response: StdResult<FeeSplitResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::FeeSplit {
+                pool_identifier,
+                offer_amount,
+            },
+        );
+
+        result(fee_split_response);
+
+        self
+    }
+
+    pub(crate) fn query_capacity_headroom(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<CapacityHeadroomResponse>),
+    ) -> &mut Self {
+        let capacity_headroom_response: StdResult<CapacityHeadroomResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::CapacityHeadroom { pool_identifier },
+            );
+
+        result(capacity_headroom_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_with_reserves_out(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SimulationWithReservesResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationWithReservesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationWithReservesOut {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_marginal_output_curve(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        steps: u32,
+        max_offer: Uint128,
+        result: impl Fn(StdResult<MarginalCurveResponse>),
+    ) -> &mut Self {
+        let marginal_curve_response: StdResult<MarginalCurveResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::MarginalOutputCurve {
+                    pool_identifier,
+                    offer_denom,
+                    ask_denom,
+                    steps,
+                    max_offer,
+                },
+            );
+
+        result(marginal_curve_response);
+
+        self
+    }
+
+    pub(crate) fn query_validate_pool_spec(
+        &mut self,
+        asset_denoms: Vec<String>,
+        asset_decimals: Vec<u8>,
+        pool_type: PoolType,
+        pool_fees: PoolFee,
+        result: impl Fn(StdResult<ValidatePoolResponse>),
+    ) -> &mut Self {
+        let validate_pool_response: StdResult<ValidatePoolResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ValidatePoolSpec {
+                    asset_denoms,
+                    asset_decimals,
+                    pool_type,
+                    pool_fees,
+                },
+            );
+
+        result(validate_pool_response);
+
+        self
+    }
+
+    pub(crate) fn query_canonical_asset_order(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Vec<String>>),
+    ) -> &mut Self {
+        let canonical_asset_order_response: StdResult<Vec<String>> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::CanonicalAssetOrder { pool_identifier },
+            );
+
+        result(canonical_asset_order_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_min_amp(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationMinAmp {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_offer_to_reach_price(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        target_price: Decimal256,
+        result: impl Fn(StdResult<Uint128>),
+    ) -> &mut Self {
+        let offer_amount_response: StdResult<Uint128> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::OfferToReachPrice {
+                pool_identifier,
+                offer_denom,
+                ask_denom,
+                target_price,
+            },
+        );
+
+        result(offer_amount_response);
+
+        self
+    }
+
+    pub(crate) fn query_pricing_path(
+        &mut self,
+        denom: String,
+        base_denom: String,
+        max_hops: u32,
+        result: impl Fn(StdResult<Vec<String>>),
+    ) -> &mut Self {
+        let pricing_path_response: StdResult<Vec<String>> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::PricingPath {
+                denom,
+                base_denom,
+                max_hops,
+            },
+        );
+
+        result(pricing_path_response);
+
+        self
+    }
+
+    pub(crate) fn query_breakeven_spread(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_denom: String,
+        result: impl Fn(StdResult<Decimal256>),
+    ) -> &mut Self {
+        let breakeven_spread_response: StdResult<Decimal256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::BreakevenSpread {
+                pool_identifier,
+                offer_asset,
+                ask_denom,
+            },
+        );
+
+        result(breakeven_spread_response);
+
+        self
+    }
+
+    pub(crate) fn query_swap_would_succeed(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        min_receive: Uint128,
+        max_spread: Decimal256,
+        result: impl Fn(StdResult<SwapCheckResponse>),
+    ) -> &mut Self {
+        let swap_check_response: StdResult<SwapCheckResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SwapWouldSucceed {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                min_receive,
+                max_spread,
+            },
+        );
+
+        result(swap_check_response);
+
+        self
+    }
+
+    pub(crate) fn query_peg_deviation(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<PegDeviationResponse>),
+    ) -> &mut Self {
+        let peg_deviation_response: StdResult<PegDeviationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PegDeviation {
+                    pool_identifier,
+                    base_denom,
+                    quote_denom,
+                },
+            );
+
+        result(peg_deviation_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_many_to_one(
+        &mut self,
+        offer_assets: Vec<Coin>,
+        ask_denom: String,
+        pool_routes: Vec<Vec<SwapOperation>>,
+        result: impl Fn(StdResult<ManyToOneResponse>),
+    ) -> &mut Self {
+        let many_to_one_response: StdResult<ManyToOneResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationManyToOne {
+                offer_assets,
+                ask_denom,
+                pool_routes,
+            },
+        );
+
+        result(many_to_one_response);
+
+        self
+    }
+
+    pub(crate) fn query_swap_fee_in_offer(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<SwapFeeInOfferResponse>),
+    ) -> &mut Self {
+        let swap_fee_response: StdResult<SwapFeeInOfferResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SwapFeeInOffer {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(swap_fee_response);
+
+        self
+    }
+
+    pub(crate) fn query_post_fee_withdrawal_reserves(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Vec<Coin>>),
+    ) -> &mut Self {
+        let reserves_response: StdResult<Vec<Coin>> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::PostFeeWithdrawalReserves { pool_identifier },
+        );
+
+        result(reserves_response);
+
+        self
+    }
+
+    pub(crate) fn query_safe_trade_size(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        max_impact_bps: u16,
+        result: impl Fn(StdResult<Uint128>),
+    ) -> &mut Self {
+        let safe_trade_size_response: StdResult<Uint128> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SafeTradeSize {
+                pool_identifier,
+                offer_denom,
+                ask_denom,
+                max_impact_bps,
+            },
+        );
+
+        result(safe_trade_size_response);
+
+        self
+    }
+
+    pub(crate) fn query_adjacent_pools(
+        &mut self,
+        pool_identifier: String,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &mut Self {
+        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::AdjacentPools {
+                pool_identifier,
+                limit,
+            },
+        );
+
+        result(pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_matrix(
+        &mut self,
+        pool_identifier: String,
+        probe_amount: Uint128,
+        result: impl Fn(StdResult<PriceMatrixResponse>),
+    ) -> &mut Self {
+        let price_matrix_response: StdResult<PriceMatrixResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PriceMatrix {
+                    pool_identifier,
+                    probe_amount,
+                },
+            );
+
+        result(price_matrix_response);
+
+        self
+    }
+
+    pub(crate) fn query_constant_product_equivalent_amp(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Uint256>),
+    ) -> &mut Self {
+        let equivalent_amp_response: StdResult<Uint256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::ConstantProductEquivalentAmp { pool_identifier },
+        );
+
+        result(equivalent_amp_response);
+
+        self
+    }
+
+    pub(crate) fn query_sandwich_risk(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        attacker_capital: Uint128,
+        result: impl Fn(StdResult<SandwichRiskResponse>),
+    ) -> &mut Self {
+        let sandwich_risk_response: StdResult<SandwichRiskResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SandwichRisk {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                    attacker_capital,
+                },
+            );
+
+        result(sandwich_risk_response);
+
+        self
+    }
+
+    pub(crate) fn query_ramping_pools(
+        &mut self,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &mut Self {
+        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::RampingPools { limit },
+        );
+
+        result(pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_liquidity_concentration(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<ConcentrationResponse>),
+    ) -> &mut Self {
+        let concentration_response: StdResult<ConcentrationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::LiquidityConcentration { pool_identifier },
+            );
+
+        result(concentration_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_with_pending(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        pending_deposits: Vec<Coin>,
+        pending_withdrawals: Vec<Coin>,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationWithPending {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                pending_deposits,
+                pending_withdrawals,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_max_possible_output(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        result: impl Fn(StdResult<Uint128>),
+    ) -> &mut Self {
+        let max_output_response: StdResult<Uint128> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::MaxPossibleOutput {
+                pool_identifier,
+                offer_denom,
+                ask_denom,
+            },
+        );
+
+        result(max_output_response);
+
+        self
+    }
+
+    pub(crate) fn query_direct_vs_route(
+        &mut self,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        direct_pool: String,
+        route: Vec<SwapOperation>,
+        result: impl Fn(StdResult<DirectVsRouteResponse>),
+    ) -> &mut Self {
+        let direct_vs_route_response: StdResult<DirectVsRouteResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::DirectVsRoute {
+                    offer_asset,
+                    ask_asset_denom,
+                    direct_pool,
+                    route,
+                },
+            );
+
+        result(direct_vs_route_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_vs_reference(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        reference_price: Decimal256,
+        result: impl Fn(StdResult<ReferenceSimulationResponse>),
+    ) -> &mut Self {
+        let reference_simulation_response: StdResult<ReferenceSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationVsReference {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                    reference_price,
+                },
+            );
+
+        result(reference_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulate_until_price(
+        &mut self,
+        pool_identifier: String,
+        offer_asset_denom: String,
+        ask_asset_denom: String,
+        total_offer: Uint128,
+        stop_price: Decimal256,
+        result: impl Fn(StdResult<CascadeResponse>),
+    ) -> &mut Self {
+        let cascade_response: StdResult<CascadeResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulateUntilPrice {
+                pool_identifier,
+                offer_asset_denom,
+                ask_asset_denom,
+                total_offer,
+                stop_price,
+            },
+        );
+
+        result(cascade_response);
+
+        self
+    }
+
+    pub(crate) fn query_imbalance_fee_schedule(
+        &mut self,
+        pool_identifier: String,
+        sample_points: u32,
+        result: impl Fn(StdResult<ImbalanceFeeResponse>),
+    ) -> &mut Self {
+        let imbalance_fee_response: StdResult<ImbalanceFeeResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ImbalanceFeeSchedule {
+                    pool_identifier,
+                    sample_points,
+                },
+            );
+
+        result(imbalance_fee_response);
+
+        self
+    }
+
+    pub(crate) fn query_lp_to_withdraw(
+        &mut self,
+        pool_identifier: String,
+        desired: Coin,
+        result: impl Fn(StdResult<Uint128>),
+    ) -> &mut Self {
+        let lp_to_withdraw_response: StdResult<Uint128> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::LpToWithdraw {
+                pool_identifier,
+                desired,
+            },
+        );
+
+        result(lp_to_withdraw_response);
+
+        self
+    }
+
+    pub(crate) fn query_rebalance_to_equal(
+        &mut self,
+        pool_identifier: String,
+        balance_a: Coin,
+        balance_b: Coin,
+        result: impl Fn(StdResult<RebalanceResponse>),
+    ) -> &mut Self {
+        let rebalance_response: StdResult<RebalanceResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::RebalanceToEqual {
+                pool_identifier,
+                balance_a,
+                balance_b,
+            },
+        );
+
+        result(rebalance_response);
+
+        self
+    }
+
+    pub(crate) fn query_revenue_over_distribution(
+        &mut self,
+        pool_identifier: String,
+        trade_sizes: Vec<Uint128>,
+        result: impl Fn(StdResult<RevenueDistributionResponse>),
+    ) -> &mut Self {
+        let revenue_distribution_response: StdResult<RevenueDistributionResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::RevenueOverDistribution {
+                    pool_identifier,
+                    trade_sizes,
+                },
+            );
+
+        result(revenue_distribution_response);
+
+        self
+    }
+
+    pub(crate) fn query_band_width_for_fees(
+        &mut self,
+        pool_identifier: String,
+        new_total_fee: Decimal,
+        result: impl Fn(StdResult<BandWidthResponse>),
+    ) -> &mut Self {
+        let band_width_response: StdResult<BandWidthResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::BandWidthForFees {
+                pool_identifier,
+                new_total_fee,
+            },
+        );
+
+        result(band_width_response);
+
+        self
+    }
+
+    pub(crate) fn query_mid_price_rational(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<RationalPriceResponse>),
+    ) -> &mut Self {
+        let rational_price_response: StdResult<RationalPriceResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::MidPriceRational {
+                    pool_identifier,
+                    base_denom,
+                    quote_denom,
+                },
+            );
+
+        result(rational_price_response);
+
+        self
+    }
+
+    pub(crate) fn query_swap_apr_contribution(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<AprContributionResponse>),
+    ) -> &mut Self {
+        let apr_contribution_response: StdResult<AprContributionResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SwapAprContribution {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(apr_contribution_response);
+
+        self
+    }
+
+    pub(crate) fn query_reserves_for_invariant(
+        &mut self,
+        pool_identifier: String,
+        target_d: Uint256,
+        result: impl Fn(StdResult<Vec<Coin>>),
+    ) -> &mut Self {
+        let reserves_response: StdResult<Vec<Coin>> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::ReservesForInvariant {
+                pool_identifier,
+                target_d,
+            },
+        );
+
+        result(reserves_response);
+
+        self
+    }
+
+    pub(crate) fn query_reachable(
+        &mut self,
+        offer_denom: String,
+        ask_denom: String,
+        max_hops: u32,
+        result: impl Fn(StdResult<bool>),
+    ) -> &mut Self {
+        let reachable_response: StdResult<bool> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::Reachable {
+                offer_denom,
+                ask_denom,
+                max_hops,
+            },
+        );
+
+        result(reachable_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_sensitivity(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<SensitivityResponse>),
+    ) -> &mut Self {
+        let sensitivity_response: StdResult<SensitivityResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PriceSensitivity {
+                    pool_identifier,
+                    base_denom,
+                    quote_denom,
+                },
+            );
+
+        result(sensitivity_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_tier(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<FeeTierResponse>),
+    ) -> &mut Self {
+        let fee_tier_response: StdResult<FeeTierResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::FeeTier { pool_identifier },
+        );
+
+        result(fee_tier_response);
+
+        self
+    }
+
+    pub(crate) fn query_balanced_slippage(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        amount: Uint128,
+        result: impl Fn(StdResult<BalancedSlippageResponse>),
+    ) -> &mut Self {
+        let balanced_slippage_response: StdResult<BalancedSlippageResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::BalancedSlippage {
+                    pool_identifier,
+                    offer_denom,
+                    ask_denom,
+                    amount,
+                },
+            );
+
+        result(balanced_slippage_response);
+
+        self
+    }
+
+    pub(crate) fn query_min_amp_for_positive_spread(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Uint256>),
+    ) -> &mut Self {
+        let min_amp_response: StdResult<Uint256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::MinAmpForPositiveSpread { pool_identifier },
+        );
+
+        result(min_amp_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_with_rebate(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        rebate_bps: u16,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationWithRebate {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                rebate_bps,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_curve_coefficients(
+        &mut self,
+        pool_identifier: String,
+        offer_asset_denom: String,
+        ask_asset_denom: String,
+        around: Uint128,
+        result: impl Fn(StdResult<CurveCoefficientsResponse>),
+    ) -> &mut Self {
+        let curve_coefficients_response: StdResult<CurveCoefficientsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PriceCurveCoefficients {
+                    pool_identifier,
+                    offer_asset_denom,
+                    ask_asset_denom,
+                    around,
+                },
+            );
+
+        result(curve_coefficients_response);
+
+        self
+    }
+
+    pub(crate) fn query_pools_above_fee(
+        &mut self,
+        min_total_fee: Decimal,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<PoolsResponse>),
+    ) -> &mut Self {
+        let pools_response: StdResult<PoolsResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::PoolsAboveFee {
+                min_total_fee,
+                limit,
+            },
+        );
+
+        result(pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_amp_range(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<AmpRangeSimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<AmpRangeSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationAmpRange {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_reserve_shares_of_d(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Vec<(String, Decimal256)>>),
+    ) -> &mut Self {
+        let reserve_shares_response: StdResult<Vec<(String, Decimal256)>> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ReserveSharesOfD { pool_identifier },
+            );
+
+        result(reserve_shares_response);
+
+        self
+    }
+
+    pub(crate) fn query_lp_price(
+        &mut self,
+        pool_identifier: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<LpPriceResponse>),
+    ) -> &mut Self {
+        let lp_price_response: StdResult<LpPriceResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::LpPrice {
+                pool_identifier,
+                quote_denom,
+            },
+        );
+
+        result(lp_price_response);
+
+        self
+    }
+
+    pub(crate) fn query_invariant_holds(
+        &mut self,
+        pool_identifier: String,
+        tolerance_bps: u16,
+        result: impl Fn(StdResult<bool>),
+    ) -> &mut Self {
+        let invariant_holds_response: StdResult<bool> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::InvariantHolds {
+                pool_identifier,
+                tolerance_bps,
+            },
+        );
+
+        result(invariant_holds_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_dynamic_fee(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        base_fee: Decimal,
+        volatility_multiplier: Decimal,
+        result: impl Fn(StdResult<SimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<SimulationResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SimulationDynamicFee {
+                offer_asset,
+                ask_asset_denom,
+                pool_identifier,
+                base_fee,
+                volatility_multiplier,
+            },
+        );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_standard_slippage(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        standard_notional: Uint128,
+        result: impl Fn(StdResult<Decimal256>),
+    ) -> &mut Self {
+        let standard_slippage_response: StdResult<Decimal256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::StandardSlippage {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                standard_notional,
+            },
+        );
+
+        result(standard_slippage_response);
+
+        self
+    }
+
+    pub(crate) fn query_cheapest_for_output(
+        &mut self,
+        ask_asset: Coin,
+        offer_denom: String,
+        candidate_pools: Vec<String>,
+        result: impl Fn(StdResult<CheapestResponse>),
+    ) -> &mut Self {
+        let cheapest_response: StdResult<CheapestResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::CheapestForOutput {
+                ask_asset,
+                offer_denom,
+                candidate_pools,
+            },
+        );
+
+        result(cheapest_response);
+
+        self
+    }
+
+    pub(crate) fn query_effective_bid_ask(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        size: Uint128,
+        result: impl Fn(StdResult<BidAskResponse>),
+    ) -> &mut Self {
+        let bid_ask_response: StdResult<BidAskResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::EffectiveBidAsk {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                size,
+            },
+        );
+
+        result(bid_ask_response);
+
+        self
+    }
+
+    pub(crate) fn query_optimal_trade_for_utility(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        slippage_penalty: Decimal256,
+        max_offer: Uint128,
+        result: impl Fn(StdResult<Uint128>),
+    ) -> &mut Self {
+        let optimal_trade_response: StdResult<Uint128> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::OptimalTradeForUtility {
+                pool_identifier,
+                offer_denom,
+                ask_denom,
+                slippage_penalty,
+                max_offer,
+            },
+        );
+
+        result(optimal_trade_response);
+
+        self
+    }
+
+    pub(crate) fn query_reserves_after_route(
+        &mut self,
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+        result: impl Fn(StdResult<Vec<(String, Vec<Coin>)>>),
+    ) -> &mut Self {
+        let reserves_response: StdResult<Vec<(String, Vec<Coin>)>> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ReservesAfterRoute {
+                    offer_amount,
+                    operations,
+                },
+            );
+
+        result(reserves_response);
+
+        self
+    }
+
+    pub(crate) fn query_breakeven_fee(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        expected_volatility: Decimal256,
+        result: impl Fn(StdResult<Decimal256>),
+    ) -> &mut Self {
+        let breakeven_fee_response: StdResult<Decimal256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::BreakevenFee {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                expected_volatility,
+            },
+        );
+
+        result(breakeven_fee_response);
+
+        self
+    }
+
+    pub(crate) fn query_routing_graph(
+        &mut self,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<RoutingGraphResponse>),
+    ) -> &mut Self {
+        let routing_graph_response: StdResult<RoutingGraphResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::RoutingGraph { limit },
+            );
+
+        result(routing_graph_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_external_fee(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        fee_denom: String,
+        fee_price: Decimal256,
+        result: impl Fn(StdResult<ExternalFeeSimulationResponse>),
+    ) -> &mut Self {
+        let simulation_response: StdResult<ExternalFeeSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationExternalFee {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                    fee_denom,
+                    fee_price,
+                },
+            );
+
+        result(simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_oracle_arb_to_price(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        oracle_price: Decimal256,
+        result: impl Fn(StdResult<RebalanceResponse>),
+    ) -> &mut Self {
+        let rebalance_response: StdResult<RebalanceResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::OracleArbToPrice {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                oracle_price,
+            },
+        );
+
+        result(rebalance_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_percentages(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<FeePercentagesResponse>),
+    ) -> &mut Self {
+        let fee_percentages_response: StdResult<FeePercentagesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::FeePercentages {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(fee_percentages_response);
+
+        self
+    }
+
+    pub(crate) fn query_canonical_pools(
+        &mut self,
+        quote_denom: String,
+        limit: Option<u32>,
+        result: impl Fn(StdResult<CanonicalPoolsResponse>),
+    ) -> &mut Self {
+        let canonical_pools_response: StdResult<CanonicalPoolsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::CanonicalPools { quote_denom, limit },
+            );
+
+        result(canonical_pools_response);
+
+        self
+    }
+
+    pub(crate) fn query_whale_exit_impact(
+        &mut self,
+        pool_identifier: String,
+        lp_amount: Uint128,
+        single_denom: Option<String>,
+        result: impl Fn(StdResult<WhaleExitResponse>),
+    ) -> &mut Self {
+        let whale_exit_response: StdResult<WhaleExitResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::WhaleExitImpact {
+                pool_identifier,
+                lp_amount,
+                single_denom,
+            },
+        );
+
+        result(whale_exit_response);
+
+        self
+    }
+
+    pub(crate) fn query_rebalance_to_balanced(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<Vec<Coin>>),
+    ) -> &mut Self {
+        let rebalance_response: StdResult<Vec<Coin>> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::RebalanceToBalanced { pool_identifier },
+        );
+
+        result(rebalance_response);
+
+        self
+    }
+
+    pub(crate) fn query_fee_capture_ratio(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<FeeCaptureResponse>),
+    ) -> &mut Self {
+        let fee_capture_response: StdResult<FeeCaptureResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::FeeCaptureRatio { pool_identifier },
+        );
+
+        result(fee_capture_response);
+
+        self
+    }
+
+    pub(crate) fn query_share_price_hwm(
+        &mut self,
+        pool_identifier: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<SharePriceResponse>),
+    ) -> &mut Self {
+        let share_price_response: StdResult<SharePriceResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::SharePriceHwm {
+                pool_identifier,
+                quote_denom,
+            },
+        );
+
+        result(share_price_response);
+
+        self
+    }
+
+    pub(crate) fn query_global_stats(
+        &mut self,
+        result: impl Fn(StdResult<GlobalStatsResponse>),
+    ) -> &mut Self {
+        let global_stats_response: StdResult<GlobalStatsResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::GlobalStats {},
+            );
+
+        result(global_stats_response);
+
+        self
+    }
+
+    pub(crate) fn query_reverse_simulation_partial(
+        &mut self,
+        pool_identifier: String,
+        ask_asset: Coin,
+        offer_denom: String,
+        result: impl Fn(StdResult<PartialReverseResponse>),
+    ) -> &mut Self {
+        let partial_reverse_response: StdResult<PartialReverseResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ReverseSimulationPartial {
+                    ask_asset,
+                    offer_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(partial_reverse_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_consistency(
+        &mut self,
+        base_denom: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<ConsistencyResponse>),
+    ) -> &mut Self {
+        let consistency_response: StdResult<ConsistencyResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PriceConsistency {
+                    base_denom,
+                    quote_denom,
+                },
+            );
+
+        result(consistency_response);
+
+        self
+    }
+
+    pub(crate) fn query_deposit_rebalance(
+        &mut self,
+        pool_identifier: String,
+        assets: Vec<Coin>,
+        result: impl Fn(StdResult<DepositRebalanceResponse>),
+    ) -> &mut Self {
+        let deposit_rebalance_response: StdResult<DepositRebalanceResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::DepositRebalance {
+                    pool_identifier,
+                    assets,
+                },
+            );
+
+        result(deposit_rebalance_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_safe(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_denom: String,
+        result: impl Fn(StdResult<SafeSimulationResponse>),
+    ) -> &mut Self {
+        let safe_simulation_response: StdResult<SafeSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationSafe {
+                    offer_asset,
+                    ask_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(safe_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_min_hops(
+        &mut self,
+        offer_denom: String,
+        ask_denom: String,
+        max_search: u32,
+        result: impl Fn(StdResult<Option<u32>>),
+    ) -> &mut Self {
+        let min_hops_response: StdResult<Option<u32>> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::MinHops {
+                offer_denom,
+                ask_denom,
+                max_search,
+            },
+        );
+
+        result(min_hops_response);
+
+        self
+    }
+
+    pub(crate) fn query_lp_for_target_value(
+        &mut self,
+        pool_identifier: String,
+        target_value: Uint128,
+        value_denom: String,
+        result: impl Fn(StdResult<LpForValueResponse>),
+    ) -> &mut Self {
+        let lp_for_value_response: StdResult<LpForValueResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::LpForTargetValue {
+                    pool_identifier,
+                    target_value,
+                    value_denom,
+                },
+            );
+
+        result(lp_for_value_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_bench(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_denom: String,
+        max_iterations: u32,
+        result: impl Fn(StdResult<BenchSimulationResponse>),
+    ) -> &mut Self {
+        let bench_simulation_response: StdResult<BenchSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationBench {
+                    offer_asset,
+                    ask_denom,
+                    pool_identifier,
+                    max_iterations,
+                },
+            );
+
+        result(bench_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_historical_reserves(
+        &mut self,
+        pool_identifier: String,
+        at_height: u64,
+        result: impl Fn(StdResult<HistoricalReservesResponse>),
+    ) -> &mut Self {
+        let historical_reserves_response: StdResult<HistoricalReservesResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::HistoricalReserves {
+                    pool_identifier,
+                    at_height,
+                },
+            );
+
+        result(historical_reserves_response);
+
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn query_blended_entry_price(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        existing_amount: Uint128,
+        existing_price: Decimal256,
+        new_offer: Uint128,
+        result: impl Fn(StdResult<Decimal256>),
+    ) -> &mut Self {
+        let blended_entry_price_response: StdResult<Decimal256> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::BlendedEntryPrice {
+                pool_identifier,
+                offer_denom,
+                ask_denom,
+                existing_amount,
+                existing_price,
+                new_offer,
+            },
+        );
+
+        result(blended_entry_price_response);
+
+        self
+    }
+
+    pub(crate) fn query_price_summary(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        size: Uint128,
+        result: impl Fn(StdResult<PriceSummaryResponse>),
+    ) -> &mut Self {
+        let price_summary_response: StdResult<PriceSummaryResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::PriceSummary {
+                    pool_identifier,
+                    base_denom,
+                    quote_denom,
+                    size,
+                },
+            );
+
+        result(price_summary_response);
+
+        self
+    }
+
+    pub(crate) fn query_asset_roles(
+        &mut self,
+        pool_identifier: String,
+        result: impl Fn(StdResult<AssetRolesResponse>),
+    ) -> &mut Self {
+        let asset_roles_response: StdResult<AssetRolesResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::AssetRoles { pool_identifier },
+        );
+
+        result(asset_roles_response);
+
+        self
+    }
+
+    pub(crate) fn query_fractional_simulation(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_denom: String,
+        fractions: Vec<Decimal256>,
+        result: impl Fn(StdResult<FractionalSimulationResponse>),
+    ) -> &mut Self {
+        let fractional_simulation_response: StdResult<FractionalSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::FractionalSimulation {
+                    offer_asset,
+                    ask_denom,
+                    pool_identifier,
+                    fractions,
+                },
+            );
+
+        result(fractional_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_equal_fee_split(
+        &mut self,
+        pool_a: String,
+        pool_b: String,
+        offer_denom: String,
+        ask_denom: String,
+        total_offer: Uint128,
+        result: impl Fn(StdResult<EqualFeeSplitResponse>),
+    ) -> &mut Self {
+        let equal_fee_split_response: StdResult<EqualFeeSplitResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::EqualFeeSplit {
+                    pool_a,
+                    pool_b,
+                    offer_denom,
+                    ask_denom,
+                    total_offer,
+                },
+            );
+
+        result(equal_fee_split_response);
+
+        self
+    }
+
+    pub(crate) fn query_reserve_ratio(
+        &mut self,
+        pool_identifier: String,
+        numerator_denom: String,
+        denominator_denom: String,
+        result: impl Fn(StdResult<ReserveRatioResponse>),
+    ) -> &mut Self {
+        let reserve_ratio_response: StdResult<ReserveRatioResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ReserveRatio {
+                    pool_identifier,
+                    numerator_denom,
+                    denominator_denom,
+                },
+            );
+
+        result(reserve_ratio_response);
+
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn query_simulation_range(
+        &mut self,
+        pool_identifier: String,
+        offer_denom: String,
+        ask_denom: String,
+        min_amount: Uint128,
+        max_amount: Uint128,
+        steps: u32,
+        result: impl Fn(StdResult<RangeSimulationResponse>),
+    ) -> &mut Self {
+        let range_simulation_response: StdResult<RangeSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationRange {
+                    offer_denom,
+                    ask_denom,
+                    pool_identifier,
+                    min_amount,
+                    max_amount,
+                    steps,
+                },
+            );
+
+        result(range_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_best_pool_for_pair(
+        &mut self,
+        offer_denom: String,
+        ask_denom: String,
+        standard_offer: Uint128,
+        result: impl Fn(StdResult<BestPoolResponse>),
+    ) -> &mut Self {
+        let best_pool_response: StdResult<BestPoolResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::BestPoolForPair {
+                offer_denom,
+                ask_denom,
+                standard_offer,
+            },
+        );
+
+        result(best_pool_response);
+
+        self
+    }
+
+    pub(crate) fn query_slippage_coefficient(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        result: impl Fn(StdResult<SlippageCoefficientResponse>),
+    ) -> &mut Self {
+        let slippage_coefficient_response: StdResult<SlippageCoefficientResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SlippageCoefficient {
+                    pool_identifier,
+                    base_denom,
+                    quote_denom,
+                },
+            );
+
+        result(slippage_coefficient_response);
+
+        self
+    }
+
+    pub(crate) fn query_config_migration_check(
+        &mut self,
+        proposed: Config,
+        result: impl Fn(StdResult<MigrationCheckResponse>),
+    ) -> &mut Self {
+        let migration_check_response: StdResult<MigrationCheckResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::ConfigMigrationCheck { proposed },
+            );
+
+        result(migration_check_response);
+
+        self
+    }
+
+    pub(crate) fn query_simulation_with_reinvest(
+        &mut self,
+        pool_identifier: String,
+        offer_asset: Coin,
+        ask_asset_denom: String,
+        result: impl Fn(StdResult<ReinvestSimulationResponse>),
+    ) -> &mut Self {
+        let reinvest_simulation_response: StdResult<ReinvestSimulationResponse> =
+            self.app.wrap().query_wasm_smart(
+                &self.pool_manager_addr,
+                &amm::pool_manager::QueryMsg::SimulationWithReinvest {
+                    offer_asset,
+                    ask_asset_denom,
+                    pool_identifier,
+                },
+            );
+
+        result(reinvest_simulation_response);
+
+        self
+    }
+
+    pub(crate) fn query_realized_volatility(
+        &mut self,
+        pool_identifier: String,
+        base_denom: String,
+        quote_denom: String,
+        window_blocks: u32,
+        result: impl Fn(StdResult<VolatilityResponse>),
+    ) -> &mut Self {
+        let volatility_response: StdResult<VolatilityResponse> = self.app.wrap().query_wasm_smart(
+            &self.pool_manager_addr,
+            &amm::pool_manager::QueryMsg::RealizedVolatility {
+                pool_identifier,
+                base_denom,
+                quote_denom,
+                window_blocks,
+            },
+        );
+
+        result(volatility_response);
+
+        self
+    }
 }
@@ -96,6 +96,14 @@ impl PoolFee {
         Ok(())
     }
 
+    /// Returns the sum of the shares of all the fees, including the extra fees.
+    pub fn total_fee_share(&self) -> Decimal256 {
+        [&self.protocol_fee, &self.swap_fee, &self.burn_fee]
+            .into_iter()
+            .chain(self.extra_fees.iter())
+            .fold(Decimal256::zero(), |acc, fee| acc + fee.to_decimal_256())
+    }
+
     /// Computes and applies all defined fees to a given amount.
     /// Returns the total amount of fees deducted.
     pub fn compute_and_apply_fees(&self, amount: Uint256) -> StdResult<Uint128> {
@@ -127,7 +135,7 @@ impl PoolFee {
 
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::{Decimal, StdError, Uint128, Uint256};
+    use cosmwasm_std::{Decimal, Decimal256, StdError, Uint128, Uint256};
     use test_case::test_case;
 
     use crate::fee::{Fee, PoolFee};
@@ -240,4 +248,24 @@ mod tests {
             Err(StdError::generic_err("Total fees cannot exceed 20%"))
         );
     }
+
+    #[test]
+    fn total_fee_share_includes_extra_fees() {
+        let pool_fee = PoolFee {
+            protocol_fee: Fee {
+                share: Decimal::permille(1),
+            },
+            swap_fee: Fee {
+                share: Decimal::permille(3),
+            },
+            burn_fee: Fee {
+                share: Decimal::zero(),
+            },
+            extra_fees: vec![Fee {
+                share: Decimal::permille(2),
+            }],
+        };
+
+        assert_eq!(pool_fee.total_fee_share(), Decimal256::permille(6));
+    }
 }
@@ -1,7 +1,9 @@
 use std::fmt;
 
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Decimal, Deps, StdError, StdResult, Uint128};
+use cosmwasm_std::{
+    Addr, Binary, Coin, Decimal, Decimal256, Deps, StdError, StdResult, Uint128, Uint256,
+};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 
 use crate::coin::is_factory_token;
@@ -113,6 +115,12 @@ pub struct Config {
     pub pool_creation_fee: Coin,
     //  Whether or not swaps, deposits, and withdrawals are enabled
     pub feature_toggle: FeatureToggle,
+    /// The total fee thresholds used to classify pools into fee tiers.
+    #[serde(default)]
+    pub fee_tier_thresholds: FeeTierThresholds,
+    /// The denoms pools are priced in by convention, from the most to the least preferred.
+    #[serde(default)]
+    pub quote_denom_priority: Vec<String>,
 }
 
 #[cw_serde]
@@ -212,6 +220,22 @@ pub enum ExecuteMsg {
         /// The new feature toggles of the contract, allowing fine-tuned
         /// control over which operations are allowed.
         feature_toggle: Option<FeatureToggle>,
+        /// The new total fee thresholds used to classify pools into fee tiers.
+        fee_tier_thresholds: Option<FeeTierThresholds>,
+        /// The new denoms pools are priced in by convention, from the most to the least preferred.
+        quote_denom_priority: Option<Vec<String>>,
+    },
+    /// Schedules a linear ramp of the amplification coefficient of a stableswap pool, starting
+    /// now from the current amp and reaching `target_amp` at `ramp_end_time`. The ramp must last
+    /// at least a day, and the target can be at most 10 times above or below the current amp.
+    /// Only the owner can ramp the amp.
+    RampAmp {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+        /// The amp to reach at the end of the ramp.
+        target_amp: u64,
+        /// The timestamp, in seconds, at which the ramp ends.
+        ramp_end_time: u64,
     },
 }
 
@@ -251,34 +275,1040 @@ pub enum QueryMsg {
         /// The pool identifier to swap in.
         pool_identifier: String,
     },
-    /// Simulates swap operations.
-    #[returns(SimulateSwapOperationsResponse)]
-    SimulateSwapOperations {
-        /// The amount to swap.
-        offer_amount: Uint128,
-        /// The operations to perform.
-        operations: Vec<SwapOperation>,
-    },
-    /// Simulates a reverse swap operations, i.e. given the ask asset, how much of the offer asset
-    /// is needed to perform the swap.
-    #[returns(ReverseSimulateSwapOperationsResponse)]
-    ReverseSimulateSwapOperations {
-        /// The amount to get after the swap.
-        ask_amount: Uint128,
-        /// The operations to perform.
-        operations: Vec<SwapOperation>,
-    },
-    /// Retrieves the pool information for the given pool identifier.
-    #[returns(PoolsResponse)]
-    Pools {
-        /// An optional parameter specifying the pool identifier to do the query for. If not
-        /// provided, it will return all pools based on the pagination parameters.
-        pool_identifier: Option<String>,
-        /// An optional parameter specifying what pool (identifier) to start searching after.
-        start_after: Option<String>,
-        /// The amount of pools to return. If unspecified, will default to a value specified by
-        /// the contract.
-        limit: Option<u32>,
+    /// Simulates swap operations.
+    #[returns(SimulateSwapOperationsResponse)]
+    SimulateSwapOperations {
+        /// The amount to swap.
+        offer_amount: Uint128,
+        /// The operations to perform.
+        operations: Vec<SwapOperation>,
+    },
+    /// Simulates a reverse swap operations, i.e. given the ask asset, how much of the offer asset
+    /// is needed to perform the swap.
+    #[returns(ReverseSimulateSwapOperationsResponse)]
+    ReverseSimulateSwapOperations {
+        /// The amount to get after the swap.
+        ask_amount: Uint128,
+        /// The operations to perform.
+        operations: Vec<SwapOperation>,
+    },
+    /// Retrieves the pool information for the given pool identifier.
+    #[returns(PoolsResponse)]
+    Pools {
+        /// An optional parameter specifying the pool identifier to do the query for. If not
+        /// provided, it will return all pools based on the pagination parameters.
+        pool_identifier: Option<String>,
+        /// An optional parameter specifying what pool (identifier) to start searching after.
+        start_after: Option<String>,
+        /// The amount of pools to return. If unspecified, will default to a value specified by
+        /// the contract.
+        limit: Option<u32>,
+    },
+    /// Computes the swap that moves the pool's spot price to the given target price.
+    #[returns(RebalanceResponse)]
+    RebalanceSwap {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset being priced.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+        /// The target spot price, expressed as the amount of quote asset per unit of base asset.
+        target_price: Decimal256,
+    },
+    /// Simulates a swap and reports its total cost, fees and spread combined, in a way that is
+    /// comparable across pool types.
+    #[returns(CanonicalSimulationResponse)]
+    CanonicalSimulation {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Computes the amount of each pool asset the given amount of LP tokens is redeemable for.
+    #[returns(LpValueResponse)]
+    LpValue {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The amount of LP tokens to value.
+        lp_amount: Uint128,
+    },
+    /// Simulates a swap like the `Simulation` query, but fails if the effective price of the swap
+    /// exceeds the given bound.
+    #[returns(SimulationResponse)]
+    SimulationSanity {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The maximum reasonable effective price, expressed as the amount of ask asset received
+        /// per unit of offer asset.
+        max_reasonable_price: Decimal256,
+    },
+    /// Counts the pools in the contract by pool type. Scans all the pools, meant for occasional
+    /// administrative use.
+    #[returns(PoolTypeStatsResponse)]
+    PoolTypeStats {},
+    /// Simulates providing liquidity with a single asset, which is partially swapped into the
+    /// other asset of the pool before being deposited.
+    #[returns(ZapInResponse)]
+    SimulateZapIn {
+        /// The pool identifier to provide liquidity to.
+        pool_identifier: String,
+        /// The single asset to provide.
+        offer_asset: Coin,
+    },
+    /// Simulates withdrawing liquidity and swapping all the withdrawn assets into a single denom.
+    #[returns(ZapOutResponse)]
+    SimulateZapOut {
+        /// The pool identifier to withdraw liquidity from.
+        pool_identifier: String,
+        /// The amount of LP tokens to withdraw.
+        lp_amount: Uint128,
+        /// The denom to receive all the withdrawn assets in.
+        target_denom: String,
+    },
+    /// Retrieves the status of the amp ramp of a stableswap pool.
+    #[returns(AmpRampResponse)]
+    AmpRampStatus {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+    },
+    /// Checks that the pool reserves are consistent with the LP token supply.
+    #[returns(IntegrityResponse)]
+    PoolIntegrity {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates a swap like the `Simulation` query, with the return amount reduced by a safety
+    /// margin covering the worst-case rounding of the swap computation.
+    #[returns(SimulationResponse)]
+    SimulationConservative {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Retrieves the direction of trade with the better fee-adjusted marginal rate in a two-asset
+    /// pool.
+    #[returns(CheaperDirectionResponse)]
+    CheaperDirection {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Retrieves the recipients of the fees collected by the contract.
+    #[returns(FeeRecipientsResponse)]
+    FeeRecipients {},
+    /// Simulates how changing the amp of a stableswap pool affects the value of its LP tokens.
+    #[returns(AmpImpactResponse)]
+    AmpChangeLpImpact {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+        /// The new amp to simulate.
+        new_amp: Uint256,
+    },
+    /// Encodes swap operations into a compact binary format, e.g. for caching routes.
+    #[returns(EncodedRouteResponse)]
+    EncodeRoute {
+        /// The swap operations to encode.
+        operations: Vec<SwapOperation>,
+    },
+    /// Decodes swap operations encoded with the `EncodeRoute` query.
+    #[returns(Vec<SwapOperation>)]
+    DecodeRoute {
+        /// The encoded swap operations.
+        encoded: Binary,
+    },
+    /// Splits the fees a pool charges on the given trade volume by destination.
+    #[returns(FeeSplitResponse)]
+    FeeSplit {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The trade volume to compute the fees for.
+        offer_amount: Uint128,
+    },
+    /// Computes how much more of each asset the pool can hold before its invariant computation
+    /// overflows.
+    #[returns(CapacityHeadroomResponse)]
+    CapacityHeadroom {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates a swap and returns the reserves the pool would hold after it's executed.
+    #[returns(SimulationWithReservesResponse)]
+    SimulationWithReservesOut {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Samples the output curve of a pool for the given pair, returning the cumulative return for
+    /// evenly spaced offer amounts up to `max_offer`.
+    #[returns(MarginalCurveResponse)]
+    MarginalOutputCurve {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to offer.
+        offer_denom: String,
+        /// The denom of the asset to ask for.
+        ask_denom: String,
+        /// The amount of points to sample, capped at 100.
+        steps: u32,
+        /// The largest offer amount to sample.
+        max_offer: Uint128,
+    },
+    /// Validates a pool creation specification, reporting all the validation failures at once.
+    #[returns(ValidatePoolResponse)]
+    ValidatePoolSpec {
+        /// The asset denoms for the pool.
+        asset_denoms: Vec<String>,
+        /// The decimals for the given asset denoms, provided in the same order as `asset_denoms`.
+        asset_decimals: Vec<u8>,
+        /// The type of pool to create.
+        pool_type: PoolType,
+        /// The fees for the pool.
+        pool_fees: PoolFee,
+    },
+    /// Retrieves the asset denoms of a pool in the order they are stored in. Deposits and
+    /// withdrawals follow this order.
+    #[returns(Vec<String>)]
+    CanonicalAssetOrder {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates a swap on a stableswap pool using the lesser of its current and target amp,
+    /// giving the worst-case output while the amp is ramping.
+    #[returns(SimulationResponse)]
+    SimulationMinAmp {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Computes the offer amount whose swap leaves the spot price of the offer asset, expressed in
+    /// the ask asset, at `target_price`.
+    #[returns(Uint128)]
+    OfferToReachPrice {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to offer.
+        offer_denom: String,
+        /// The denom of the asset to ask for.
+        ask_denom: String,
+        /// The target spot price, as ask asset per offer asset.
+        target_price: Decimal256,
+    },
+    /// Retrieves the shortest chain of pools connecting `denom` to `base_denom`, i.e. the pools
+    /// needed to price `denom` against `base_denom`.
+    #[returns(Vec<String>)]
+    PricingPath {
+        /// The denom to price.
+        denom: String,
+        /// The denom to price against.
+        base_denom: String,
+        /// The maximum amount of pools in the path, capped at 5.
+        max_hops: u32,
+    },
+    /// Computes the spread at which a round-trip swap through the pool breaks even, as simulated
+    /// with its fees and price impact.
+    #[returns(Decimal256)]
+    BreakevenSpread {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The asset offered in the first leg of the round trip.
+        offer_asset: Coin,
+        /// The denom of the asset asked for in the first leg of the round trip.
+        ask_denom: String,
+    },
+    /// Checks whether an exact-input swap would succeed if executed, reporting why it wouldn't.
+    #[returns(SwapCheckResponse)]
+    SwapWouldSucceed {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The minimum amount of the ask asset to receive.
+        min_receive: Uint128,
+        /// The maximum spread to accept.
+        max_spread: Decimal256,
+    },
+    /// Computes how far the spot price of a stableswap pair deviates from the 1:1 peg.
+    #[returns(PegDeviationResponse)]
+    PegDeviation {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to price.
+        base_denom: String,
+        /// The denom of the asset to price against.
+        quote_denom: String,
+    },
+    /// Simulates swapping each of the offer assets into `ask_denom` through its route, returning
+    /// the total proceeds.
+    #[returns(ManyToOneResponse)]
+    SimulationManyToOne {
+        /// The assets to swap.
+        offer_assets: Vec<Coin>,
+        /// The denom of the asset to swap into.
+        ask_denom: String,
+        /// The route of each offer asset, in the same order as `offer_assets`.
+        pool_routes: Vec<Vec<SwapOperation>>,
+    },
+    /// Simulates a swap and expresses its swap fee in the offer asset.
+    #[returns(SwapFeeInOfferResponse)]
+    SwapFeeInOffer {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Retrieves the reserves the pool would hold after withdrawing its accumulated protocol fees.
+    #[returns(Vec<Coin>)]
+    PostFeeWithdrawalReserves {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Computes the largest offer amount whose price impact stays within `max_impact_bps`.
+    #[returns(Uint128)]
+    SafeTradeSize {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to offer.
+        offer_denom: String,
+        /// The denom of the asset to ask for.
+        ask_denom: String,
+        /// The maximum price impact, in basis points.
+        max_impact_bps: u16,
+    },
+    /// Retrieves the pools sharing at least one asset denom with the given pool.
+    #[returns(PoolsResponse)]
+    AdjacentPools {
+        /// The pool identifier to get the adjacent pools of.
+        pool_identifier: String,
+        /// The amount of pools to return. If unspecified, will default to a value specified by
+        /// the contract.
+        limit: Option<u32>,
+    },
+    /// Simulates swapping `probe_amount` between every ordered pair of distinct assets in the pool.
+    #[returns(PriceMatrixResponse)]
+    PriceMatrix {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The amount of the offer asset to simulate each swap with.
+        probe_amount: Uint128,
+    },
+    /// Computes the amp at which a stableswap pool approximates a constant product pool at its
+    /// current reserves.
+    #[returns(Uint256)]
+    ConstantProductEquivalentAmp {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates sandwiching a swap with `attacker_capital`, returning the attacker's profit.
+    #[returns(SandwichRiskResponse)]
+    SandwichRisk {
+        /// The offer asset of the victim's swap.
+        offer_asset: Coin,
+        /// The ask asset denom of the victim's swap.
+        ask_asset_denom: String,
+        /// The pool identifier the victim swaps in.
+        pool_identifier: String,
+        /// The amount of the offer asset the attacker front-runs the swap with.
+        attacker_capital: Uint128,
+    },
+    /// Retrieves the stableswap pools whose amp is currently ramping.
+    #[returns(PoolsResponse)]
+    RampingPools {
+        /// The amount of pools to return. If unspecified, will default to a value specified by
+        /// the contract.
+        limit: Option<u32>,
+    },
+    /// Computes how concentrated the liquidity of a pool is across its assets.
+    #[returns(ConcentrationResponse)]
+    LiquidityConcentration {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates a swap against the reserves the pool would hold after the given pending deposits
+    /// and withdrawals.
+    #[returns(SimulationResponse)]
+    SimulationWithPending {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The assets pending to be deposited into the pool.
+        pending_deposits: Vec<Coin>,
+        /// The assets pending to be withdrawn from the pool.
+        pending_withdrawals: Vec<Coin>,
+    },
+    /// Computes the maximum amount of the ask asset obtainable from the pool, however large the
+    /// offer.
+    #[returns(Uint128)]
+    MaxPossibleOutput {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to offer.
+        offer_denom: String,
+        /// The denom of the asset to ask for.
+        ask_denom: String,
+    },
+    /// Compares the output of swapping through a direct pool against a multi-hop route.
+    #[returns(DirectVsRouteResponse)]
+    DirectVsRoute {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The identifier of the pool to swap in directly.
+        direct_pool: String,
+        /// The route to compare the direct swap against.
+        route: Vec<SwapOperation>,
+    },
+    /// Simulates a swap and compares its execution price against a reference price.
+    #[returns(ReferenceSimulationResponse)]
+    SimulationVsReference {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The reference price, as ask asset per offer asset.
+        reference_price: Decimal256,
+    },
+    /// Simulates selling the offer asset in small increments until its marginal price drops below
+    /// the given stop price, i.e. a price-limit order.
+    #[returns(CascadeResponse)]
+    SimulateUntilPrice {
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The offer asset denom.
+        offer_asset_denom: String,
+        /// The ask asset denom.
+        ask_asset_denom: String,
+        /// The total amount of the offer asset to sell at most.
+        total_offer: Uint128,
+        /// The price, as ask asset per offer asset, below which the selling stops.
+        stop_price: Decimal256,
+    },
+    /// Samples the effective fee charged on imbalanced deposits into a stableswap pool.
+    #[returns(ImbalanceFeeResponse)]
+    ImbalanceFeeSchedule {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+        /// The number of points to sample, capped at 100.
+        sample_points: u32,
+    },
+    /// Computes the amount of LP tokens to burn to get exactly the desired amount of an asset,
+    /// through a proportional withdrawal on constant product pools, and through a single-sided
+    /// withdrawal on stableswap pools, i.e. the inverse of the `SimulateZapOut` query.
+    #[returns(Uint128)]
+    LpToWithdraw {
+        /// The identifier of the pool to withdraw from.
+        pool_identifier: String,
+        /// The asset to get.
+        desired: Coin,
+    },
+    /// Computes the swap that leaves the holder of two balances with equal value of both.
+    #[returns(RebalanceResponse)]
+    RebalanceToEqual {
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The balance held of the first asset.
+        balance_a: Coin,
+        /// The balance held of the second asset.
+        balance_b: Coin,
+    },
+    /// Estimates the fee revenue of a pool over a distribution of trade sizes.
+    #[returns(RevenueDistributionResponse)]
+    RevenueOverDistribution {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The sizes of the trades to simulate, capped at 100 trades.
+        trade_sizes: Vec<Uint128>,
+    },
+    /// Computes the no-arbitrage price band the pool would have under the given total fee.
+    #[returns(BandWidthResponse)]
+    BandWidthForFees {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The proposed total fee, i.e. the sum of all the pool fees.
+        new_total_fee: Decimal,
+    },
+    /// Computes the spot price of an asset in the pool as an exact fraction.
+    #[returns(RationalPriceResponse)]
+    MidPriceRational {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset being priced.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+    },
+    /// Simulates a swap and reports how much it contributes to the LP yield relative to the TVL.
+    #[returns(AprContributionResponse)]
+    SwapAprContribution {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Computes the balanced reserves a stableswap pool needs to hold for its invariant to be the
+    /// given target.
+    #[returns(Vec<Coin>)]
+    ReservesForInvariant {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+        /// The target invariant D.
+        target_d: Uint256,
+    },
+    /// Checks whether `offer_denom` can be swapped into `ask_denom` through at most `max_hops`
+    /// pools.
+    #[returns(bool)]
+    Reachable {
+        /// The denom to swap from.
+        offer_denom: String,
+        /// The denom to swap into.
+        ask_denom: String,
+        /// The maximum amount of pools to swap through, capped at 5.
+        max_hops: u32,
+    },
+    /// Computes how much the spot price of `base_denom` moves per unit of its reserve.
+    #[returns(SensitivityResponse)]
+    PriceSensitivity {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset being priced.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+    },
+    /// Classifies a pool into a fee tier based on its total fee.
+    #[returns(FeeTierResponse)]
+    FeeTier {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates swaps of the same value in both directions of a pair, reporting both spreads.
+    #[returns(BalancedSlippageResponse)]
+    BalancedSlippage {
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The denom offered in the forward swap.
+        offer_denom: String,
+        /// The denom offered in the reverse swap.
+        ask_denom: String,
+        /// The amount of `offer_denom` to swap forward.
+        amount: Uint128,
+    },
+    /// Computes the amp below which the reverse simulations of a stableswap pool reliably have a
+    /// non-negative spread.
+    #[returns(Uint256)]
+    MinAmpForPositiveSpread {
+        /// The identifier of the stableswap pool.
+        pool_identifier: String,
+    },
+    /// Simulates a swap for a trader getting part of the swap fee rebated.
+    #[returns(SimulationResponse)]
+    SimulationWithRebate {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The part of the swap fee rebated to the trader, in basis points of the swapped
+        /// amount like the swap fee itself, so at most the swap fee's basis points.
+        rebate_bps: u16,
+    },
+    /// Computes a second-order approximation of the swap output as a function of the input,
+    /// around the given input amount.
+    #[returns(CurveCoefficientsResponse)]
+    PriceCurveCoefficients {
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The offer asset denom.
+        offer_asset_denom: String,
+        /// The ask asset denom.
+        ask_asset_denom: String,
+        /// The offer amount to approximate the curve around.
+        around: Uint128,
+    },
+    /// Retrieves the pools whose total fee exceeds the given threshold.
+    #[returns(PoolsResponse)]
+    PoolsAboveFee {
+        /// The total fee the pools must exceed.
+        min_total_fee: Decimal,
+        /// The amount of pools to return. If unspecified, will default to a value specified by
+        /// the contract.
+        limit: Option<u32>,
+    },
+    /// Simulates a swap on a stableswap pool at both its current and target amp, giving the range
+    /// of outputs the trader may get while the amp is ramping.
+    #[returns(AmpRangeSimulationResponse)]
+    SimulationAmpRange {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Gets each reserve of a stableswap pool as a fraction of its invariant D, normalized to the
+    /// same precision. In a balanced pool each share is `1/n`.
+    #[returns(Vec<(String, Decimal256)>)]
+    ReserveSharesOfD {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Gets the value of an LP token of the pool in `quote_denom`, i.e. the pool's total value
+    /// locked divided by the LP token supply.
+    #[returns(LpPriceResponse)]
+    LpPrice {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom to value the LP token in. Must be one of the pool's assets.
+        quote_denom: String,
+    },
+    /// Checks that the invariant computed from the pool reserves is at least the one implied by the
+    /// LP token supply, allowing a shortfall of up to `tolerance_bps`.
+    #[returns(bool)]
+    InvariantHolds {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The shortfall to tolerate, in basis points of the invariant implied by the LP supply.
+        tolerance_bps: u16,
+    },
+    /// Simulates a swap with the pool's swap fee replaced by `base_fee` scaled by the pool's recent
+    /// realized volatility times `volatility_multiplier`, modeling a dynamic fee that grows with
+    /// volatility.
+    #[returns(SimulationResponse)]
+    SimulationDynamicFee {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The swap fee when the pool price doesn't move.
+        base_fee: Decimal,
+        /// The factor to scale the pool's recent realized volatility by, before scaling the base fee
+        /// by one plus it.
+        volatility_multiplier: Decimal,
+    },
+    /// Gets the slippage of selling `base_denom` worth `standard_notional` of `quote_denom` at the
+    /// spot price, so pools of different depths can be compared.
+    #[returns(Decimal256)]
+    StandardSlippage {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom to sell.
+        base_denom: String,
+        /// The denom the notional is expressed in, and to buy.
+        quote_denom: String,
+        /// The value of the trade, in `quote_denom`.
+        standard_notional: Uint128,
+    },
+    /// Gets the candidate pool requiring the least `offer_denom` to return `ask_asset`.
+    #[returns(CheapestResponse)]
+    CheapestForOutput {
+        /// The ask asset to get.
+        ask_asset: Coin,
+        /// The offer asset denom to swap.
+        offer_denom: String,
+        /// The identifiers of the pools to compare.
+        candidate_pools: Vec<String>,
+    },
+    /// Gets the effective prices, fees included, of selling and buying `size` of `base_denom`
+    /// against `quote_denom`, like the bid and ask of an order book.
+    #[returns(BidAskResponse)]
+    EffectiveBidAsk {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom being priced.
+        base_denom: String,
+        /// The denom the prices are expressed in.
+        quote_denom: String,
+        /// The amount of `base_denom` to sell and buy.
+        size: Uint128,
+    },
+    /// Gets the offer amount, up to `max_offer`, maximizing the return amount minus
+    /// `slippage_penalty` times the spread of the swap.
+    #[returns(Uint128)]
+    OptimalTradeForUtility {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The offer asset denom to swap.
+        offer_denom: String,
+        /// The ask asset denom to get.
+        ask_denom: String,
+        /// The weight of the spread in the utility.
+        slippage_penalty: Decimal256,
+        /// The largest offer amount to consider.
+        max_offer: Uint128,
+    },
+    /// Gets the reserves of each pool touched by the swap operations, after all of them execute.
+    #[returns(Vec<(String, Vec<Coin>)>)]
+    ReservesAfterRoute {
+        /// The amount to swap through the route.
+        offer_amount: Uint128,
+        /// The swap operations to perform.
+        operations: Vec<SwapOperation>,
+    },
+    /// Gets the swap fee at which the fees earned on the arbitrage volume of a price move of
+    /// `expected_volatility` offset the impermanent loss of the move.
+    #[returns(Decimal256)]
+    BreakevenFee {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom whose price moves.
+        base_denom: String,
+        /// The denom prices are expressed in.
+        quote_denom: String,
+        /// The relative move of the base asset's price, e.g. 0.1 for 10%.
+        expected_volatility: Decimal256,
+    },
+    /// Gets the pools as the edges of a routing graph, one per pair of assets of each pool.
+    #[returns(RoutingGraphResponse)]
+    RoutingGraph {
+        /// The amount of pools to scan. If unspecified, will default to a value specified by the
+        /// contract.
+        limit: Option<u32>,
+    },
+    /// Simulates a swap charging no fees in kind, with the fees it would otherwise charge
+    /// expressed in `fee_denom` instead.
+    #[returns(ExternalFeeSimulationResponse)]
+    SimulationExternalFee {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The denom the fees are paid in.
+        fee_denom: String,
+        /// The amount of `fee_denom` a unit of the ask asset is worth.
+        fee_price: Decimal256,
+    },
+    /// Computes the arbitrage swap aligning the pool's spot price with an oracle price, or a
+    /// zero-amount swap if the difference is within the pool's fees.
+    #[returns(RebalanceResponse)]
+    OracleArbToPrice {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset being priced.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+        /// The oracle price, expressed as the amount of quote asset per unit of base asset.
+        oracle_price: Decimal256,
+    },
+    /// Simulates a swap and breaks down its cost, as percentages of the fee-free output at the
+    /// spot price, into each fee and the spread.
+    #[returns(FeePercentagesResponse)]
+    FeePercentages {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Gets the deepest pool, by total value locked in `quote_denom`, of each distinct pair of
+    /// denoms.
+    #[returns(CanonicalPoolsResponse)]
+    CanonicalPools {
+        /// The denom to value the pools in.
+        quote_denom: String,
+        /// The amount of pools to scan. If unspecified, will default to a value specified by the
+        /// contract.
+        limit: Option<u32>,
+    },
+    /// Simulates withdrawing `lp_amount` LP tokens, either proportionally or into `single_denom`,
+    /// and reports its effect on the pool's spot price and on the value of the remaining LP tokens.
+    #[returns(WhaleExitResponse)]
+    WhaleExitImpact {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The amount of LP tokens withdrawn.
+        lp_amount: Uint128,
+        /// The denom to withdraw everything into. If unspecified, the withdrawal is proportional.
+        single_denom: Option<String>,
+    },
+    /// Computes the sequence of swaps bringing a stableswap pool to equal reserves, normalized by
+    /// their decimals.
+    #[returns(Vec<Coin>)]
+    RebalanceToBalanced {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Gets the ratio of the fees a pool has charged on its swaps to the fees implied by its
+    /// cumulative volume and its current fee rate.
+    #[returns(FeeCaptureResponse)]
+    FeeCaptureRatio {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Gets the current value of an LP token of the pool in `quote_denom`, along with the highest
+    /// value it has reached.
+    #[returns(SharePriceResponse)]
+    SharePriceHwm {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom to value the LP token in. Must be one of the pool's assets.
+        quote_denom: String,
+    },
+    /// Gets the number of pools and their total value locked in the first denom of the config's
+    /// `quote_denom_priority`. Scans all the pools, so it's meant for occasional use, e.g. by
+    /// dashboards.
+    #[returns(GlobalStatsResponse)]
+    GlobalStats {},
+    /// Simulates a reverse swap like `ReverseSimulation`, falling back to the largest amount of
+    /// the ask asset the pool can deliver when the requested amount is out of reach.
+    #[returns(PartialReverseResponse)]
+    ReverseSimulationPartial {
+        /// The ask asset to get after the swap.
+        ask_asset: Coin,
+        /// The offer asset denom to input.
+        offer_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Gets how far apart the spot prices of `base_denom` in `quote_denom` are across the pools
+    /// holding both assets.
+    #[returns(ConsistencyResponse)]
+    PriceConsistency {
+        /// The denom of the asset to price.
+        base_denom: String,
+        /// The denom to price the base asset in.
+        quote_denom: String,
+    },
+    /// Simulates a deposit into a stableswap pool, showing the balanced deposit it's equivalent to
+    /// and the fee its imbalance is charged.
+    #[returns(DepositRebalanceResponse)]
+    DepositRebalance {
+        /// The pool identifier to deposit into.
+        pool_identifier: String,
+        /// The assets to deposit.
+        assets: Vec<Coin>,
+    },
+    /// Simulates a swap like `Simulation`, flagging the swaps that take a large share of the ask
+    /// asset reserve.
+    #[returns(SafeSimulationResponse)]
+    SimulationSafe {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Gets the smallest amount of pools to swap through to get from `offer_denom` to
+    /// `ask_denom`, if they're connected within `max_search` pools.
+    #[returns(Option<u32>)]
+    MinHops {
+        /// The denom to swap from.
+        offer_denom: String,
+        /// The denom to swap into.
+        ask_denom: String,
+        /// The maximum amount of pools to search through, capped at 5.
+        max_search: u32,
+    },
+    /// Gets the balanced deposit worth `target_value` of `value_denom` and the LP tokens it would
+    /// mint.
+    #[returns(LpForValueResponse)]
+    LpForTargetValue {
+        /// The pool identifier to deposit into.
+        pool_identifier: String,
+        /// The value of the deposit, in the smallest unit of `value_denom`.
+        target_value: Uint128,
+        /// The denom to value the deposit in. Must be one of the pool's assets.
+        value_denom: String,
+    },
+    /// Simulates a swap with the stableswap solver capped at `max_iterations` iterations,
+    /// reporting how many it used and whether it converged. Meant for benchmarking the solver.
+    #[returns(BenchSimulationResponse)]
+    SimulationBench {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The maximum amount of iterations of each of the solver's approximations, capped at
+        /// 256.
+        max_iterations: u32,
+    },
+    /// Gets the snapshot of the pool reserves nearest to, and not after, `at_height`.
+    #[returns(HistoricalReservesResponse)]
+    HistoricalReserves {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The block height to get the reserves at.
+        at_height: u64,
+    },
+    /// Gets the average price paid for a position of `ask_denom` after adding the return of a swap
+    /// of `new_offer` to it.
+    #[returns(Decimal256)]
+    BlendedEntryPrice {
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The denom paid for the position.
+        offer_denom: String,
+        /// The denom of the position.
+        ask_denom: String,
+        /// The amount of the ask asset already in the position, in its smallest unit.
+        existing_amount: Uint128,
+        /// The average price paid for the existing position, in offer asset per ask asset.
+        existing_price: Decimal256,
+        /// The amount of the offer asset to swap into the position.
+        new_offer: Uint128,
+    },
+    /// Gets the spot price of `base_denom` in `quote_denom`, the execution price of selling `size`
+    /// of it and the price impact between both.
+    #[returns(PriceSummaryResponse)]
+    PriceSummary {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the asset to price.
+        base_denom: String,
+        /// The denom to price the base asset in.
+        quote_denom: String,
+        /// The amount of the base asset to sell for the execution price.
+        size: Uint128,
+    },
+    /// Gets which of the pool's assets is the base and which is the quote by convention, i.e. how
+    /// its price is displayed.
+    #[returns(AssetRolesResponse)]
+    AssetRoles {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+    },
+    /// Simulates executing fractions of a swap one after the other, reporting the cumulative
+    /// return after each of them.
+    #[returns(FractionalSimulationResponse)]
+    FractionalSimulation {
+        /// The offer asset to swap, in total.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The fractions of the offer asset to swap, in execution order. Must sum to at most one.
+        fractions: Vec<Decimal256>,
+    },
+    /// Splits an offer between two pools of the same pair so that both are paid the same amount
+    /// of fees.
+    #[returns(EqualFeeSplitResponse)]
+    EqualFeeSplit {
+        /// The identifier of the first pool.
+        pool_a: String,
+        /// The identifier of the second pool.
+        pool_b: String,
+        /// The denom to swap from.
+        offer_denom: String,
+        /// The denom to swap into.
+        ask_denom: String,
+        /// The total amount of the offer asset to split.
+        total_offer: Uint128,
+    },
+    /// Gets the ratio between two reserves of the pool, regardless of its curve.
+    #[returns(ReserveRatioResponse)]
+    ReserveRatio {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom of the reserve in the numerator.
+        numerator_denom: String,
+        /// The denom of the reserve in the denominator.
+        denominator_denom: String,
+    },
+    /// Simulates swaps of evenly spaced amounts between `min_amount` and `max_amount`, e.g. to
+    /// render a slippage curve.
+    #[returns(RangeSimulationResponse)]
+    SimulationRange {
+        /// The denom to swap from.
+        offer_denom: String,
+        /// The denom to swap into.
+        ask_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+        /// The smallest amount to simulate.
+        min_amount: Uint128,
+        /// The largest amount to simulate.
+        max_amount: Uint128,
+        /// The amount of points to simulate, capped at 100.
+        steps: u32,
+    },
+    /// Finds the pool returning the most for swapping `standard_offer` of `offer_denom` into
+    /// `ask_denom`, among all the pools holding both.
+    #[returns(BestPoolResponse)]
+    BestPoolForPair {
+        /// The denom to swap from.
+        offer_denom: String,
+        /// The denom to swap into.
+        ask_denom: String,
+        /// The amount of `offer_denom` to simulate through each pool.
+        standard_offer: Uint128,
+    },
+    /// Gets the relative change of the spot price of `base_denom`, in `quote_denom`, per whole unit
+    /// of `base_denom` sold into the pool, characterizing its local slippage.
+    #[returns(SlippageCoefficientResponse)]
+    SlippageCoefficient {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom being sold into the pool.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+    },
+    /// Compares a proposed config against the current one, flagging the changes that could
+    /// disrupt the existing pools.
+    #[returns(MigrationCheckResponse)]
+    ConfigMigrationCheck {
+        /// The config proposed to replace the current one.
+        proposed: Config,
+    },
+    /// Simulates a swap as if all of its fees were reinvested into the pool, and gets how much the
+    /// value of an LP token grows from them.
+    #[returns(ReinvestSimulationResponse)]
+    SimulationWithReinvest {
+        /// The offer asset to swap.
+        offer_asset: Coin,
+        /// The ask asset denom to get.
+        ask_asset_denom: String,
+        /// The pool identifier to swap in.
+        pool_identifier: String,
+    },
+    /// Gets the realized volatility of the price of `base_denom` in `quote_denom`, from the
+    /// snapshots of the pool reserves taken within the last `window_blocks` blocks.
+    #[returns(VolatilityResponse)]
+    RealizedVolatility {
+        /// The pool identifier to do the query for.
+        pool_identifier: String,
+        /// The denom being priced.
+        base_denom: String,
+        /// The denom the price is expressed in.
+        quote_denom: String,
+        /// The amount of blocks before the current one the observations are taken from.
+        window_blocks: u32,
     },
 }
 
@@ -358,6 +1388,37 @@ pub struct FeatureToggle {
     pub swaps_enabled: bool,
 }
 
+/// The total fee thresholds used to classify pools into fee tiers. Pools with a total fee up to
+/// `stable_max` are in the [FeeTier::Stable] tier, up to `standard_max` in the [FeeTier::Standard]
+/// tier, and in the [FeeTier::Exotic] tier above it.
+#[cw_serde]
+pub struct FeeTierThresholds {
+    /// The highest total fee of the stable tier.
+    pub stable_max: Decimal,
+    /// The highest total fee of the standard tier.
+    pub standard_max: Decimal,
+}
+
+impl Default for FeeTierThresholds {
+    fn default() -> Self {
+        Self {
+            stable_max: Decimal::permille(1),
+            standard_max: Decimal::permille(5),
+        }
+    }
+}
+
+/// The fee tier of a pool, derived from its total fee.
+#[cw_serde]
+pub enum FeeTier {
+    /// Low fee pools, usually pairing pegged assets.
+    Stable,
+    /// Pools with the most common fees.
+    Standard,
+    /// High fee pools, usually pairing volatile or illiquid assets.
+    Exotic,
+}
+
 /// The response for the `SimulateSwapOperations` query.
 #[cw_serde]
 pub struct SimulateSwapOperationsResponse {
@@ -372,6 +1433,603 @@ pub struct ReverseSimulateSwapOperationsResponse {
     pub amount: Uint128,
 }
 
+/// The response for the `RebalanceSwap` query.
+#[cw_serde]
+pub struct RebalanceResponse {
+    /// The denom of the asset to offer.
+    pub offer_denom: String,
+    /// The amount of the asset to offer. Zero if the pool is already at the target price.
+    pub offer_amount: Uint128,
+}
+
+/// The response for the `CanonicalSimulation` query.
+#[cw_serde]
+pub struct CanonicalSimulationResponse {
+    /// The return amount of the ask asset given the offer amount, after fees.
+    pub return_amount: Uint128,
+    /// The shortfall of the return amount against the fee-free spot price value of the offer
+    /// amount, in basis points of the latter. Includes both fees and spread.
+    pub total_cost_bps: Decimal256,
+}
+
+/// The response for the `LpValue` query.
+#[cw_serde]
+pub struct LpValueResponse {
+    /// The proportional share of the pool reserves the LP tokens are redeemable for.
+    pub assets: Vec<Coin>,
+}
+
+/// The response for the `PoolTypeStats` query.
+#[cw_serde]
+pub struct PoolTypeStatsResponse {
+    /// The amount of constant product pools.
+    pub constant_product: u32,
+    /// The amount of stableswap pools.
+    pub stableswap: u32,
+}
+
+/// The response for the `SimulateZapIn` query.
+#[cw_serde]
+pub struct ZapInResponse {
+    /// The amount of LP tokens that would be minted.
+    pub lp_amount: Uint128,
+    /// The amount of the other pool asset returned by the swap.
+    pub swap_return: Uint128,
+}
+
+/// The response for the `SimulateZapOut` query.
+#[cw_serde]
+pub struct ZapOutResponse {
+    /// The total amount of the target denom received.
+    pub return_amount: Uint128,
+}
+
+/// The response for the `AmpRampStatus` query.
+#[cw_serde]
+pub struct AmpRampResponse {
+    /// The amp at the current block time.
+    pub current_amp: Uint256,
+    /// The amp at the end of the ramp.
+    pub target_amp: Uint256,
+    /// The fraction of the ramp window that has elapsed. One if the pool isn't ramping.
+    pub progress: Decimal256,
+}
+
+/// The response for the `PoolIntegrity` query.
+#[cw_serde]
+pub struct IntegrityResponse {
+    /// Whether the invariant computed from the reserves covers the one implied by the LP supply.
+    pub consistent: bool,
+    /// The minimum invariant implied by the LP supply. For constant product pools it's the total
+    /// share raised to the amount of assets, for stableswap pools it's the total share itself.
+    pub expected_k: Uint256,
+    /// The invariant computed from the reserves, i.e. the product of the reserves for constant
+    /// product pools and D for stableswap pools.
+    pub actual_k: Uint256,
+}
+
+/// The response for the `CheaperDirection` query.
+#[cw_serde]
+pub struct CheaperDirectionResponse {
+    /// The denom to offer.
+    pub offer_denom: String,
+    /// The denom to ask for.
+    pub ask_denom: String,
+}
+
+/// The response for the `FeeRecipients` query.
+#[cw_serde]
+pub struct FeeRecipientsResponse {
+    /// The address the protocol fees are sent to.
+    pub protocol_fee_collector: Addr,
+    /// The address the burn fees are sent to. `None` as burn fees are burned via the bank module.
+    pub burn_address: Option<Addr>,
+}
+
+/// The response for the `AmpChangeLpImpact` query.
+#[cw_serde]
+pub struct AmpImpactResponse {
+    /// The value of one LP token with the current amp, for each pool asset as the amount received
+    /// when withdrawing and swapping everything into that asset.
+    pub lp_value_before: Vec<Coin>,
+    /// The value of one LP token with the new amp, in the same terms as `lp_value_before`.
+    pub lp_value_after: Vec<Coin>,
+}
+
+/// The response for the `EncodeRoute` query.
+#[cw_serde]
+pub struct EncodedRouteResponse {
+    /// The encoded swap operations.
+    pub encoded: Binary,
+}
+
+/// The response for the `FeeSplit` query.
+#[cw_serde]
+pub struct FeeSplitResponse {
+    /// The fee that stays in the pool for the liquidity providers, i.e. the swap fee.
+    pub lp_fee: Uint128,
+    /// The fee sent to the fee collector.
+    pub protocol_fee: Uint128,
+    /// The fee that is burned.
+    pub burn_fee: Uint128,
+}
+
+/// The response for the `CapacityHeadroom` query.
+#[cw_serde]
+pub struct CapacityHeadroomResponse {
+    /// The maximum amount of each asset that can be added to the pool, the other reserves staying
+    /// the same.
+    pub max_additional_per_asset: Vec<Coin>,
+}
+
+/// The response for the `SimulationWithReservesOut` query.
+#[cw_serde]
+pub struct SimulationWithReservesResponse {
+    /// The simulation of the swap.
+    pub simulation: SimulationResponse,
+    /// The pool reserves after the swap, including the fees retained by the pool.
+    pub reserves_after: Vec<Coin>,
+}
+
+/// The response for the `MarginalOutputCurve` query.
+#[cw_serde]
+pub struct MarginalCurveResponse {
+    /// The sampled `(offer_amount, return_amount)` points, in increasing offer amount order.
+    pub points: Vec<(Uint128, Uint128)>,
+}
+
+/// The response for the `ValidatePoolSpec` query.
+#[cw_serde]
+pub struct ValidatePoolResponse {
+    /// Whether the pool can be created with the given specification.
+    pub valid: bool,
+    /// The validation failures, empty if the specification is valid.
+    pub errors: Vec<String>,
+}
+
+/// The response for the `SwapWouldSucceed` query.
+#[cw_serde]
+pub struct SwapCheckResponse {
+    /// Whether the swap would succeed.
+    pub would_succeed: bool,
+    /// The reason the swap would fail, if it would.
+    pub reason: Option<String>,
+}
+
+/// The response for the `PegDeviation` query.
+#[cw_serde]
+pub struct PegDeviationResponse {
+    /// The deviation of the spot price from 1.0 in basis points, positive when the base asset is
+    /// worth more than the quote asset.
+    pub deviation_bps: i64,
+}
+
+/// The response for the `SimulationManyToOne` query.
+#[cw_serde]
+pub struct ManyToOneResponse {
+    /// The total amount of the ask asset received.
+    pub total_return: Uint128,
+    /// The amount of the ask asset received for each offer asset, in the same order.
+    pub per_asset: Vec<Uint128>,
+}
+
+/// The response for the `SwapFeeInOffer` query.
+#[cw_serde]
+pub struct SwapFeeInOfferResponse {
+    /// The swap fee amount, in the ask asset.
+    pub swap_fee_amount: Uint128,
+    /// The swap fee amount converted to the offer asset at the pool's spot price.
+    pub swap_fee_in_offer: Uint128,
+}
+
+/// The response for the `PriceMatrix` query.
+#[cw_serde]
+pub struct PriceMatrixResponse {
+    /// The `(offer_denom, ask_denom, return_amount)` entries, one per ordered pair of assets.
+    pub entries: Vec<(String, String, Uint128)>,
+}
+
+/// The response for the `SandwichRisk` query.
+#[cw_serde]
+pub struct SandwichRiskResponse {
+    /// The profit of the attacker, in the offer asset.
+    pub max_extractable: Uint128,
+}
+
+/// The response for the `LiquidityConcentration` query.
+#[cw_serde]
+pub struct ConcentrationResponse {
+    /// The concentration index, 0 when the pool is perfectly balanced and approaching 1 as the
+    /// liquidity concentrates in a single asset.
+    pub index: Decimal256,
+}
+
+/// Whether a direct swap or a multi-hop route gives the better output.
+#[cw_serde]
+pub enum DirectOrRoute {
+    Direct,
+    Route,
+}
+
+/// The response for the `DirectVsRoute` query.
+#[cw_serde]
+pub struct DirectVsRouteResponse {
+    /// The output of the direct swap.
+    pub direct_output: Uint128,
+    /// The output of the route.
+    pub route_output: Uint128,
+    /// The cheaper of the two, the direct swap if both give the same output.
+    pub cheaper: DirectOrRoute,
+}
+
+/// The response for the `SimulationVsReference` query.
+#[cw_serde]
+pub struct ReferenceSimulationResponse {
+    /// The amount of the ask asset returned by the swap.
+    pub return_amount: Uint128,
+    /// How much more expensive the swap is than the reference price in basis points, negative if
+    /// it's cheaper.
+    pub premium_or_discount_bps: i64,
+}
+
+/// The response for the `SimulateUntilPrice` query.
+#[cw_serde]
+pub struct CascadeResponse {
+    /// The amount of the offer asset that got sold before hitting the stop price.
+    pub executed_offer: Uint128,
+    /// The amount of the ask asset returned for the executed offer.
+    pub return_amount: Uint128,
+}
+
+/// The response for the `ImbalanceFeeSchedule` query.
+#[cw_serde]
+pub struct ImbalanceFeeResponse {
+    /// The sampled `(imbalance_ratio, fee_rate)` pairs, by increasing imbalance ratio.
+    pub points: Vec<(Decimal256, Decimal256)>,
+}
+
+/// The response for the `RevenueOverDistribution` query.
+#[cw_serde]
+pub struct RevenueDistributionResponse {
+    /// The total swap fee collected by the LPs over the trades.
+    pub total_swap_fee: Uint128,
+    /// The total protocol fee collected over the trades.
+    pub total_protocol_fee: Uint128,
+}
+
+/// The response for the `BandWidthForFees` query.
+#[cw_serde]
+pub struct BandWidthResponse {
+    /// The lowest external price that can't be arbitraged against the pool.
+    pub lower: Decimal256,
+    /// The highest external price that can't be arbitraged against the pool.
+    pub upper: Decimal256,
+}
+
+/// The response for the `MidPriceRational` query.
+#[cw_serde]
+pub struct RationalPriceResponse {
+    /// The numerator of the price, in quote asset.
+    pub numerator: Uint256,
+    /// The denominator of the price, in base asset.
+    pub denominator: Uint256,
+}
+
+/// The response for the `SwapAprContribution` query.
+#[cw_serde]
+pub struct AprContributionResponse {
+    /// The swap fee generated for the LPs by the trade, in the ask asset.
+    pub fee_generated: Uint128,
+    /// The generated fee in basis points of the pool's total value locked.
+    pub fee_as_bps_of_tvl: Decimal256,
+}
+
+/// The response for the `PriceSensitivity` query.
+#[cw_serde]
+pub struct SensitivityResponse {
+    /// How much the spot price drops for every unit of the base asset added to the pool.
+    pub price_per_unit: Decimal256,
+}
+
+/// The response for the `FeeTier` query.
+#[cw_serde]
+pub struct FeeTierResponse {
+    /// The fee tier of the pool.
+    pub tier: FeeTier,
+}
+
+/// The response for the `BalancedSlippage` query.
+#[cw_serde]
+pub struct BalancedSlippageResponse {
+    /// The spread of the forward swap, in the ask asset.
+    pub forward_spread: Uint128,
+    /// The spread of the reverse swap, in the offer asset.
+    pub reverse_spread: Uint128,
+}
+
+/// The response for the `PriceCurveCoefficients` query. Offering `around + d` returns
+/// approximately `c0 + c1 * d - c2 * d^2` of the ask asset.
+#[cw_serde]
+pub struct CurveCoefficientsResponse {
+    /// The return amount when offering `around`.
+    pub c0: Decimal256,
+    /// The marginal return per unit offered at `around`.
+    pub c1: Decimal256,
+    /// Half the rate at which the marginal return decreases, i.e. the curvature of the output.
+    pub c2: Decimal256,
+}
+
+/// The response for the `SimulationAmpRange` query.
+#[cw_serde]
+pub struct AmpRangeSimulationResponse {
+    /// The lowest return amount over the ramp window.
+    pub min_return: Uint128,
+    /// The highest return amount over the ramp window.
+    pub max_return: Uint128,
+}
+
+/// The response for the `LpPrice` query.
+#[cw_serde]
+pub struct LpPriceResponse {
+    /// The amount of the quote asset a unit of the LP token is worth.
+    pub price: Decimal256,
+}
+
+/// The response for the `CheapestForOutput` query.
+#[cw_serde]
+pub struct CheapestResponse {
+    /// The identifier of the pool requiring the least offer amount.
+    pub pool_identifier: String,
+    /// The offer amount required by that pool.
+    pub offer_amount: Uint128,
+}
+
+/// The response for the `EffectiveBidAsk` query. Both prices are in units of the quote asset per
+/// unit of the base asset, normalized by their decimals.
+#[cw_serde]
+pub struct BidAskResponse {
+    /// The effective price received when selling the base asset.
+    pub bid: Decimal256,
+    /// The effective price paid when buying the base asset.
+    pub ask: Decimal256,
+}
+
+/// The response for the `RoutingGraph` query.
+#[cw_serde]
+pub struct RoutingGraphResponse {
+    /// The `(denom_a, denom_b, pool_identifier)` edges of the graph. Each edge can be swapped
+    /// through in both directions.
+    pub edges: Vec<(String, String, String)>,
+}
+
+/// The response for the `SimulationExternalFee` query.
+#[cw_serde]
+pub struct ExternalFeeSimulationResponse {
+    /// The return amount of the swap, with no fees deducted.
+    pub return_amount: Uint128,
+    /// The fees of the swap, in the fee denom.
+    pub fee_in_fee_denom: Uint128,
+}
+
+/// The response for the `FeePercentages` query. All the components are percentages of the swap's
+/// fee-free output at the spot price, and add up to its total cost.
+#[cw_serde]
+pub struct FeePercentagesResponse {
+    /// The share of the output taken by the swap fee.
+    pub swap_pct: Decimal256,
+    /// The share of the output taken by the protocol fee.
+    pub protocol_pct: Decimal256,
+    /// The share of the output taken by the burn fee.
+    pub burn_pct: Decimal256,
+    /// The share of the output lost to the spread, and to any extra fees.
+    pub spread_pct: Decimal256,
+}
+
+/// The response for the `CanonicalPools` query.
+#[cw_serde]
+pub struct CanonicalPoolsResponse {
+    /// The `(denom_a, denom_b, pool_identifier)` of the deepest pool of each pair, with the denoms
+    /// of a pair in lexicographical order.
+    pub pools: Vec<(String, String, String)>,
+}
+
+/// The response for the `WhaleExitImpact` query.
+#[cw_serde]
+pub struct WhaleExitResponse {
+    /// The spot price of the pool's first asset, in its second asset, after the withdrawal.
+    pub price_after: Decimal256,
+    /// The value of an LP token after the withdrawal, relative to its value before it. One means
+    /// the remaining LPs are unaffected.
+    pub remaining_lp_value_change: Decimal256,
+}
+
+/// The response for the `FeeCaptureRatio` query.
+#[cw_serde]
+pub struct FeeCaptureResponse {
+    /// The charged fees divided by the theoretical ones. Below one means fees were lost to
+    /// rounding or leaked.
+    pub ratio: Decimal256,
+}
+
+/// The response for the `SharePriceHwm` query.
+#[cw_serde]
+pub struct SharePriceResponse {
+    /// The amount of the quote asset a unit of the LP token is currently worth.
+    pub current: Decimal256,
+    /// The highest amount of the quote asset a unit of the LP token has been worth.
+    pub high_water_mark: Decimal256,
+}
+
+/// The response for the `GlobalStats` query.
+#[cw_serde]
+pub struct GlobalStatsResponse {
+    /// The number of pools in the contract.
+    pub total_pools: u32,
+    /// The total value locked in the pools holding the quote asset, in its smallest unit.
+    pub total_tvl_in_quote: Uint256,
+    /// The denom the total value locked is expressed in.
+    pub quote_denom: String,
+}
+
+/// The response for the `ReverseSimulationPartial` query.
+#[cw_serde]
+pub struct PartialReverseResponse {
+    /// The amount of the ask asset the swap can deliver, up to the requested amount.
+    pub achievable_ask: Uint128,
+    /// The amount of the offer asset needed to get the `achievable_ask`.
+    pub offer_amount: Uint128,
+}
+
+/// The response for the `PriceConsistency` query.
+#[cw_serde]
+pub struct ConsistencyResponse {
+    /// The spread between the highest and the lowest spot price, in basis points of the lowest.
+    pub spread_bps: u64,
+    /// The number of pools the spot prices were collected from.
+    pub pools: u32,
+}
+
+/// The response for the `DepositRebalance` query.
+#[cw_serde]
+pub struct DepositRebalanceResponse {
+    /// The assets the LP tokens minted for the deposit are worth, i.e. the balanced deposit it's
+    /// equivalent to.
+    pub effective_assets: Vec<Coin>,
+    /// The value lost to the deposit's imbalance, charged on the assets deposited in excess.
+    pub imbalance_fee: Vec<Coin>,
+}
+
+/// The response for the `SimulationSafe` query.
+#[cw_serde]
+pub struct SafeSimulationResponse {
+    /// The simulated swap.
+    pub simulation: SimulationResponse,
+    /// Whether the swap returns more than the safe share of the ask asset reserve, meaning it
+    /// incurs an extreme slippage.
+    pub liquidity_warning: bool,
+}
+
+/// The response for the `LpForTargetValue` query.
+#[cw_serde]
+pub struct LpForValueResponse {
+    /// The amount of LP tokens the deposit would mint.
+    pub lp_amount: Uint128,
+    /// The deposit, in the same ratio as the pool reserves.
+    pub deposit: Vec<Coin>,
+}
+
+/// The response for the `SimulationBench` query.
+#[cw_serde]
+pub struct BenchSimulationResponse {
+    /// The amount of the ask asset returned by the swap, after fees.
+    pub return_amount: Uint128,
+    /// The amount of iterations the solver performed, zero for constant product pools.
+    pub iterations_used: u32,
+    /// Whether the solver converged within the iteration cap.
+    pub converged: bool,
+}
+
+/// The response for the `HistoricalReserves` query.
+#[cw_serde]
+pub struct HistoricalReservesResponse {
+    /// The pool reserves in the snapshot.
+    pub reserves: Vec<Coin>,
+    /// The block height the snapshot was taken at.
+    pub height: u64,
+}
+
+/// The response for the `PriceSummary` query.
+#[cw_serde]
+pub struct PriceSummaryResponse {
+    /// The fee-free marginal price of the base asset.
+    pub mid: Decimal256,
+    /// The price the base asset is sold at for the given size, after fees.
+    pub execution: Decimal256,
+    /// How far the execution price is from the mid price, in basis points of the mid price.
+    pub impact_bps: u64,
+}
+
+/// The response for the `AssetRoles` query.
+#[cw_serde]
+pub struct AssetRolesResponse {
+    /// The denom of the asset being priced.
+    pub base: String,
+    /// The denom the base asset is priced in.
+    pub quote: String,
+}
+
+/// The response for the `FractionalSimulation` query.
+#[cw_serde]
+pub struct FractionalSimulationResponse {
+    /// The cumulative return amount after each fraction is swapped.
+    pub results: Vec<Uint128>,
+}
+
+/// The response for the `EqualFeeSplit` query.
+#[cw_serde]
+pub struct EqualFeeSplitResponse {
+    /// The amount of the offer asset to swap through the first pool.
+    pub split_a: Uint128,
+    /// The amount of the offer asset to swap through the second pool.
+    pub split_b: Uint128,
+}
+
+/// The response for the `ReserveRatio` query.
+#[cw_serde]
+pub struct ReserveRatioResponse {
+    /// The numerator reserve divided by the denominator one, both normalized by their decimals.
+    pub ratio: Decimal256,
+}
+
+/// The response for the `SimulationRange` query.
+#[cw_serde]
+pub struct RangeSimulationResponse {
+    /// The simulated offer amounts, in increasing order, paired with their return amounts.
+    pub points: Vec<(Uint128, Uint128)>,
+}
+
+/// The response for the `BestPoolForPair` query.
+#[cw_serde]
+pub struct BestPoolResponse {
+    /// The identifier of the pool returning the most.
+    pub pool_identifier: String,
+    /// The amount of the ask denom the pool returns.
+    pub return_amount: Uint128,
+}
+
+/// The response for the `SlippageCoefficient` query.
+#[cw_serde]
+pub struct SlippageCoefficientResponse {
+    /// The relative price change per whole unit of the base denom, roughly the inverse of the
+    /// pool depth.
+    pub coefficient: Decimal256,
+}
+
+/// The response for the `ConfigMigrationCheck` query.
+#[cw_serde]
+pub struct MigrationCheckResponse {
+    /// Whether the proposed config raised no warnings.
+    pub safe: bool,
+    /// The potentially disruptive changes of the proposed config.
+    pub warnings: Vec<String>,
+}
+
+/// The response for the `SimulationWithReinvest` query.
+#[cw_serde]
+pub struct ReinvestSimulationResponse {
+    /// The simulated swap. The fees are charged as usual, but all of them stay in the pool.
+    pub simulation: SimulationResponse,
+    /// The relative growth of the LP token value, in the ask asset, from the reinvested fees.
+    pub share_value_increase: Decimal256,
+}
+
+/// The response for the `RealizedVolatility` query.
+#[cw_serde]
+pub struct VolatilityResponse {
+    /// The standard deviation of the log-returns between consecutive observations.
+    pub volatility: Decimal256,
+}
+
 /// Gets the total supply of the given liquidity asset
 pub fn get_total_share(deps: &Deps, liquidity_asset: String) -> StdResult<Uint128> {
     if is_factory_token(liquidity_asset.as_str()) {